@@ -15,7 +15,7 @@
 use std::sync::Arc;
 
 use arrow_arith::arity::binary;
-use arrow_array::{Int32Array, RecordBatch, StringArray};
+use arrow_array::{Decimal128Array, Int32Array, RecordBatch, StringArray};
 use arrow_schema::{DataType, Field, Schema};
 use arrow_udf::function;
 use arrow_udf_js::Runtime as JsRuntime;
@@ -128,6 +128,61 @@ def gcd(a: int, b: int) -> int:
     });
 }
 
+fn bench_eval_vectorized(c: &mut Criterion) {
+    #[function("checked_add(int, int) -> int")]
+    fn checked_add(a: i32, b: i32) -> Option<i32> {
+        a.checked_add(b)
+    }
+
+    // functions with nullable arguments are evaluated row by row
+    #[function("checked_add_rowwise(int, int) -> int")]
+    fn checked_add_rowwise(a: Option<i32>, b: Option<i32>) -> Option<i32> {
+        a?.checked_add(b?)
+    }
+
+    #[function("clamp(int, int, int) -> int")]
+    fn clamp(x: i32, min: i32, max: i32) -> i32 {
+        x.max(min).min(max)
+    }
+
+    #[function("clamp_rowwise(int, int, int) -> int")]
+    fn clamp_rowwise(x: Option<i32>, min: Option<i32>, max: Option<i32>) -> Option<i32> {
+        Some(x?.max(min?).min(max?))
+    }
+
+    let input = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+            Field::new("c", DataType::Int32, true),
+        ])),
+        vec![
+            Arc::new(Int32Array::from_iter(0..1024)),
+            Arc::new(Int32Array::from_iter(
+                (0..1024).map(|i| (i % 7 != 0).then_some(i)),
+            )),
+            Arc::new(Int32Array::from_iter(512..1536)),
+        ],
+    )
+    .unwrap();
+
+    c.bench_function("checked_add/rust", |bencher| {
+        bencher.iter(|| checked_add_int32_int32_int32_eval(&input).unwrap())
+    });
+
+    c.bench_function("checked_add/rust-rowwise", |bencher| {
+        bencher.iter(|| checked_add_rowwise_int32_int32_int32_eval(&input).unwrap())
+    });
+
+    c.bench_function("clamp/rust", |bencher| {
+        bencher.iter(|| clamp_int32_int32_int32_int32_eval(&input).unwrap())
+    });
+
+    c.bench_function("clamp/rust-rowwise", |bencher| {
+        bencher.iter(|| clamp_rowwise_int32_int32_int32_int32_eval(&input).unwrap())
+    });
+}
+
 fn bench_eval_range(c: &mut Criterion) {
     let js_code = r#"
     export function* range(n) {
@@ -229,6 +284,7 @@ def range1(n: int):
 
 fn bench_eval_decimal(c: &mut Criterion) {
     #[function("decimal(decimal) -> decimal")]
+    #[function("decimal(decimal(38,10)) -> decimal(38,10)")]
     fn decimal<T>(a: T) -> T {
         a
     }
@@ -254,6 +310,23 @@ def decimal_(a):
         bencher.iter(|| decimal_decimal_decimal_eval(&input).unwrap())
     });
 
+    c.bench_function("decimal/rust-decimal128", |bencher| {
+        let input = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new(
+                "a",
+                DataType::Decimal128(38, 10),
+                true,
+            )])),
+            vec![Arc::new(
+                Decimal128Array::from(vec![0; 1024])
+                    .with_precision_and_scale(38, 10)
+                    .unwrap(),
+            )],
+        )
+        .unwrap();
+        bencher.iter(|| decimal_decimal_38_10_decimal_38_10_eval(&input).unwrap())
+    });
+
     c.bench_function("decimal/js", |bencher| {
         let mut rt = JsRuntime::new().unwrap();
         rt.add_function(
@@ -357,6 +430,7 @@ def retract(state, value):
 criterion_group!(
     benches,
     bench_eval_gcd,
+    bench_eval_vectorized,
     bench_eval_range,
     bench_eval_decimal,
     bench_eval_sum
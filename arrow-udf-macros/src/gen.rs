@@ -20,6 +20,9 @@ use quote::{format_ident, quote};
 
 use super::*;
 
+/// The default number of rows evaluated concurrently by an async function.
+const DEFAULT_CONCURRENCY: usize = 16;
+
 impl FunctionAttr {
     /// Expands the wildcard in function arguments or return type.
     pub fn expand(&self) -> Vec<Self> {
@@ -49,15 +52,16 @@ impl FunctionAttr {
     /// The types of arguments and return value should not contain wildcard.
     pub fn generate_function_descriptor(&self, user_fn: &UserFunctionAttr) -> Result<TokenStream2> {
         let name = self.name.clone();
-        let variadic = matches!(self.args.last(), Some(t) if t == "...");
+        let variadic = self.is_variadic();
         let args = match variadic {
             true => &self.args[..self.args.len() - 1],
             false => &self.args[..],
         }
         .iter()
-        .map(|ty| field("", ty))
+        .zip(self.arg_names(&user_fn.arg_names))
+        .map(|(ty, name)| arg_field(name, ty))
         .collect_vec();
-        let ret = field(&self.name, &self.ret);
+        let ret = arg_field(&self.name, &self.ret);
 
         let eval_name = match &self.output {
             Some(output) => format_ident!("{}", output),
@@ -67,22 +71,61 @@ impl FunctionAttr {
         let ffi_name = format_ident!("{}_ffi", self.ident_name());
         let export_name = format!("arrowudf_{}", base64_encode(&self.normalize_signature()));
         let eval_function = self.generate_function(user_fn, &eval_name)?;
-        let kind = match self.is_table_function {
-            true => quote! { Table },
-            false => quote! { Scalar },
+        let type_infer_function = self.generate_type_infer()?;
+        let type_infer = match type_infer_function.is_some() {
+            true => {
+                let type_infer_name = self.type_infer_name();
+                quote! { Some(#type_infer_name) }
+            }
+            false => quote! { None },
+        };
+        let kind = match (self.is_table_function, user_fn.async_) {
+            (true, false) => quote! { Table },
+            (false, false) => quote! { Scalar },
+            (false, true) => quote! { AsyncScalar },
+            (true, true) => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "async table functions are not supported",
+                ))
+            }
         };
         let ffi_wrapper = match self.is_table_function {
             true => quote! { table_wrapper },
             false => quote! { scalar_wrapper },
         };
+        let volatility = self.volatility(user_fn);
+        let variadic_type = match self.variadic_type() {
+            Some(ty) => {
+                let field = arg_field("", ty);
+                quote! { Some(#field) }
+            }
+            None => quote! { None },
+        };
+        // null arguments are skipped unless they are `Option` or the function is vectorized
+        let strict = !self.vectorized
+            && user_fn.args_option.iter().all(|b| !b)
+            && !(self.variadic_type().is_some()
+                && user_fn.args_option_slice.get(args.len()) == Some(&true));
+        let description = description(user_fn);
+        let examples = &self.examples;
+        let defaults = &self.defaults;
+        // async functions can not be called through the synchronous FFI
+        let ffi_function = (!user_fn.async_).then(|| {
+            quote! {
+                #[export_name = #export_name]
+                unsafe extern "C" fn #ffi_name(ptr: *const u8, len: usize, out: *mut arrow_udf::ffi::CSlice) -> i32 {
+                    arrow_udf::ffi::#ffi_wrapper(#eval_name, ptr, len, out)
+                }
+            }
+        });
 
         Ok(quote! {
             #eval_function
 
-            #[export_name = #export_name]
-            unsafe extern "C" fn #ffi_name(ptr: *const u8, len: usize, out: *mut arrow_udf::ffi::CSlice) -> i32 {
-                arrow_udf::ffi::#ffi_wrapper(#eval_name, ptr, len, out)
-            }
+            #type_infer_function
+
+            #ffi_function
 
             #[cfg(feature = "global_registry")]
             #[::arrow_udf::codegen::linkme::distributed_slice(::arrow_udf::sig::SIGNATURES)]
@@ -95,53 +138,242 @@ impl FunctionAttr {
                     name: #name.into(),
                     arg_types: args.into(),
                     variadic: #variadic,
+                    variadic_type: #variadic_type,
+                    defaults: vec![#(#defaults.into()),*],
                     return_type: #ret,
+                    type_infer: #type_infer,
                     function: FunctionKind::#kind(#eval_name),
+                    volatility: #volatility,
+                    strict: #strict,
+                    description: #description,
+                    examples: vec![#(#examples.into()),*],
                 }
             }
         })
     }
 
+    /// Returns the name of each argument, specified by `arg_names` or taken from the Rust function.
+    ///
+    /// Arguments without a name have an empty name.
+    fn arg_names<'a>(&'a self, rust_names: &'a [String]) -> impl Iterator<Item = &'a str> {
+        let names = match self.arg_names.is_empty() {
+            true => rust_names,
+            false => &self.arg_names,
+        };
+        names
+            .iter()
+            .map(|s| s.as_str())
+            .chain(std::iter::repeat(""))
+    }
+
+    /// Returns the volatility of the function.
+    ///
+    /// Functions reading the context are stable unless specified as volatile.
+    fn volatility(&self, user_fn: &UserFunctionAttr) -> TokenStream2 {
+        if self.volatile {
+            quote! { ::arrow_udf::sig::Volatility::Volatile }
+        } else if self.stable || user_fn.context {
+            quote! { ::arrow_udf::sig::Volatility::Stable }
+        } else {
+            quote! { ::arrow_udf::sig::Volatility::Immutable }
+        }
+    }
+
+    /// Returns an expression of whether the function has an error column, or `None` if it never has.
+    ///
+    /// Functions returning `Result` always have an error column. Other functions have an error
+    /// column if converting their arguments or return value can fail.
+    fn error_column(&self, user_fn: &UserFunctionAttr) -> Option<TokenStream2> {
+        let types = self.args.iter().chain([&self.ret]);
+        let json_args = self.args.iter().zip(&user_fn.args_json);
+        if user_fn.has_error()
+            || types.clone().any(|ty| ty.contains("decimal("))
+            || json_args
+                .into_iter()
+                .any(|(ty, json)| *json && ty == "json")
+        {
+            return Some(quote! { true });
+        }
+        // whether struct types are fallible is known after expansion
+        let struct_types = types
+            .flat_map(|ty| ty.split("struct ").skip(1))
+            .map(|s| {
+                s.split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .next()
+                    .unwrap()
+            })
+            .unique()
+            .map(|s| format_ident!("{}", s))
+            .collect_vec();
+        if struct_types.is_empty() {
+            return None;
+        }
+        Some(quote! { (false #(|| <#struct_types as ::arrow_udf::types::StructType>::FALLIBLE)*) })
+    }
+
+    /// Returns the name of the generated type inference function.
+    fn type_infer_name(&self) -> Ident {
+        format_ident!("{}_type_infer", self.ident_name())
+    }
+
+    /// Generate a function to infer the return type from argument types,
+    /// if the function returns `any` or `anyarray`.
+    ///
+    /// If `type_infer` is not specified, the return type is inferred from the first `any` or
+    /// `anyarray` argument.
+    fn generate_type_infer(&self) -> Result<Option<TokenStream2>> {
+        if !types::is_polymorphic(&self.ret) {
+            return Ok(None);
+        }
+        let name = self.type_infer_name();
+        let body = match &self.type_infer {
+            Some(type_infer) => {
+                let type_infer: syn::Expr = syn::parse_str(type_infer)?;
+                quote! { (#type_infer)(args) }
+            }
+            None => {
+                let i = (self.args.iter())
+                    .position(|ty| types::is_polymorphic(ty))
+                    .expect("checked in parse");
+                match (self.ret.as_str(), self.args[i].as_str()) {
+                    ("any", "anyarray") => quote! {
+                        match &args[#i] {
+                            DataType::List(field) => Ok(field.data_type().clone()),
+                            t => Err(ArrowError::InvalidArgumentError(format!("expect list type, but got {t}"))),
+                        }
+                    },
+                    ("anyarray", "any") => {
+                        quote! { Ok(DataType::new_list(args[#i].clone(), true)) }
+                    }
+                    _ => quote! { Ok(args[#i].clone()) },
+                }
+            }
+        };
+        Ok(Some(quote! {
+            fn #name(args: &[::arrow_udf::codegen::arrow_schema::DataType])
+                -> ::arrow_udf::Result<::arrow_udf::codegen::arrow_schema::DataType>
+            {
+                #[allow(unused_imports)]
+                use ::arrow_udf::codegen::arrow_schema::{ArrowError, DataType};
+                #body
+            }
+        }))
+    }
+
     /// Generate a scalar or table function.
     fn generate_function(
         &self,
         user_fn: &UserFunctionAttr,
         eval_fn_name: &Ident,
     ) -> Result<TokenStream2> {
-        let fn_with_visibility = if let Some(visiblity) = &self.visibility {
-            // handle the scope of the visibility by parsing the visibility string
-            match syn::parse_str::<syn::Visibility>(visiblity)? {
-                syn::Visibility::Public(token) => quote! { #token fn },
-                syn::Visibility::Restricted(vis_restricted) => quote! { #vis_restricted fn },
-                syn::Visibility::Inherited => quote! { fn },
-            }
-        } else {
-            quote! { fn }
-        };
+        let visibility = self.visibility()?;
+        let fn_with_visibility = quote! { #visibility fn };
+        if self.vectorized && (self.is_table_function || self.batch_fn.is_some()) {
+            return Err(Error::new(
+                Span::call_site(),
+                "`vectorized` can not be used with table functions or `batch_fn`",
+            ));
+        }
 
-        let variadic = matches!(self.args.last(), Some(t) if t == "...");
+        let variadic = self.is_variadic();
         let num_args = self.args.len() - if variadic { 1 } else { 0 };
+        let variadic_type = self.variadic_type();
+        match variadic_type {
+            Some(ty) if types::is_converted(ty) || types::is_polymorphic(ty) => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!("unsupported variadic type: {ty}"),
+                ));
+            }
+            None if variadic && !self.vectorized => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "variadic arguments without a type are only supported by vectorized functions. use `type...` to declare their type",
+                ));
+            }
+            _ => {}
+        }
+        // typed variadic arguments are passed as `&[T]`, or `&[Option<T>]` to receive null values
+        let variadic_option = variadic_type.is_some()
+            && user_fn
+                .args_option_slice
+                .get(num_args)
+                .copied()
+                .unwrap_or(false);
         let user_fn_name = format_ident!("{}", user_fn.name);
 
         let children_indices = (0..num_args).collect_vec();
-
-        /// Return a list of identifiers with the given prefix and indices.
-        fn idents(prefix: &str, indices: &[usize]) -> Vec<Ident> {
-            indices
-                .iter()
-                .map(|i| format_ident!("{prefix}{i}"))
-                .collect()
-        }
         let inputs = idents("i", &children_indices);
         let arrays = idents("a", &children_indices);
         let arg_arrays = children_indices
             .iter()
-            .map(|i| format_ident!("{}", types::array_type(&self.args[*i])));
+            .map(|i| format_ident!("{}", types::array_type(&self.args[*i])))
+            .collect_vec();
         let ret_array_type = format_ident!("{}", types::array_type(&self.ret));
-        let ret_data_type = field(&self.name, &self.ret);
+        let polymorphic = types::is_polymorphic(&self.ret);
+        let ret_data_type = match polymorphic {
+            true => {
+                let name = &self.name;
+                quote! { Field::new(#name, return_type.clone(), true) }
+            }
+            false => arg_field(&self.name, &self.ret),
+        };
+        // the schema is static unless the return type is inferred from the input
+        let define_schema = |fields: TokenStream2| match polymorphic {
+            true => quote! {
+                let schema: SchemaRef = Arc::new(Schema::new(#fields));
+            },
+            false => quote! {
+                static SCHEMA: once_cell::sync::Lazy<SchemaRef> = once_cell::sync::Lazy::new(|| {
+                    Arc::new(Schema::new(#fields))
+                });
+                let schema = SCHEMA.clone();
+            },
+        };
+        // `AnyBuilder` and `AnyArrayBuilder` check the types of values when finishing
+        // struct types are encoded from the struct array, e.g. enums to unions
+        let ret_struct_type = struct_type(&self.ret);
+        let finish_builder = match (polymorphic, &ret_struct_type) {
+            (true, _) => quote! { builder.finish()? },
+            (false, Some(s)) => {
+                quote! { <#s as ::arrow_udf::types::StructType>::encode(builder.finish())? }
+            }
+            (false, None) => quote! { Arc::new(builder.finish()) },
+        };
+        let mut read_inputs = arrays
+            .iter()
+            .zip(&self.args)
+            .map(|(array, ty)| gen_read_value(array, ty))
+            .collect_vec();
+        // inputs and whether they are passed as `Option`, including the variadic row
+        let mut all_inputs = inputs.clone();
+        let mut inputs_option = user_fn
+            .args_option
+            .iter()
+            .take(num_args)
+            .copied()
+            .collect_vec();
+        if let Some(ty) = variadic_type {
+            let read_value = gen_read_value(&format_ident!("va"), ty);
+            let transform = transform_input(&format_ident!("v"), ty);
+            let read_value = match transform.to_string() == "v" {
+                true => read_value,
+                false => quote! { #read_value.map(|v| #transform) },
+            };
+            // a null in the variadic arguments makes the row null unless `&[Option<T>]`
+            let collection = match variadic_option {
+                true => quote! { Vec<_> },
+                false => quote! { Option<Vec<_>> },
+            };
+            read_inputs.push(quote! {
+                variadic_arrays.iter().map(|va| #read_value).collect::<#collection>()
+            });
+            all_inputs.push(format_ident!("variadic_row"));
+            inputs_option.push(variadic_option);
+        }
 
-        let variadic_args = variadic.then(|| quote! { variadic_row, });
-        let context = user_fn.context.then(|| quote! { &self.context, });
+        let variadic_args = variadic_type.is_some().then(|| quote! { &variadic_row, });
+        let context = user_fn.context.then(|| quote! { context, });
         let writer = user_fn.write.then(|| quote! { builder, });
         let await_ = user_fn.async_.then(|| quote! { .await });
         // transform inputs for array arguments
@@ -149,7 +381,41 @@ impl FunctionAttr {
         let transformed_inputs = inputs
             .iter()
             .zip(&self.args)
-            .map(|(input, ty)| transform_input(input, ty));
+            .zip(user_fn.args_json.iter().chain(std::iter::repeat(&false)))
+            .map(|((input, ty), json)| transform_arg(input, ty, *json));
+        // convert inputs that may fail before calling the function
+        // e.g. `decimal(38, 10)` values may not fit in `rust_decimal::Decimal`
+        let (converted_inputs, conversions): (Vec<_>, Vec<_>) = itertools::multizip((
+            &inputs,
+            &self.args,
+            user_fn.args_option.iter().chain(std::iter::repeat(&false)),
+            user_fn.args_json.iter().chain(std::iter::repeat(&false)),
+        ))
+        .filter_map(|(input, ty, option, json)| {
+            Some((input, convert_arg(input, ty, *option, *json)?))
+        })
+        .unzip();
+        // the error of the first failed conversion
+        let conversion_error = (0..conversions.len()).map(|i| {
+            let patterns = (0..conversions.len()).map(|j| match i == j {
+                true => quote! { Err(e) },
+                false => quote! { _ },
+            });
+            quote! { (#(#patterns,)*) }
+        });
+        let conversion_error = conversion_error.collect_vec();
+        let convert_inputs = |output: TokenStream2, on_error: TokenStream2| {
+            if conversions.is_empty() {
+                return output;
+            }
+            quote! {
+                match (#(#conversions,)*) {
+                    (#(Ok(#converted_inputs),)*) => #output,
+                    #(#conversion_error)|* => #on_error,
+                }
+            }
+        };
+        let append_null = gen_append_null(&self.ret);
         // call the user defined function
         let mut output = quote! { #user_fn_name(
             #(#transformed_inputs,)*
@@ -157,134 +423,202 @@ impl FunctionAttr {
             #context
             #writer
         ) #await_ };
+        let call = output.clone();
         // handle error if the function returns `Result`
         // wrap a `Some` if the function doesn't return `Option`
+        let handle_scalar_output = |output: TokenStream2| match user_fn.return_type_kind {
+            ReturnTypeKind::T => quote! { Some(#output) },
+            ReturnTypeKind::Option => output,
+            ReturnTypeKind::Result => {
+                quote! { match #output {
+                    Ok(x)  => Some(x),
+                    Err(e) => { (&ErrorRef(&e)).append_to(&mut error_builder, i)?; None }
+                } }
+            }
+            ReturnTypeKind::ResultOption => {
+                quote! { match #output {
+                    Ok(x)  => x,
+                    Err(e) => { (&ErrorRef(&e)).append_to(&mut error_builder, i)?; None }
+                } }
+            }
+        };
+        // table functions report errors in the error column,
+        // or yield the error and end the iteration if the policy is `FailFast`.
+        let append_table_error = quote! {
+            if let Err(e) = (&ErrorRef(&e)).append_to(&mut error_builder, i) {
+                yield_!(Err(e));
+                return;
+            }
+        };
+        let table_error = quote! {{
+            index_builder.append_value(i as i32);
+            #append_null;
+            #append_table_error
+            None
+        }};
         output = if self.is_table_function {
-            match user_fn.return_type_kind {
+            let output = match user_fn.return_type_kind {
                 ReturnTypeKind::T => quote! { Some(#output) },
                 ReturnTypeKind::Option => output,
                 ReturnTypeKind::Result => {
                     quote! { match #output {
                         Ok(x) => Some(x),
-                        Err(e) => {
-                            index_builder.append_value(i as i32);
-                            builder.append_null();
-                            error_builder.append_value(e.to_string());
-                            None
-                        }
+                        Err(e) => #table_error
                     } }
                 }
                 ReturnTypeKind::ResultOption => {
                     quote! { match #output {
                         Ok(x) => x,
-                        Err(e) => {
-                            index_builder.append_value(i as i32);
-                            builder.append_null();
-                            error_builder.append_value(e.to_string());
-                            None
-                        }
+                        Err(e) => #table_error
                     } }
                 }
-            }
+            };
+            convert_inputs(output, table_error.clone())
         } else {
-            match user_fn.return_type_kind {
-                ReturnTypeKind::T => quote! { Some(#output) },
-                ReturnTypeKind::Option => output,
-                ReturnTypeKind::Result => {
-                    quote! { match #output {
-                        Ok(x)  => { error_builder.append_null(); Some(x) },
-                        Err(e) => { error_builder.append_value(e.to_string()); None }
-                    } }
-                }
-                ReturnTypeKind::ResultOption => {
-                    quote! { match #output {
-                        Ok(x)  => { error_builder.append_null(); x },
-                        Err(e) => { error_builder.append_value(e.to_string()); None }
-                    } }
-                }
-            }
+            convert_inputs(
+                handle_scalar_output(output),
+                quote! {{ (&ErrorRef(&e)).append_to(&mut error_builder, i)?; None }},
+            )
         };
         // if user function accepts non-option arguments, we assume the function
         // returns null on null input, so we need to unwrap the inputs before calling.
-        let some_inputs = inputs
+        let some_inputs = all_inputs
             .iter()
-            .zip(user_fn.args_option.iter())
+            .zip(inputs_option.iter())
             .map(|(input, opt)| {
                 if *opt {
                     quote! { #input }
                 } else {
                     quote! { Some(#input) }
                 }
-            });
-        if !self.is_table_function && user_fn.has_error() {
-            output = quote! {
-                match (#(#inputs,)*) {
-                    (#(#some_inputs,)*) => #output,
-                    _ => { error_builder.append_null(); None },
+            })
+            .collect_vec();
+        output = quote! {
+            match (#(#all_inputs,)*) {
+                (#(#some_inputs,)*) => #output,
+                _ => None,
+            }
+        };
+        // rows without error are null in the error column
+        let error_column = match self.batch_fn.is_some() || self.vectorized {
+            true => user_fn.has_error().then(|| quote! { true }),
+            false => self.error_column(user_fn),
+        };
+        let error_append_null = error_column.is_some().then(|| {
+            quote! {
+                if error_builder.len() == i {
+                    error_builder.append_null();
                 }
-            };
-        } else {
-            output = quote! {
-                match (#(#inputs,)*) {
-                    (#(#some_inputs,)*) => #output,
-                    _ => None,
+            }
+        });
+        // append `v` to the builder, reporting conversion errors in the error column
+        let append_output_value = |append: TokenStream2| match error_column.is_some() {
+            true => quote! {
+                if let Err(e) = #append {
+                    (&ErrorRef(&e)).append_to(&mut error_builder, i)?;
                 }
-            };
-        }
+            },
+            false => quote! { #append?; },
+        };
 
+        // the output schema of table functions, checked before creating the generator
+        let mut define_table_schema = None;
         let eval = if self.is_table_function {
-            let builder = builder(&self.ret);
-            let append_output = gen_append(&self.ret);
-            let error_append_null = user_fn
-                .has_error()
-                .then(|| quote! { error_builder.append_null(); });
+            let builder = builder(&self.ret, &quote! { input.num_rows() });
+            let append_output = match types::table_columns(&self.ret) {
+                Some(columns) => gen_append_table(&columns, &user_fn.iterator_item_tuple_option)?,
+                None => gen_append(&self.ret),
+            };
+            let append_output = match error_column.is_some() {
+                true => quote! {
+                    if let Err(e) = #append_output {
+                        #append_table_error
+                    }
+                    if error_builder.len() < index_builder.len() {
+                        error_builder.append_null();
+                    }
+                },
+                // values are always converted without an error column
+                false => quote! {
+                    if let Err(e) = #append_output {
+                        yield_!(Err(e));
+                        return;
+                    }
+                },
+            };
             let element = match user_fn.iterator_item_kind.clone().unwrap() {
-                ReturnTypeKind::T => quote! {{ #error_append_null; Some(v) }},
-                ReturnTypeKind::Option => quote! {{ #error_append_null; v }},
+                ReturnTypeKind::T => quote! { Some(v) },
+                ReturnTypeKind::Option => quote! { v },
                 ReturnTypeKind::Result => {
                     quote! { match v {
-                        Ok(x) => { error_builder.append_null(); Some(x) },
-                        Err(e) => { error_builder.append_value(e.to_string()); None }
+                        Ok(x) => Some(x),
+                        Err(e) => { #append_table_error None }
                     } }
                 }
                 ReturnTypeKind::ResultOption => {
                     quote! { match v {
-                        Ok(x) => { error_builder.append_null(); x },
-                        Err(e) => { error_builder.append_value(e.to_string()); None }
+                        Ok(x) => x,
+                        Err(e) => { #append_table_error None }
                     } }
                 }
             };
 
-            let error_field = user_fn.has_error().then(|| {
-                quote! { Field::new("error", DataType::Utf8, true), }
+            let name = &self.name;
+            let error_field = error_column.as_ref().map(|error_column| {
+                quote! { fields.extend(#error_column.then(|| error_field(#name))); }
             });
-            let let_error_builder = user_fn.has_error().then(|| {
-                quote! { let mut error_builder = StringBuilder::with_capacity(input.num_rows(), input.num_rows() * 16); }
+            let let_error_builder = error_column.is_some().then(|| {
+                quote! { let mut error_builder = ErrorBuilder::with_capacity(input.num_rows()).with_policy(context.error_policy()); }
             });
-            let error_array = user_fn.has_error().then(|| {
-                quote! { Arc::new(error_builder.finish()) }
+            let error_array = error_column.as_ref().map(|error_column| {
+                quote! { columns.extend(#error_column.then(|| error_builder.finish())); }
             });
+            let context = user_fn.context.then(|| quote! { let context = &*context; });
+            let value_array = match (polymorphic, &ret_struct_type) {
+                (true, _) => quote! { builder.finish() },
+                (false, Some(s)) => quote! {
+                    <#s as ::arrow_udf::types::StructType>::encode(builder.finish())
+                },
+                (false, None) => quote! { Ok::<ArrayRef, Error>(Arc::new(builder.finish())) },
+            };
+            // struct values are flattened into one column per field
+            // an error ends the iteration after it is yielded
             let yield_batch = quote! {
                 let index_array = Arc::new(index_builder.finish());
-                let value_array = Arc::new(builder.finish());
-                yield_!(RecordBatch::try_new(SCHEMA.clone(), vec![index_array, value_array, #error_array]).unwrap());
+                let value_array = match #value_array {
+                    Ok(array) => array,
+                    Err(e) => {
+                        yield_!(Err(e));
+                        return;
+                    }
+                };
+                let mut columns: Vec<ArrayRef> = vec![index_array];
+                columns.extend(::arrow_udf::codegen::flatten_struct_array(value_array));
+                #error_array
+                yield_!(RecordBatch::try_new(schema.clone(), columns));
             };
+            define_table_schema = Some(quote! {
+                let schema: ::arrow_udf::codegen::arrow_schema::SchemaRef = {
+                    use ::std::sync::Arc;
+                    use ::arrow_udf::codegen::arrow_schema::{self, Schema, Field, DataType, IntervalUnit, TimeUnit};
+                    use ::arrow_udf::codegen::error_field;
+                    let mut fields = vec![Field::new("row", DataType::Int32, true)];
+                    fields.extend(::arrow_udf::codegen::flatten_struct_field(#ret_data_type)?);
+                    #error_field
+                    Arc::new(Schema::new(fields))
+                };
+            });
             quote! {{
-                static SCHEMA: once_cell::sync::Lazy<SchemaRef> = once_cell::sync::Lazy::new(|| {
-                    Arc::new(Schema::new(vec![
-                        Field::new("row", DataType::Int32, true),
-                        #ret_data_type,
-                        #error_field
-                    ]))
-                });
+                #context
                 let mut index_builder = Int32Builder::with_capacity(input.num_rows());
                 let mut builder = #builder;
                 let builder = &mut builder;
                 #let_error_builder
                 for i in 0..input.num_rows() {
-                    #(let #inputs = unsafe { (!#arrays.is_null(i)).then(|| #arrays.value_unchecked(i)) };)*
-                    let Some(iter) = (#output) else {
-                        continue;
+                    #(let #all_inputs = #read_inputs;)*
+                    let iter = match #output {
+                        Some(iter) => iter,
+                        None => continue,
                     };
                     for v in iter {
                         index_builder.append_value(i as i32);
@@ -312,33 +646,217 @@ impl FunctionAttr {
                 let c = #fn_name(#(#arrays),*);
                 let array = Arc::new(c);
             }
-        } else if types::is_primitive(&self.ret)
-            && self.args.iter().all(|ty| types::is_primitive(ty))
-            && self.args.len() <= 2
-            && user_fn.is_pure()
-            && !variadic
+        } else if self.vectorized {
+            if user_fn.write {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`&mut Write` is not supported for vectorized functions",
+                ));
+            }
+            // user function on whole arrays.
+            // variadic arguments are passed as `&[ArrayRef]`, or `&[&XxxArray]` if typed.
+            let variadic_arg = match variadic_type {
+                Some(_) => Some(quote! { variadic_arrays, }),
+                None => variadic.then(|| quote! { &input.columns()[#num_args..], }),
+            };
+            let context_arg = user_fn.context.then(|| quote! { context, });
+            let let_context = user_fn.context.then(|| quote! { let context = &*context; });
+            let wrap_array = |array: TokenStream2| match polymorphic {
+                true => array,
+                false => quote! { Arc::new(#array) },
+            };
+            let output = match user_fn.return_type_kind {
+                ReturnTypeKind::T => wrap_array(quote! { output }),
+                ReturnTypeKind::Result => {
+                    // an error fails all rows
+                    let null_array = match polymorphic {
+                        true => quote! { new_null_array(&return_type, input.num_rows()) },
+                        false => quote! {{
+                            let field = #ret_data_type;
+                            let array = new_null_array(field.data_type(), input.num_rows());
+                            Arc::new(#ret_array_type::from(array.to_data()))
+                        }},
+                    };
+                    let ok_array = wrap_array(quote! { array });
+                    quote! {
+                        match output {
+                            Ok(array) => {
+                                for _ in 0..input.num_rows() {
+                                    error_builder.append_null();
+                                }
+                                #ok_array
+                            }
+                            Err(e) => {
+                                for i in 0..input.num_rows() {
+                                    (&ErrorRef(&e)).append_to(&mut error_builder, i)?;
+                                }
+                                #null_array
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    return Err(Error::new(
+                        user_fn.return_type_span,
+                        "vectorized functions must return an array or `Result` of an array",
+                    ))
+                }
+            };
+            quote! {
+                #let_context
+                let output = #user_fn_name(#(#arrays,)* #variadic_arg #context_arg) #await_;
+                let array = #output;
+                if array.len() != input.num_rows() {
+                    return Err(Error::InvalidArgumentError(format!(
+                        "expect {} rows from the vectorized function, but got {}",
+                        input.num_rows(),
+                        array.len(),
+                    )));
+                }
+            }
+        } else if user_fn.async_ {
+            if user_fn.write {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`&mut Write` is not supported for async functions",
+                ));
+            }
+            // evaluate rows concurrently and collect the results in order,
+            // then append them to the builder sequentially.
+            let concurrency = match &self.concurrency {
+                Some(n) => n.parse::<usize>().map_err(|_| {
+                    Error::new(
+                        Span::call_site(),
+                        "`concurrency` must be a positive integer",
+                    )
+                })?,
+                None => DEFAULT_CONCURRENCY,
+            };
+            if concurrency == 0 {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`concurrency` must be a positive integer",
+                ));
+            }
+            let builder = builder(&self.ret, &quote! { input.num_rows() });
+            let append = append_output_value(gen_append(&self.ret));
+            let handled = handle_scalar_output(quote! { output });
+            let context = user_fn.context.then(|| quote! { let context = &*context; });
+            // conversion errors are returned from the async block and reported in order
+            let (call, handle_output) = match conversions.is_empty() {
+                true => (call, quote! { Some(output) => #handled, }),
+                false => (
+                    convert_inputs(quote! { Ok(#call) }, quote! { Err(e) }),
+                    quote! {
+                        Some(Ok(output)) => #handled,
+                        Some(Err(e)) => { (&ErrorRef(&e)).append_to(&mut error_builder, i)?; None }
+                    },
+                ),
+            };
+            let for_each_output = match error_column.is_some() {
+                true => quote! { for (i, output) in outputs.into_iter().enumerate() },
+                false => quote! { for output in outputs },
+            };
+            quote! {
+                use ::arrow_udf::codegen::futures_util::stream::{self, StreamExt};
+
+                #context
+
+                // the concurrency of the context overrides the attribute
+                let concurrency = context.concurrency().unwrap_or(#concurrency);
+                let outputs: Vec<_> = stream::iter(0..input.num_rows())
+                    .map(move |i| async move {
+                        #(let #all_inputs = #read_inputs;)*
+                        match (#(#all_inputs,)*) {
+                            (#(#some_inputs,)*) => Some(#call),
+                            _ => None,
+                        }
+                    })
+                    .buffered(concurrency)
+                    .collect()
+                    .await;
+                let mut builder = #builder;
+                let builder = &mut builder;
+                #for_each_output {
+                    let v = match output {
+                        #handle_output
+                        None => None,
+                    };
+                    #append
+                    #error_append_null
+                }
+                let array = #finish_builder;
+            }
+        } else if !variadic
+            && !user_fn.async_
+            && !user_fn.write
+            && !user_fn.context
+            && user_fn.args_option.iter().all(|b| !b)
+            && types::is_vectorizable(&self.ret)
+            && self.args.iter().all(|ty| types::is_vectorizable(ty))
         {
-            // SIMD optimization for primitive types
-            match self.args.len() {
-                0 => quote! {
-                    let c = #ret_array_type::from_iter_values(
-                        std::iter::repeat_with(|| #user_fn_name()).take(input.num_rows())
-                    );
-                    let array = Arc::new(c);
-                },
-                1 => quote! {
-                    let c: #ret_array_type = arrow_arith::arity::unary(a0, #user_fn_name);
-                    let array = Arc::new(c);
-                },
-                2 => quote! {
-                    let c: #ret_array_type = arrow_arith::arity::binary(a0, a1, #user_fn_name)?;
-                    let array = Arc::new(c);
-                },
-                n => todo!("SIMD optimization for {n} arguments"),
+            // vectorized evaluation for primitive types.
+            // the null bitmap is the bitwise AND of the validity of all inputs.
+            let read_values = quote! {
+                #(let #inputs = unsafe { #arrays.value_unchecked(i) };)*
+            };
+            let to_native = gen_to_native(&self.ret);
+            let values = if user_fn.return_type_kind == ReturnTypeKind::T
+                && self.args.len() <= 2
+                && types::is_primitive(&self.ret)
+                && self.args.iter().all(|ty| types::is_primitive(ty))
+            {
+                // pure functions on at most 2 primitive arguments are also evaluated on null
+                // slots, so that the loop can be auto-vectorized by the compiler.
+                quote! {
+                    let values = (0..input.num_rows()).map(|i| {
+                        #read_values
+                        let v = #call;
+                        #to_native
+                    });
+                    let values = values.collect();
+                }
+            } else {
+                // other functions are only evaluated on valid slots,
+                // and the validity of outputs is collected in a separate bitmap.
+                let output = handle_scalar_output(call.clone());
+                quote! {
+                    let mut validity = BooleanBufferBuilder::new(input.num_rows());
+                    let mut values = Vec::with_capacity(input.num_rows());
+                    for i in 0..input.num_rows() {
+                        let v = if nulls.as_ref().is_some_and(|n| n.is_null(i)) {
+                            None
+                        } else {
+                            #read_values
+                            #output
+                        };
+                        #error_append_null
+                        validity.append(v.is_some());
+                        values.push(match v {
+                            Some(v) => #to_native,
+                            None => Default::default(),
+                        });
+                    }
+                    let values = values.into();
+                    let nulls = Some(NullBuffer::new(validity.finish()));
+                }
+            };
+            let values_type = match self.ret.as_str() {
+                "boolean" => quote! { BooleanBuffer },
+                _ => quote! { ScalarBuffer<_> },
+            };
+            quote! {
+                use ::arrow_udf::codegen::arrow_buffer::{BooleanBuffer, BooleanBufferBuilder, NullBuffer, ScalarBuffer};
+
+                let nulls: Option<NullBuffer> = None;
+                #(let nulls = NullBuffer::union(nulls.as_ref(), #arrays.nulls());)*
+                #values
+                let values: #values_type = values;
+                let array = Arc::new(#ret_array_type::new(values, nulls));
             }
         } else {
             // no optimization
-            let builder = builder(&self.ret);
+            let builder = builder(&self.ret, &quote! { input.num_rows() });
             // append the `output` to the `builder`
             let append_output = if user_fn.write {
                 if !matches!(
@@ -358,20 +876,23 @@ impl FunctionAttr {
                     }
                 }}
             } else {
-                let append = gen_append(&self.ret);
+                let append = append_output_value(gen_append(&self.ret));
                 quote! {{
                     let v = #output;
                     #append
                 }}
             };
+            let context = user_fn.context.then(|| quote! { let context = &*context; });
             quote! {
+                #context
                 let mut builder = #builder;
                 let builder = &mut builder;
                 for i in 0..input.num_rows() {
-                    #(let #inputs = unsafe { (!#arrays.is_null(i)).then(|| #arrays.value_unchecked(i)) };)*
+                    #(let #all_inputs = #read_inputs;)*
                     #append_output
+                    #error_append_null
                 }
-                let array = Arc::new(builder.finish());
+                let array = #finish_builder;
             }
         };
 
@@ -380,36 +901,168 @@ impl FunctionAttr {
                 #eval
             }
         } else {
-            let error_field = user_fn.has_error().then(|| {
-                quote! { Field::new("error", DataType::Utf8, true), }
+            let name = &self.name;
+            let error_field = error_column.as_ref().map(|error_column| {
+                quote! { fields.extend(#error_column.then(|| error_field(#name))); }
             });
-            let let_error_builder = user_fn.has_error().then(|| {
-                quote! { let mut error_builder = StringBuilder::with_capacity(input.num_rows(), input.num_rows() * 16); }
+            let let_error_builder = error_column.is_some().then(|| {
+                quote! { let mut error_builder = ErrorBuilder::with_capacity(input.num_rows()).with_policy(context.error_policy()); }
             });
-            let error_array = user_fn.has_error().then(|| {
-                quote! { Arc::new(error_builder.finish()) }
+            let error_array = error_column.as_ref().map(|error_column| {
+                quote! { columns.extend(#error_column.then(|| error_builder.finish())); }
             });
-            quote! {
-                #let_error_builder
-                #eval
+            if self.ret == "timestamptz" {
+                // preserve the time zone of the first `timestamptz` argument
+                let timezone = match self.args.iter().position(|ty| ty == "timestamptz") {
+                    Some(i) => {
+                        let array = &arrays[i];
+                        quote! { #array.timezone().unwrap_or("UTC") }
+                    }
+                    None => quote! { "UTC" },
+                };
+                quote! {
+                    #let_error_builder
+                    #eval
 
-                static SCHEMA: once_cell::sync::Lazy<SchemaRef> = once_cell::sync::Lazy::new(|| {
-                    Arc::new(Schema::new(vec![#ret_data_type, #error_field]))
-                });
-                Ok(RecordBatch::try_new(SCHEMA.clone(), vec![array, #error_array]).unwrap())
+                    let array = Arc::new((*array).clone().with_timezone(#timezone));
+                    let mut fields = vec![#ret_data_type.with_data_type(array.data_type().clone())];
+                    #error_field
+                    let mut columns: Vec<ArrayRef> = vec![array];
+                    #error_array
+                    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap())
+                }
+            } else {
+                let define_schema = define_schema(quote! {{
+                    let mut fields = vec![#ret_data_type];
+                    #error_field
+                    fields
+                }});
+                quote! {
+                    #let_error_builder
+                    #eval
+
+                    #define_schema
+                    let mut columns: Vec<ArrayRef> = vec![array];
+                    #error_array
+                    Ok(RecordBatch::try_new(schema, columns).unwrap())
+                }
             }
         };
 
-        // downcast input arrays
-        let downcast_arrays = quote! {
-            #(
-                let #arrays: &#arg_arrays = input.column(#children_indices).as_any().downcast_ref()
-                    .ok_or_else(|| ::arrow_udf::codegen::arrow_schema::ArrowError::CastError(
-                        format!("expect {} for the {}-th argument", stringify!(#arg_arrays), #children_indices)
-                    ))?;
-            )*
+        // cast and downcast input arrays
+        let columns = idents("c", &children_indices);
+        let cast_columns = gen_cast_columns(&self.args[..num_args]);
+        // `any` arguments are passed as `ArrayRef` without downcasting
+        let downcast_arrays = itertools::multizip((&arrays, &arg_arrays, &columns, &self.args))
+            .enumerate()
+            .map(|(i, (array, arg_array, column, ty))| match ty.as_str() {
+                "any" => quote! { let #array = &#column; },
+                _ => quote! {
+                    let #array: &#arg_array = #column.as_any().downcast_ref()
+                        .ok_or_else(|| ::arrow_udf::codegen::arrow_schema::ArrowError::CastError(
+                            format!("expect {} for the {}-th argument", stringify!(#arg_array), #i)
+                        ))?;
+                },
+            })
+            .collect::<TokenStream2>();
+        let downcast_arrays_unchecked = itertools::multizip((
+            &arrays,
+            &arg_arrays,
+            &columns,
+            &self.args,
+        ))
+        .map(|(array, arg_array, column, ty)| match ty.as_str() {
+            "any" => quote! { let #array = &#column; },
+            _ => quote! { let #array: &#arg_array = #column.as_any().downcast_ref().unwrap(); },
+        })
+        .collect::<TokenStream2>();
+
+        // typed variadic arguments are cast and downcast to `variadic_arrays: &[&XxxArray]`
+        let (cast_columns, downcast_arrays, downcast_arrays_unchecked) = match variadic_type {
+            Some(ty) => {
+                let cast_column = gen_cast_column(quote! { column }, ty);
+                let array_type = format_ident!("{}", types::array_type(ty));
+                (
+                    quote! {
+                        #cast_columns
+                        let variadic_columns = input.columns()[#num_args..]
+                            .iter()
+                            .map(|column| -> ::arrow_udf::Result<_> { Ok(#cast_column) })
+                            .collect::<::arrow_udf::Result<Vec<_>>>()?;
+                    },
+                    quote! {
+                        #downcast_arrays
+                        let variadic_arrays = variadic_columns
+                            .iter()
+                            .enumerate()
+                            .map(|(i, column)| column.as_any().downcast_ref::<#array_type>()
+                                .ok_or_else(|| ::arrow_udf::codegen::arrow_schema::ArrowError::CastError(
+                                    format!("expect {} for the {}-th argument", stringify!(#array_type), #num_args + i)
+                                )))
+                            .collect::<::arrow_udf::Result<Vec<_>>>()?;
+                        let variadic_arrays = variadic_arrays.as_slice();
+                    },
+                    quote! {
+                        #downcast_arrays_unchecked
+                        let variadic_arrays = variadic_columns
+                            .iter()
+                            .map(|column| column.as_any().downcast_ref::<#array_type>().unwrap())
+                            .collect::<Vec<_>>();
+                        let variadic_arrays = variadic_arrays.as_slice();
+                    },
+                )
+            }
+            None => (cast_columns, downcast_arrays, downcast_arrays_unchecked),
         };
 
+        // infer the return type from the input types
+        let infer_return_type = polymorphic.then(|| {
+            let type_infer = self.type_infer_name();
+            quote! {
+                let return_type = #type_infer(&[#(#columns.data_type().clone()),*])?;
+            }
+        });
+
+        // evaluate pure unary functions once per distinct value of a dictionary-encoded input,
+        // and return a dictionary-encoded output.
+        let eval_dictionary = (num_args == 1
+            && !variadic
+            && !self.is_table_function
+            && self.batch_fn.is_none()
+            && !self.vectorized
+            && !self.volatile
+            && error_column.is_none()
+            && user_fn.is_pure())
+        .then(|| {
+            quote! {
+                if let Some(dict) = ::arrow_udf::codegen::arrow_array::cast::AsArray::as_any_dictionary_opt(input.column(0)) {
+                    use ::std::sync::Arc;
+                    use ::arrow_udf::codegen::arrow_array::RecordBatch;
+                    use ::arrow_udf::codegen::arrow_schema::Schema;
+
+                    let field = input.schema().field(0).clone().with_data_type(dict.values().data_type().clone());
+                    let values = RecordBatch::try_new(Arc::new(Schema::new(vec![field])), vec![dict.values().clone()])?;
+                    let output = #eval_fn_name(&values)?;
+                    let array = dict.with_values(output.column(0).clone());
+                    let field = output.schema().field(0).clone().with_data_type(array.data_type().clone());
+                    return RecordBatch::try_new(Arc::new(Schema::new(vec![field])), vec![array]);
+                }
+            }
+        });
+
+        // fill the missing trailing arguments with their default values
+        let fill_defaults = (!self.defaults.is_empty()).then(|| {
+            let fields = self.args[..num_args].iter().map(|ty| arg_field("", ty));
+            let defaults = &self.defaults;
+            quote! {
+                let input = {
+                    use ::std::sync::Arc;
+                    use ::arrow_udf::codegen::arrow_schema::{self, TimeUnit, IntervalUnit};
+                    ::arrow_udf::codegen::fill_defaults(input, &[#(#fields),*], &[#(#defaults),*])?
+                };
+            }
+        });
+
         // the function body
         let body = quote! {
             use ::std::sync::Arc;
@@ -419,6 +1072,7 @@ impl FunctionAttr {
             use ::arrow_udf::codegen::arrow_array::array::*;
             use ::arrow_udf::codegen::arrow_array::builder::*;
             use ::arrow_udf::codegen::arrow_schema::{Schema, SchemaRef, Field, DataType, IntervalUnit, TimeUnit};
+            use ::arrow_udf::codegen::{error_field, ErrorBuilder, ErrorRef, AppendError as _, AppendErrorCode as _};
             use ::arrow_udf::codegen::arrow_arith;
             use ::arrow_udf::codegen::arrow_schema;
             use ::arrow_udf::codegen::chrono;
@@ -432,13 +1086,40 @@ impl FunctionAttr {
         Ok(if self.is_table_function {
             quote! {
                 #fn_with_visibility #eval_fn_name<'a>(input: &'a ::arrow_udf::codegen::arrow_array::RecordBatch)
-                    -> ::arrow_udf::Result<Box<dyn Iterator<Item = ::arrow_udf::codegen::arrow_array::RecordBatch> + 'a>>
+                    -> ::arrow_udf::Result<Box<dyn Iterator<Item = ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::RecordBatch>> + 'a>>
                 {
                     const BATCH_SIZE: usize = 1024;
                     use ::arrow_udf::codegen::genawaiter::{rc::gen, yield_};
                     use ::arrow_udf::codegen::arrow_array::array::*;
+                    use ::arrow_udf::codegen::arrow_schema;
+                    let context = ::arrow_udf::Context::current();
+                    context.check_deadline()?;
+                    #fill_defaults
+                    #cast_columns
+                    #infer_return_type
+                    // check the types before creating the generator
                     #downcast_arrays
-                    Ok(Box::new(gen!({ #body }).into_iter()))
+                    #define_table_schema
+                    Ok(Box::new(gen!({
+                        #downcast_arrays_unchecked
+                        #body
+                    }).into_iter()))
+                }
+            }
+        } else if user_fn.async_ {
+            quote! {
+                #fn_with_visibility #eval_fn_name<'a>(input: &'a ::arrow_udf::codegen::arrow_array::RecordBatch)
+                    -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::RecordBatch>> + Send + 'a>>
+                {
+                    let context = ::arrow_udf::Context::current();
+                    Box::pin(async move {
+                        context.check_deadline()?;
+                        #fill_defaults
+                        #cast_columns
+                        #infer_return_type
+                        #downcast_arrays
+                        #body
+                    })
                 }
             }
         } else {
@@ -446,64 +1127,587 @@ impl FunctionAttr {
                 #fn_with_visibility #eval_fn_name(input: &::arrow_udf::codegen::arrow_array::RecordBatch)
                     -> ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::RecordBatch>
                 {
+                    let context = ::arrow_udf::Context::current();
+                    context.check_deadline()?;
+                    #fill_defaults
+                    #eval_dictionary
+                    #cast_columns
+                    #infer_return_type
                     #downcast_arrays
                     #body
                 }
             }
         })
     }
+    /// Generate a descriptor of the aggregate function.
+    ///
+    /// The types of arguments and return value should not contain wildcard.
+    pub fn generate_aggregate_descriptor(
+        &self,
+        user_fn: &UserFunctionAttr,
+    ) -> Result<TokenStream2> {
+        if self.is_variadic() {
+            return Err(Error::new(
+                Span::call_site(),
+                "variadic arguments are not supported for aggregate functions",
+            ));
+        }
+        if !self.defaults.is_empty() {
+            return Err(Error::new(
+                Span::call_site(),
+                "default values are not supported for aggregate functions",
+            ));
+        }
+        let name = self.name.clone();
+        // the first argument is the state
+        let args = (self.args.iter())
+            .zip(self.arg_names(user_fn.arg_names.get(1..).unwrap_or_default()))
+            .map(|(ty, name)| arg_field(name, ty))
+            .collect_vec();
+        let ret = field(&self.name, &self.ret);
+
+        let eval_name = match &self.output {
+            Some(output) => format_ident!("{}", output),
+            None => format_ident!("{}_eval", self.ident_name()),
+        };
+        let sig_name = format_ident!("{}_sig", self.ident_name());
+        let eval_function = self.generate_aggregate(user_fn, &eval_name)?;
+        let volatility = self.volatility(user_fn);
+        // the first argument is the state
+        let strict = user_fn.args_option.iter().skip(1).all(|b| !b);
+        let description = description(user_fn);
+        let examples = &self.examples;
+
+        Ok(quote! {
+            #eval_function
+
+            #[cfg(feature = "global_registry")]
+            #[::arrow_udf::codegen::linkme::distributed_slice(::arrow_udf::sig::SIGNATURES)]
+            fn #sig_name() -> ::arrow_udf::sig::FunctionSignature {
+                use ::arrow_udf::sig::{FunctionSignature, FunctionKind};
+                use ::arrow_udf::codegen::arrow_schema::{self, TimeUnit, IntervalUnit, Field};
+
+                let args: Vec<Field> = vec![#(#args),*];
+                FunctionSignature {
+                    name: #name.into(),
+                    arg_types: args.into(),
+                    variadic: false,
+                    variadic_type: None,
+                    defaults: vec![],
+                    return_type: #ret,
+                    type_infer: None,
+                    function: FunctionKind::Aggregate(#eval_name),
+                    volatility: #volatility,
+                    strict: #strict,
+                    description: #description,
+                    examples: vec![#(#examples.into()),*],
+                }
+            }
+        })
+    }
+
+    /// Generate an aggregate function.
+    ///
+    /// The generated item is a constant of `arrow_udf::AggregateFunction`.
+    fn generate_aggregate(
+        &self,
+        user_fn: &UserFunctionAttr,
+        eval_name: &Ident,
+    ) -> Result<TokenStream2> {
+        let visibility = self.visibility()?;
+        if (self.args.iter().chain([&self.ret])).any(|ty| types::is_polymorphic(ty)) {
+            return Err(Error::new(
+                Span::call_site(),
+                "`any` and `anyarray` are not supported in aggregate functions",
+            ));
+        }
+        let state_ty = self.state.as_deref().unwrap_or(&self.ret);
+        if self.finish.is_none() && state_ty != self.ret {
+            return Err(Error::new(
+                Span::call_site(),
+                "`finish` must be specified if the state type is different from the return type",
+            ));
+        }
+        let user_fn_name = format_ident!("{}", user_fn.name);
+
+        let children_indices = (0..self.args.len()).collect_vec();
+        let inputs = idents("i", &children_indices);
+        let arrays = idents("a", &children_indices);
+        let arg_arrays = children_indices
+            .iter()
+            .map(|i| format_ident!("{}", types::array_type(&self.args[*i])));
+        let read_inputs = arrays
+            .iter()
+            .zip(&self.args)
+            .map(|(array, ty)| gen_read_value(array, ty))
+            .collect_vec();
+        let state_array_type = format_ident!("{}", types::array_type(state_ty));
+        // conversion errors of aggregate functions fail the batch
+        let transform_state = transform_input(&format_ident!("s"), state_ty);
+        let transform_state = match types::is_fallible(state_ty) {
+            true => quote! { #transform_state? },
+            false => transform_state,
+        };
+
+        // the first argument of the user function is the state
+        let state_option = user_fn.args_option.first().copied().unwrap_or(false);
+        let some_inputs =
+            inputs
+                .iter()
+                .zip(user_fn.args_option.iter().skip(1))
+                .map(|(input, opt)| {
+                    if *opt {
+                        quote! { #input }
+                    } else {
+                        quote! { Some(#input) }
+                    }
+                });
+        let transformed_inputs = inputs
+            .iter()
+            .zip(&self.args)
+            .zip(
+                user_fn
+                    .args_json
+                    .iter()
+                    .skip(1)
+                    .chain(std::iter::repeat(&false)),
+            )
+            .map(|((input, ty), json)| transform_arg(input, ty, *json))
+            .collect_vec();
+        let (converted_inputs, conversions): (Vec<_>, Vec<_>) = itertools::multizip((
+            &inputs,
+            &self.args,
+            user_fn.args_option.iter().skip(1),
+            user_fn
+                .args_json
+                .iter()
+                .skip(1)
+                .chain(std::iter::repeat(&false)),
+        ))
+        .filter_map(|(input, ty, option, json)| {
+            Some((input, convert_arg(input, ty, *option, *json)?))
+        })
+        .unzip();
+        let retract = user_fn.retract.then(|| quote! { retract, });
+        let mut call = quote! { #user_fn_name(state, #(#transformed_inputs,)* #retract) };
+        // convert the return value to `Option<State>`
+        call = match user_fn.return_type_kind {
+            ReturnTypeKind::T => quote! { Some(#call) },
+            ReturnTypeKind::Option => call,
+            ReturnTypeKind::Result => {
+                quote! { Some(#call.map_err(|e| Error::ComputeError(e.to_string()))?) }
+            }
+            ReturnTypeKind::ResultOption => {
+                quote! { #call.map_err(|e| Error::ComputeError(e.to_string()))? }
+            }
+        };
+        let update_state = if state_option {
+            quote! { state = #call; }
+        } else {
+            // if the state is null, the first accumulated value becomes the state
+            let first_value = match (&transformed_inputs[..], &self.init_state) {
+                ([input], None) if user_fn.retract => quote! { (!retract).then(|| #input.into()) },
+                ([input], None) => quote! { Some(#input.into()) },
+                (_, None) => {
+                    return Err(Error::new(
+                        Span::call_site(),
+                        "aggregate functions with zero or multiple arguments require `init_state` or an `Option` state",
+                    ))
+                }
+                // the state is never null with `init_state`
+                (_, Some(_)) => quote! { None },
+            };
+            quote! {
+                state = match state {
+                    Some(state) => #call,
+                    None => #first_value,
+                };
+            }
+        };
+        let update_state = quote! {
+            match (#(#inputs,)*) {
+                (#(#some_inputs,)*) => {
+                    #(let #converted_inputs = #conversions?;)*
+                    #update_state
+                }
+                _ => {}
+            }
+        };
+
+        let one = quote! { 1 };
+        let state_builder = builder(state_ty, &one);
+        let append_state = gen_append(state_ty);
+        let append_state_null = gen_append_null(state_ty);
+        let append_state_value = gen_append_value(state_ty);
+        let init_state = match &self.init_state {
+            Some(init_state) => {
+                let init_state: syn::Expr = syn::parse_str(init_state)?;
+                quote! {{
+                    let v = #init_state;
+                    #append_state_value?
+                }}
+            }
+            None => quote! { #append_state_null },
+        };
+        let downcast_state = quote! {
+            let state: &#state_array_type = state.as_any().downcast_ref()
+                .ok_or_else(|| Error::CastError(
+                    format!("expect {} for the state", stringify!(#state_array_type))
+                ))?;
+            let mut state = match unsafe { (state.len() > 0 && !state.is_null(0)).then(|| state.value_unchecked(0)) } {
+                Some(s) => Some(#transform_state),
+                None => None,
+            };
+        };
+        let return_state = quote! {
+            let mut builder = #state_builder;
+            let builder = &mut builder;
+            let v = state;
+            #append_state?;
+            Ok(Arc::new(builder.finish()))
+        };
+        let columns = idents("c", &children_indices);
+        let cast_columns = gen_cast_columns(&self.args);
+        let downcast_arrays = quote! {
+            #cast_columns
+            #(
+                let #arrays: &#arg_arrays = #columns.as_any().downcast_ref()
+                    .ok_or_else(|| Error::CastError(
+                        format!("expect {} for the {}-th argument", stringify!(#arg_arrays), #children_indices)
+                    ))?;
+            )*
+        };
+        let get_inputs = quote! {
+            #(let #inputs = #read_inputs;)*
+        };
+
+        let let_retract = user_fn.retract.then(|| quote! { let retract = false; });
+        let accumulate_or_retract = if user_fn.retract && !self.append_only {
+            quote! {{
+                fn accumulate_or_retract(state: &dyn Array, ops: &BooleanArray, input: &RecordBatch) -> Result<ArrayRef> {
+                    #downcast_arrays
+                    #downcast_state
+                    for i in 0..input.num_rows() {
+                        let retract = ops.is_valid(i) && ops.value(i);
+                        #get_inputs
+                        #update_state
+                    }
+                    #return_state
+                }
+                Some(accumulate_or_retract)
+            }}
+        } else {
+            quote! { None }
+        };
+        let merge = match &self.merge {
+            Some(merge_fn) => {
+                let merge_fn = format_ident!("{}", merge_fn);
+                quote! {{
+                    fn merge(states: &dyn Array) -> Result<ArrayRef> {
+                        let states: &#state_array_type = states.as_any().downcast_ref()
+                            .ok_or_else(|| Error::CastError(
+                                format!("expect {} for the states", stringify!(#state_array_type))
+                            ))?;
+                        let mut state = None;
+                        for i in 0..states.len() {
+                            let Some(s) = (unsafe { (!states.is_null(i)).then(|| states.value_unchecked(i)) }) else {
+                                continue;
+                            };
+                            let s = #transform_state;
+                            state = Some(match state {
+                                Some(state) => #merge_fn(state, s),
+                                None => s,
+                            });
+                        }
+                        #return_state
+                    }
+                    Some(merge)
+                }}
+            }
+            None => quote! { None },
+        };
+        let finish_state = match &self.finish {
+            Some(finish_fn) => {
+                let finish_fn = format_ident!("{}", finish_fn);
+                quote! { #finish_fn(#transform_state) }
+            }
+            None => transform_state.clone(),
+        };
+        let ret_builder = builder(&self.ret, &quote! { states.len() });
+        let append_output = gen_append(&self.ret);
+
+        Ok(quote! {
+            #[allow(non_upper_case_globals)]
+            #visibility const #eval_name: ::arrow_udf::AggregateFunction = {
+                use ::std::sync::Arc;
+                use ::arrow_udf::{Result, Error};
+                use ::arrow_udf::codegen::arrow_array;
+                use ::arrow_udf::codegen::arrow_array::RecordBatch;
+                use ::arrow_udf::codegen::arrow_array::array::*;
+                use ::arrow_udf::codegen::arrow_array::builder::*;
+                use ::arrow_udf::codegen::arrow_schema::{Schema, SchemaRef, Field, DataType, IntervalUnit, TimeUnit};
+                use ::arrow_udf::codegen::arrow_schema;
+                use ::arrow_udf::codegen::chrono;
+                use ::arrow_udf::codegen::rust_decimal;
+                use ::arrow_udf::codegen::serde_json;
+
+                fn create_state() -> Result<ArrayRef> {
+                    let mut builder = #state_builder;
+                    let builder = &mut builder;
+                    #init_state;
+                    Ok(Arc::new(builder.finish()))
+                }
+
+                fn accumulate(state: &dyn Array, input: &RecordBatch) -> Result<ArrayRef> {
+                    #let_retract
+                    #downcast_arrays
+                    #downcast_state
+                    for i in 0..input.num_rows() {
+                        #get_inputs
+                        #update_state
+                    }
+                    #return_state
+                }
+
+                fn finish(states: &dyn Array) -> Result<ArrayRef> {
+                    let states: &#state_array_type = states.as_any().downcast_ref()
+                        .ok_or_else(|| Error::CastError(
+                            format!("expect {} for the states", stringify!(#state_array_type))
+                        ))?;
+                    let mut builder = #ret_builder;
+                    let builder = &mut builder;
+                    for i in 0..states.len() {
+                        let v = match unsafe { (!states.is_null(i)).then(|| states.value_unchecked(i)) } {
+                            Some(s) => Some(#finish_state),
+                            None => None,
+                        };
+                        #append_output?;
+                    }
+                    Ok(Arc::new(builder.finish()))
+                }
+
+                ::arrow_udf::AggregateFunction {
+                    create_state,
+                    accumulate,
+                    accumulate_or_retract: #accumulate_or_retract,
+                    merge: #merge,
+                    finish,
+                }
+            };
+        })
+    }
+
+    /// Returns the visibility of generated items.
+    fn visibility(&self) -> Result<TokenStream2> {
+        let Some(visibility) = &self.visibility else {
+            return Ok(quote! {});
+        };
+        // handle the scope of the visibility by parsing the visibility string
+        Ok(match syn::parse_str::<syn::Visibility>(visibility)? {
+            syn::Visibility::Public(token) => quote! { #token },
+            syn::Visibility::Restricted(vis_restricted) => quote! { #vis_restricted },
+            syn::Visibility::Inherited => quote! {},
+        })
+    }
+}
+
+/// Returns the description of the function from its doc comment.
+fn description(user_fn: &UserFunctionAttr) -> TokenStream2 {
+    match &user_fn.doc {
+        Some(doc) => quote! { Some(#doc.into()) },
+        None => quote! { None },
+    }
+}
+
+/// Return a list of identifiers with the given prefix and indices.
+fn idents(prefix: &str, indices: &[usize]) -> Vec<Ident> {
+    indices
+        .iter()
+        .map(|i| format_ident!("{prefix}{i}"))
+        .collect()
 }
 
-/// Returns a `Field` from type name.
+/// Generate code to get the input columns `c0, c1, ..` of the given argument types.
+///
+/// Dictionary-encoded and view-typed columns are cast to the argument types.
+fn gen_cast_columns(args: &[String]) -> TokenStream2 {
+    let casts = args.iter().enumerate().map(|(i, ty)| {
+        let column = format_ident!("c{i}");
+        let cast_column = gen_cast_column(quote! { input.column(#i) }, ty);
+        quote! { let #column = #cast_column; }
+    });
+    quote! { #(#casts)* }
+}
+
+/// Generate an expression to cast the `column: &ArrayRef` to the array of type `ty`.
+fn gen_cast_column(column: TokenStream2, ty: &str) -> TokenStream2 {
+    if let Some(s) = struct_type(ty) {
+        return quote! { <#s as ::arrow_udf::types::StructType>::decode(#column)? };
+    }
+    if ty.ends_with("[]")
+        || ty.starts_with("struct ")
+        || ty.starts_with("map<")
+        || types::is_polymorphic(ty)
+    {
+        return quote! { #column.clone() };
+    }
+    let data_type = data_type(ty);
+    quote! {
+        match #column.data_type() {
+            ::arrow_udf::codegen::arrow_schema::DataType::Dictionary(_, _)
+            | ::arrow_udf::codegen::arrow_schema::DataType::Utf8View
+            | ::arrow_udf::codegen::arrow_schema::DataType::BinaryView => {
+                ::arrow_udf::codegen::arrow_cast::cast(#column, &#data_type)?
+            }
+            _ => #column.clone(),
+        }
+    }
+}
+
+/// Returns a nullable `Field` from type name.
 pub fn field(name: &str, ty: &str) -> TokenStream2 {
-    let data_type = if let Some(ty) = ty.strip_suffix("[]") {
-        let inner = field("item", ty);
-        quote! { arrow_schema::DataType::List(Arc::new(#inner)) }
-    } else if let Some(s) = ty.strip_prefix("struct ") {
-        let struct_type = format_ident!("{}", s);
-        quote! { arrow_schema::DataType::Struct(#struct_type::fields()) }
-    } else {
-        let variant: TokenStream2 = types::data_type(ty).parse().unwrap();
-        quote! { arrow_schema::DataType::#variant }
-    };
+    field_with_nullable(name, ty, true)
+}
+
+/// Returns a `Field` from type name with the given nullability.
+pub fn field_with_nullable(name: &str, ty: &str, nullable: bool) -> TokenStream2 {
+    let data_type = data_type(ty);
     let with_metadata = match ty {
         "json" => {
-            quote! { .with_metadata([("ARROW:extension:name".into(), "arrowudf.json".into())].into()) }
+            quote! { .with_metadata(std::collections::HashMap::from([("ARROW:extension:name".to_string(), "arrowudf.json".to_string())])) }
         }
         "decimal" => {
-            quote! { .with_metadata([("ARROW:extension:name".into(), "arrowudf.decimal".into())].into()) }
+            quote! { .with_metadata(std::collections::HashMap::from([("ARROW:extension:name".to_string(), "arrowudf.decimal".to_string())])) }
+        }
+        "any" => {
+            quote! { .with_metadata(std::collections::HashMap::from([("ARROW:extension:name".to_string(), "arrowudf.any".to_string())])) }
+        }
+        "anyarray" => {
+            quote! { .with_metadata(std::collections::HashMap::from([("ARROW:extension:name".to_string(), "arrowudf.anyarray".to_string())])) }
         }
         _ => quote! {},
     };
     quote! {
-        arrow_schema::Field::new(#name, #data_type, true) #with_metadata
+        arrow_schema::Field::new(#name, #data_type, #nullable) #with_metadata
+    }
+}
+
+/// Returns the `Field` of an argument or return value from type name.
+///
+/// Unlike [`field`], struct types use `StructType::data_type`, e.g. unions for enums.
+fn arg_field(name: &str, ty: &str) -> TokenStream2 {
+    match struct_type(ty) {
+        Some(s) => quote! {
+            arrow_schema::Field::new(#name, <#s as ::arrow_udf::types::StructType>::data_type(), true)
+        },
+        None => field(name, ty),
     }
 }
 
-/// Generate a builder for the given type.
-fn builder(ty: &str) -> TokenStream2 {
+/// Returns the identifier of a struct type, or `None` if the type is not a struct.
+fn struct_type(ty: &str) -> Option<Ident> {
+    ty.strip_prefix("struct ")
+        .filter(|s| !s.ends_with("[]"))
+        .map(|s| format_ident!("{}", s))
+}
+
+/// Returns a `DataType` from type name.
+fn data_type(ty: &str) -> TokenStream2 {
+    if let Some(ty) = ty.strip_suffix("[]") {
+        let inner = field("item", ty);
+        quote! { arrow_schema::DataType::List(Arc::new(#inner)) }
+    } else if let Some(s) = ty.strip_prefix("struct ") {
+        let struct_type = format_ident!("{}", s);
+        quote! { arrow_schema::DataType::Struct(#struct_type::fields()) }
+    } else if let Some(columns) = types::table_columns(ty) {
+        let fields = columns.iter().map(|(name, ty)| field(name, ty));
+        quote! { arrow_schema::DataType::Struct(vec![#(#fields),*].into()) }
+    } else if let Some((key_ty, value_ty)) = types::map_key_value(ty) {
+        let key_field = field("keys", key_ty);
+        let value_field = field("values", value_ty);
+        quote! {
+            arrow_schema::DataType::Map(
+                Arc::new(arrow_schema::Field::new(
+                    "entries",
+                    arrow_schema::DataType::Struct(vec![#key_field.with_nullable(false), #value_field].into()),
+                    false,
+                )),
+                false,
+            )
+        }
+    } else if let Some((precision, scale)) = types::decimal_precision_scale(ty) {
+        if precision <= 38 {
+            quote! { arrow_schema::DataType::Decimal128(#precision, #scale) }
+        } else {
+            quote! { arrow_schema::DataType::Decimal256(#precision, #scale) }
+        }
+    } else {
+        let variant: TokenStream2 = types::data_type(ty).parse().unwrap();
+        quote! { arrow_schema::DataType::#variant }
+    }
+}
+
+/// Generate a builder for the given type with the given capacity.
+fn builder(ty: &str, capacity: &TokenStream2) -> TokenStream2 {
     match ty {
         // `NullBuilder::with_capacity` is deprecated since v52.0, use `NullBuilder::new` instead.
         "null" => quote! { NullBuilder::new() },
-        "string" => quote! { StringBuilder::with_capacity(input.num_rows(), 1024) },
-        "binary" => quote! { BinaryBuilder::with_capacity(input.num_rows(), 1024) },
-        "largestring" => quote! { LargeStringBuilder::with_capacity(input.num_rows(), 1024) },
-        "largebinary" => quote! { LargeBinaryBuilder::with_capacity(input.num_rows(), 1024) },
+        "string" => quote! { StringBuilder::with_capacity(#capacity, 1024) },
+        "binary" => quote! { BinaryBuilder::with_capacity(#capacity, 1024) },
+        "largestring" => quote! { LargeStringBuilder::with_capacity(#capacity, 1024) },
+        "largebinary" => quote! { LargeBinaryBuilder::with_capacity(#capacity, 1024) },
         "decimal" => {
-            quote! { StringBuilder::with_capacity(input.num_rows(), input.num_rows() * 8) }
+            quote! { StringBuilder::with_capacity(#capacity, #capacity * 8) }
+        }
+        "json" => quote! { StringBuilder::with_capacity(#capacity, #capacity * 8) },
+        "timestamptz" => {
+            quote! { TimestampMicrosecondBuilder::with_capacity(#capacity).with_timezone("UTC") }
+        }
+        // `return_type` is inferred from the input types
+        "any" => quote! { ::arrow_udf::codegen::AnyBuilder::new(return_type.clone(), #capacity) },
+        "anyarray" => {
+            quote! { ::arrow_udf::codegen::AnyArrayBuilder::new(return_type.clone(), #capacity) }
         }
-        "json" => quote! { StringBuilder::with_capacity(input.num_rows(), input.num_rows() * 8) },
         s if s.ends_with("[]") => {
-            let values_builder = builder(ty.strip_suffix("[]").unwrap());
-            quote! { ListBuilder::<Box<dyn ArrayBuilder>>::with_capacity(Box::new(#values_builder), input.num_rows()) }
+            let values_builder = builder(ty.strip_suffix("[]").unwrap(), capacity);
+            quote! { ListBuilder::<Box<dyn ArrayBuilder>>::with_capacity(Box::new(#values_builder), #capacity) }
         }
         s if s.starts_with("struct ") => {
             let struct_ident = format_ident!("{}", &s[7..]);
-            quote! { StructBuilder::from_fields(#struct_ident::fields(), input.num_rows()) }
+            quote! { StructBuilder::from_fields(#struct_ident::fields(), #capacity) }
+        }
+        s if s.starts_with("table(") => {
+            let fields = types::table_columns(s)
+                .unwrap()
+                .into_iter()
+                .map(|(name, ty)| field(name, ty));
+            quote! { StructBuilder::from_fields(vec![#(#fields),*], #capacity) }
+        }
+        s if s.starts_with("map<") => {
+            let (key_ty, value_ty) = types::map_key_value(s).unwrap();
+            let key_builder = builder(key_ty, capacity);
+            let value_builder = builder(value_ty, capacity);
+            quote! {
+                MapBuilder::<Box<dyn ArrayBuilder>, Box<dyn ArrayBuilder>>::with_capacity(
+                    Some(arrow_array::builder::MapFieldNames {
+                        entry: "entries".into(),
+                        key: "keys".into(),
+                        value: "values".into(),
+                    }),
+                    Box::new(#key_builder),
+                    Box::new(#value_builder),
+                    #capacity,
+                )
+            }
+        }
+        s if s.starts_with("decimal(") => {
+            let builder_type = format_ident!("{}", types::array_builder_type(ty));
+            let data_type = data_type(ty);
+            quote! { #builder_type::with_capacity(#capacity).with_data_type(#data_type) }
         }
         _ => {
             let builder_type = format_ident!("{}", types::array_builder_type(ty));
-            quote! { #builder_type::with_capacity(input.num_rows()) }
+            quote! { #builder_type::with_capacity(#capacity) }
         }
     }
 }
@@ -514,36 +1718,144 @@ fn builder(ty: &str) -> TokenStream2 {
 pub fn builder_type(ty: &str) -> TokenStream2 {
     if ty.ends_with("[]") {
         quote! { ListBuilder::<Box<dyn ArrayBuilder>> }
+    } else if ty.starts_with("map<") {
+        quote! { MapBuilder::<Box<dyn ArrayBuilder>, Box<dyn ArrayBuilder>> }
     } else {
         types::array_builder_type(ty).parse().unwrap()
     }
 }
 
 /// Generate code to append the `v: Option<T>` to the `builder`.
+///
+/// The code evaluates to `arrow_udf::Result<()>`, see [`gen_append_value`].
 fn gen_append(ty: &str) -> TokenStream2 {
     let append_value = gen_append_value(ty);
     let append_null = gen_append_null(ty);
     quote! {
         match v {
             Some(v) => #append_value,
-            None => #append_null,
+            None => {
+                #append_null;
+                ::arrow_udf::Result::Ok(())
+            }
         }
     }
 }
 
+/// Generate code to append the row `v: Option<(T0, T1, ..)>` of a `table(..)` type
+/// to the `builder: &mut StructBuilder`.
+///
+/// `tuple_option` indicates whether each element of the tuple is `Option`.
+fn gen_append_table(columns: &[(&str, &str)], tuple_option: &[bool]) -> Result<TokenStream2> {
+    if columns.len() != tuple_option.len() {
+        return Err(Error::new(
+            Span::call_site(),
+            format!(
+                "expect the iterator to yield tuples of {} elements for `table(..)`",
+                columns.len()
+            ),
+        ));
+    }
+    let values = idents("v", &(0..columns.len()).collect_vec());
+    let append_values = columns.iter().zip(&values).zip(tuple_option).enumerate().map(
+        |(i, (((_, ty), v), option))| {
+            let builder_type = builder_type(ty);
+            let append = gen_append(ty);
+            let value = match option {
+                true => quote! { #v },
+                false => quote! { Some(#v) },
+            };
+            quote! {{
+                let builder = builder.field_builder::<#builder_type>(#i).expect("downcast table column builder");
+                let v = #value;
+                result = result.and(#append);
+            }}
+        },
+    );
+    let append_null = gen_append_table_null(columns);
+    // a row with any column failed to convert is null
+    Ok(quote! {
+        match v {
+            Some((#(#values,)*)) => {
+                let mut result = ::arrow_udf::Result::Ok(());
+                #(#append_values)*
+                builder.append(result.is_ok());
+                result
+            }
+            None => {
+                #append_null;
+                ::arrow_udf::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// Generate code to append a null row of a `table(..)` type to the `builder: &mut StructBuilder`.
+fn gen_append_table_null(columns: &[(&str, &str)]) -> TokenStream2 {
+    let append_nulls = columns.iter().enumerate().map(|(i, (_, ty))| {
+        let builder_type = builder_type(ty);
+        let append_null = gen_append_null(ty);
+        quote! {{
+            let builder = builder.field_builder::<#builder_type>(#i).expect("downcast table column builder");
+            #append_null;
+        }}
+    });
+    quote! {{
+        #(#append_nulls)*
+        builder.append_null();
+    }}
+}
+
 /// Generate code to append the `v: T` to the `builder: &mut Builder`.
+///
+/// The code evaluates to `arrow_udf::Result<()>`. If the value can not be converted, a null value
+/// is appended and the error is returned.
 pub fn gen_append_value(ty: &str) -> TokenStream2 {
+    let ok = quote! { ::arrow_udf::Result::Ok(()) };
     if let Some(inner_ty) = ty.strip_suffix("[]") {
         let value_builder_type = builder_type(inner_ty);
+        let append_value = gen_append_value(inner_ty);
         quote! {{
             // builder.values() is Box<dyn ArrayBuilder>
             let value_builder = builder.values().as_any_mut().downcast_mut::<#value_builder_type>().expect("downcast list value builder");
-            value_builder.extend(v.into_iter().map(Some));
-            builder.append(true);
+            let mut result = #ok;
+            for v in v {
+                let builder = &mut *value_builder;
+                result = #append_value;
+                if result.is_err() {
+                    break;
+                }
+            }
+            builder.append(result.is_ok());
+            result
         }}
     } else if ty.starts_with("struct ") {
+        quote! { v.append_to(builder) }
+    } else if let Some((key_ty, value_ty)) = types::map_key_value(ty) {
+        let key_builder_type = builder_type(key_ty);
+        let value_builder_type = builder_type(value_ty);
+        let append_key = gen_append_value(key_ty);
+        let append_value = gen_append_value(value_ty);
+        // keys are never fallible, so that they are never null
         quote! {{
-            v.append_to(builder);
+            let mut result = #ok;
+            for (key, value) in v {
+                {
+                    let builder = builder.keys().as_any_mut().downcast_mut::<#key_builder_type>().expect("downcast map key builder");
+                    let v = key;
+                    result = #append_key;
+                }
+                {
+                    let builder = builder.values().as_any_mut().downcast_mut::<#value_builder_type>().expect("downcast map value builder");
+                    let v = value;
+                    result = result.and(#append_value);
+                }
+                if result.is_err() {
+                    break;
+                }
+            }
+            builder.append(result.is_ok()).expect("append map");
+            result
         }}
     } else if ty == "json" {
         quote! {{
@@ -551,37 +1863,107 @@ pub fn gen_append_value(ty: &str) -> TokenStream2 {
             use std::fmt::Write;
             write!(builder, "{}", v).expect("write json");
             builder.append_value("");
+            #ok
         }}
     } else if ty == "decimal" {
-        quote! { builder.append_value(v.to_string()) }
-    } else if ty == "date32" {
-        quote! { builder.append_value(arrow_array::types::Date32Type::from_naive_date(v)) }
-    } else if ty == "time64" {
-        quote! { builder.append_value(arrow_array::temporal_conversions::time_to_time64us(v)) }
-    } else if ty == "timestamp" {
-        quote! { builder.append_value(v.and_utc().timestamp_micros()) }
+        quote! {{
+            builder.append_value(v.to_string());
+            #ok
+        }}
+    } else if let Some((precision, scale)) = types::decimal_precision_scale(ty) {
+        let scale = scale as u32;
+        let to_mantissa = match precision <= 38 {
+            true => quote! { decimal_to_i128 },
+            false => quote! { decimal_to_i256 },
+        };
+        quote! {
+            match ::arrow_udf::codegen::#to_mantissa(v, #precision, #scale) {
+                Ok(v) => {
+                    builder.append_value(v);
+                    #ok
+                }
+                Err(e) => {
+                    builder.append_null();
+                    Err(e)
+                }
+            }
+        }
+    } else if matches!(ty, "date32" | "time64" | "timestamp" | "timestamptz") {
+        let native = gen_to_native(ty);
+        quote! {{
+            builder.append_value(#native);
+            #ok
+        }}
     } else if ty == "interval" {
-        quote! { builder.append_value({
-            let v: arrow_udf::types::Interval = v.into();
-            arrow_array::types::IntervalMonthDayNanoType::make_value(v.months, v.days, v.nanos)
-        }) }
+        quote! {{
+            builder.append_value({
+                let v: arrow_udf::types::Interval = v.into();
+                arrow_array::types::IntervalMonthDayNanoType::make_value(v.months, v.days, v.nanos)
+            });
+            #ok
+        }}
     } else if ty == "null" {
-        quote! { builder.append_empty_value() }
+        quote! {{
+            builder.append_empty_value();
+            #ok
+        }}
     } else {
-        quote! { builder.append_value(v) }
+        quote! {{
+            builder.append_value(v);
+            #ok
+        }}
+    }
+}
+
+/// Generate code to convert the `v: T` to the native value stored in the array.
+fn gen_to_native(ty: &str) -> TokenStream2 {
+    match ty {
+        "date32" => quote! { arrow_array::types::Date32Type::from_naive_date(v) },
+        "time64" => quote! { arrow_array::temporal_conversions::time_to_time64us(v) },
+        "timestamp" => quote! { v.and_utc().timestamp_micros() },
+        "timestamptz" => quote! { chrono::DateTime::<chrono::Utc>::from(v).timestamp_micros() },
+        _ => quote! { v },
     }
 }
 
 /// Generate code to append null to the `builder: &mut Builder`.
 pub fn gen_append_null(ty: &str) -> TokenStream2 {
-    if let Some(s) = ty.strip_prefix("struct ") {
+    if let Some(s) = ty.strip_prefix("struct ").filter(|s| !s.ends_with("[]")) {
         let struct_type = format_ident!("{}", s);
         quote! { #struct_type::append_null(builder) }
+    } else if let Some(columns) = types::table_columns(ty) {
+        gen_append_table_null(&columns)
+    } else if ty.starts_with("map<") {
+        quote! { builder.append(false).expect("append map") }
     } else {
         quote! { builder.append_null() }
     }
 }
 
+/// Generate code to read the `i`-th value of the `array` as `Option<T>`.
+///
+/// Structs, maps and lists are read directly from the array, so that the value borrows from the input.
+/// The only exception is lists of string or binary, which are passed as a sliced array.
+fn gen_read_value(array: &Ident, ty: &str) -> TokenStream2 {
+    match ty {
+        // `any` is read as a sliced array of length 1
+        "any" => return quote! { (!#array.is_null(i)).then(|| #array.slice(i, 1)) },
+        // `anyarray` is read as the array of elements
+        "anyarray" => return quote! { (!#array.is_null(i)).then(|| #array.value(i)) },
+        _ => {}
+    }
+    let sliced_list = matches!(
+        ty,
+        "string[]" | "binary[]" | "largestring[]" | "largebinary[]"
+    );
+    if ty.starts_with("struct ") || ty.starts_with("map<") || (ty.ends_with("[]") && !sliced_list) {
+        let read_element = gen_read_element(&quote! { #array }, ty, &quote! { i });
+        quote! { (!#array.is_null(i)).then(|| #read_element) }
+    } else {
+        quote! { unsafe { (!#array.is_null(i)).then(|| #array.value_unchecked(i)) } }
+    }
+}
+
 /// Generate code to transform the input from the type got from arrow array to the type in the user function.
 ///
 /// | Data Type       | Arrow Value Type | User Function Type               |
@@ -589,32 +1971,36 @@ pub fn gen_append_null(ty: &str) -> TokenStream2 {
 /// | `date32`        | `i32`            | `chrono::NaiveDate`              |
 /// | `time64`        | `i64`            | `chrono::NaiveTime`              |
 /// | `timestamp`     | `i64`            | `chrono::NaiveDateTime`          |
+/// | `timestamptz`   | `i64`            | `chrono::DateTime<Utc>`          |
 /// | `interval`      | `i128`           | `arrow_udf::types::Interval`     |
 /// | `decimal`       | `&str`           | `rust_decimal::Decimal`          |
-/// | `json`          | `&str`           | `serde_json::Value`              |
-/// | `int8[]`        | `ArrayRef`       | `&[i8]`                          |
-/// | `int16[]`       | `ArrayRef`       | `&[i16]`                         |
-/// | `int32[]`       | `ArrayRef`       | `&[i32]`                         |
-/// | `int64[]`       | `ArrayRef`       | `&[i64]`                         |
-/// | `uint8[]`       | `ArrayRef`       | `&[u8]`                          |
-/// | `uint16[]`      | `ArrayRef`       | `&[u16]`                         |
-/// | `uint32[]`      | `ArrayRef`       | `&[u32]`                         |
-/// | `uint64[]`      | `ArrayRef`       | `&[u64]`                         |
-/// | `float32[]`     | `ArrayRef`       | `&[f32]`                         |
-/// | `float64[]`     | `ArrayRef`       | `&[f64]`                         |
+/// | `decimal(p,s)`  | `i128` / `i256`  | `Result<rust_decimal::Decimal>`  |
+/// | `json`          | `&str`           | `serde_json::Value` or `Json<T>` |
 /// | `string[]`      | `ArrayRef`       | `arrow::array::StringArray`      |
 /// | `binary[]`      | `ArrayRef`       | `arrow::array::BinaryArray`      |
 /// | `largestring[]` | `ArrayRef`       | `arrow::array::LargeStringArray` |
 /// | `largebinary[]` | `ArrayRef`       | `arrow::array::LargeBinaryArray` |
-fn transform_input(input: &Ident, ty: &str) -> TokenStream2 {
+///
+/// Other lists, structs and maps are read directly from the array by [`gen_read_element`].
+/// Types that [`types::is_fallible`] are transformed into `arrow_udf::Result`.
+pub fn transform_input(input: &Ident, ty: &str) -> TokenStream2 {
     if ty == "decimal" {
         return quote! { #input.parse::<rust_decimal::Decimal>().expect("invalid decimal") };
+    } else if let Some((precision, scale)) = types::decimal_precision_scale(ty) {
+        let scale = scale as u32;
+        let from_mantissa = match precision <= 38 {
+            true => quote! { decimal_from_i128 },
+            false => quote! { decimal_from_i256 },
+        };
+        return quote! { ::arrow_udf::codegen::#from_mantissa(#input, #precision, #scale) };
     } else if ty == "date32" {
-        return quote! { arrow_array::types::Date32Type::to_naive_date(#input) };
+        return quote! { arrow_array::types::Date32Type::to_naive_date_opt(#input).expect("invalid date") };
     } else if ty == "time64" {
         return quote! { arrow_array::temporal_conversions::as_time::<arrow_array::types::Time64MicrosecondType>(#input).expect("invalid time") };
     } else if ty == "timestamp" {
         return quote! { arrow_array::temporal_conversions::as_datetime::<arrow_array::types::TimestampMicrosecondType>(#input).expect("invalid timestamp") };
+    } else if ty == "timestamptz" {
+        return quote! { arrow_array::temporal_conversions::as_datetime::<arrow_array::types::TimestampMicrosecondType>(#input).expect("invalid timestamp").and_utc() };
     } else if ty == "interval" {
         return quote! {{
             let (months, days, nanos) = arrow_array::types::IntervalMonthDayNanoType::to_parts(#input);
@@ -623,13 +2009,7 @@ fn transform_input(input: &Ident, ty: &str) -> TokenStream2 {
     } else if ty == "json" {
         return quote! { #input.parse::<serde_json::Value>().expect("invalid json") };
     } else if let Some(elem_type) = ty.strip_suffix("[]") {
-        if types::is_primitive(elem_type) {
-            let array_type = format_ident!("{}", types::array_type(elem_type));
-            return quote! {{
-                let primitive_array: &#array_type = #input.as_primitive();
-                primitive_array.values().as_ref()
-            }};
-        } else if elem_type == "string" {
+        if elem_type == "string" {
             return quote! {
                 #input.as_any().downcast_ref::<arrow_array::StringArray>().expect("string array")
             };
@@ -645,13 +2025,104 @@ fn transform_input(input: &Ident, ty: &str) -> TokenStream2 {
             return quote! {
                 #input.as_any().downcast_ref::<arrow_array::LargeBinaryArray>().expect("large binary array")
             };
-        } else {
-            return quote! { #input };
         }
     }
     quote! { #input }
 }
 
+/// Generate code to transform an argument of the user function.
+///
+/// Arguments that [`types::is_converted`] and `json` arguments of type `Json<T>` are passed as is,
+/// since they are converted by [`convert_arg`].
+fn transform_arg(input: &Ident, ty: &str, json: bool) -> TokenStream2 {
+    if (json && ty == "json") || types::is_converted(ty) {
+        return quote! { #input };
+    }
+    transform_input(input, ty)
+}
+
+/// Generate code to convert an argument of the user function, if the type [`types::is_converted`]
+/// or the argument is a `json` of type `Json<T>`, which is deserialized into `T`.
+///
+/// The code evaluates to `arrow_udf::Result<T>`, or `arrow_udf::Result<Option<T>>` if the argument
+/// is `Option`.
+fn convert_arg(input: &Ident, ty: &str, option: bool, json: bool) -> Option<TokenStream2> {
+    let convert = if json && ty == "json" {
+        quote! { ::arrow_udf::codegen::parse_json(#input) }
+    } else if types::is_fallible(ty) {
+        transform_input(input, ty)
+    } else if ty.ends_with("[]") && types::is_converted(ty) {
+        // lists are read into `Result<Vec<_>>` and passed as iterators
+        quote! { #input.map(|v| v.into_iter()) }
+    } else if ty.starts_with("struct ") {
+        // structs are read into `Result<T>`
+        return Some(match option {
+            true => quote! { #input.transpose() },
+            false => quote! { #input },
+        });
+    } else {
+        return None;
+    };
+    Some(match option {
+        true => quote! { #input.map(|#input| #convert).transpose() },
+        false => convert,
+    })
+}
+
+/// Generate code to read the non-null value at `index` of `array: &'a XxxArray` as the type in the user function.
+///
+/// The value borrows from the array with lifetime `'a`. Values of types that [`types::is_converted`]
+/// are read as `arrow_udf::Result`, where lists are collected into a `Vec`.
+fn gen_read_element(array: &TokenStream2, ty: &str, index: &TokenStream2) -> TokenStream2 {
+    if ty == "null" {
+        return quote! { () };
+    }
+    if let Some(elem_type) = ty.strip_suffix("[]") {
+        let array_type = format_ident!("{}", types::array_type(elem_type));
+        let read_elements = if types::is_primitive(elem_type) {
+            quote! { &values.values()[start..end] }
+        } else if types::is_converted(elem_type) {
+            // elements are converted eagerly, and the first error fails the list
+            let read_element = gen_read_element(&quote! { values }, elem_type, &quote! { j });
+            quote! {
+                (start..end)
+                    .map(|j| (!values.is_null(j)).then(|| #read_element).transpose())
+                    .collect::<::arrow_udf::Result<Vec<_>>>()
+            }
+        } else {
+            let read_element = gen_read_element(&quote! { values }, elem_type, &quote! { j });
+            quote! { (start..end).map(move |j| (!values.is_null(j)).then(|| #read_element)) }
+        };
+        return quote! {{
+            let start = #array.value_offsets()[#index] as usize;
+            let end = #array.value_offsets()[#index + 1] as usize;
+            let values = #array.values().as_any().downcast_ref::<#array_type>().expect("downcast list values");
+            #read_elements
+        }};
+    }
+    if ty.starts_with("struct ") {
+        return quote! { ::arrow_udf::types::FromStructArray::from_struct_array(#array, #index) };
+    }
+    if let Some((key_ty, value_ty)) = types::map_key_value(ty) {
+        let key_array_type = format_ident!("{}", types::array_type(key_ty));
+        let value_array_type = format_ident!("{}", types::array_type(value_ty));
+        let read_key = gen_read_element(&quote! { keys }, key_ty, &quote! { j });
+        let read_value = gen_read_element(&quote! { values }, value_ty, &quote! { j });
+        return quote! {{
+            let start = #array.value_offsets()[#index] as usize;
+            let end = #array.value_offsets()[#index + 1] as usize;
+            let keys = #array.keys().as_any().downcast_ref::<#key_array_type>().expect("downcast map keys");
+            let values = #array.values().as_any().downcast_ref::<#value_array_type>().expect("downcast map values");
+            (start..end).map(move |j| (#read_key, (!values.is_null(j)).then(|| #read_value)))
+        }};
+    }
+    let transform = transform_input(&format_ident!("v"), ty);
+    quote! {{
+        let v = #array.value(#index);
+        #transform
+    }}
+}
+
 /// Encode a string to a symbol name using customized base64.
 pub fn base64_encode(input: &str) -> String {
     use base64::{
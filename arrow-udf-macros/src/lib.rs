@@ -22,9 +22,43 @@ mod struct_type;
 mod types;
 mod utils;
 
-/// Derive `StructType` for user defined struct.
+/// Derive `StructType` for user defined struct or enum.
 ///
 /// Structs that implement `StructType` can be used as Arrow struct types.
+/// This also implements `FromStructArray` so that the struct can be used as function arguments.
+///
+/// Fields of tuple structs are named `f0`, `f1`, and so on.
+///
+/// Enums are also used as `struct` types in function signatures. As arguments and return values,
+/// fieldless enums are encoded as `Dictionary(Int32, Utf8)` of the variant names, and other enums
+/// are encoded as dense unions with a struct child for each variant. Enums nested in structs or
+/// lists are stored as their struct representation.
+///
+/// # Field Attributes
+///
+/// Fields can be customized with `#[struct_type(...)]`:
+///
+/// - `rename = "name"`: use `name` as the field name instead of the Rust name.
+/// - `skip`: exclude the field from the struct type. It is set to `Default::default()` when read.
+/// - `ty = "type"`: use the data type `type` instead of the one inferred from the Rust type.
+///   This is required for type aliases, whose type can not be inferred from the name.
+///
+/// Only fields of `Option` types are nullable, and fields of `StructType` types are nested structs.
+///
+/// ```ignore
+/// type Payload = serde_json::Value;
+///
+/// #[derive(StructType)]
+/// struct Event {
+///     #[struct_type(rename = "type")]
+///     kind: String,
+///     #[struct_type(ty = "json")]
+///     payload: Option<Payload>,
+///     origin: Option<Point>,
+///     #[struct_type(skip)]
+///     cached: usize,
+/// }
+/// ```
 ///
 /// # Examples
 ///
@@ -42,8 +76,31 @@ mod utils;
 ///     let (key, value) = kv.split_once('=')?;
 ///     Some(KeyValue { key, value })
 /// }
+///
+/// #[function("get_key(struct KeyValue) -> string")]
+/// fn get_key(kv: KeyValue<'_>) -> &str {
+///     kv.key
+/// }
+/// ```
+///
+/// ```ignore
+/// #[derive(StructType)]
+/// enum Shape {
+///     Circle(f64),
+///     Rect { width: f64, height: f64 },
+///     Empty,
+/// }
+///
+/// #[function("area(struct Shape) -> float64")]
+/// fn area(s: Shape) -> f64 {
+///     match s {
+///         Shape::Circle(r) => std::f64::consts::PI * r * r,
+///         Shape::Rect { width, height } => width * height,
+///         Shape::Empty => 0.0,
+///     }
+/// }
 /// ```
-#[proc_macro_derive(StructType)]
+#[proc_macro_derive(StructType, attributes(struct_type))]
 pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
     match struct_type::gen(tokens.into()) {
         Ok(output) => output.into(),
@@ -56,14 +113,21 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// # Table of Contents
 ///
 /// - [SQL Function Signature](#sql-function-signature)
+///     - [Default Arguments](#default-arguments)
 ///     - [Multiple Function Definitions](#multiple-function-definitions)
 /// - [Rust Function Signature](#rust-function-signature)
 ///     - [Nullable Arguments](#nullable-arguments)
+///     - [Variadic Arguments](#variadic-arguments)
 ///     - [Return Value](#return-value)
 ///     - [Optimization](#optimization)
 ///     - [Functions Returning Strings](#functions-returning-strings)
+///     - [Async Functions](#async-functions)
+///     - [Context](#context)
+///     - [Vectorized Functions](#vectorized-functions)
 /// - [Table Function](#table-function)
 /// - [Registration and Invocation](#registration-and-invocation)
+///     - [Function Metadata](#function-metadata)
+///     - [Named Arguments](#named-arguments)
 /// - [Appendix: Type Matrix](#appendix-type-matrix)
 ///
 /// The following example demonstrates a simple usage:
@@ -81,14 +145,16 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// invocation. The signature follows this pattern:
 ///
 /// ```text
-/// name ( [arg_types],* [...] ) [ -> [setof] return_type ]
+/// name ( [arg_type [= default]],* [[type]...] ) [ -> [setof] return_type | table(name type, ..) ]
 /// ```
 ///
 /// Where `name` is the function name.
 ///
 /// `arg_types` is a comma-separated list of argument types. The allowed data types are listed in
 /// in the `name` column of the appendix's [type matrix]. Wildcards or `auto` can also be used, as
-/// explained below. If the function is variadic, the last argument can be denoted as `...`.
+/// explained below. If the function is variadic, the last argument can be denoted as `type...`,
+/// e.g. `concat(string, string...)`, or `...` for vectorized functions taking extra arguments of
+/// any type.
 ///
 /// When `setof` appears before the return type, this indicates that the function is a set-returning
 /// function (table function), meaning it can return multiple values instead of just one. For more
@@ -96,6 +162,23 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 ///
 /// If no return type is specified, the function returns `null`.
 ///
+/// ## Default Arguments
+///
+/// Trailing arguments can have default values, which are filled in when the input batch has fewer
+/// columns:
+///
+/// ```ignore
+/// #[function("round(float64, int32 = 0) -> float64")]
+/// fn round(x: f64, digits: i32) -> f64 {...}
+/// ```
+///
+/// The function is called with all arguments, so `round` can be evaluated on a batch of one or two
+/// columns. Default values are literals cast to the argument type: numbers, `true`, `false`,
+/// `null`, or strings quoted with `'`, e.g. `string = 'a,b'` or `date32 = '2024-01-01'`.
+/// Invalid numbers, booleans, strings and dates are rejected at compile time.
+/// Variadic functions and aggregate functions can not have default values, and arrays, maps,
+/// structs and polymorphic types can only default to `null`.
+///
 /// ## Multiple Function Definitions
 ///
 /// Multiple `#[function]` macros can be applied to a single generic Rust function to define
@@ -126,6 +209,22 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// fn add(x: Option<i32>, y: i32) -> i32 {...}
 /// ```
 ///
+/// ## Variadic Arguments
+///
+/// Variadic arguments of `type...` are passed as a slice after the other arguments. The function
+/// returns null if any of them is null, unless they are received as `&[Option<T>]`:
+///
+/// ```ignore
+/// #[function("concat(string, string...) -> string")]
+/// fn concat(first: &str, rest: &[&str]) -> String {...}
+///
+/// #[function("concat_ws(string, string...) -> string")]
+/// fn concat_ws(sep: &str, values: &[Option<&str>]) -> String {...}
+/// ```
+///
+/// All extra columns must have the declared type. Struct, `any` and `anyarray` types can not be
+/// variadic.
+///
 /// ## Return Value
 ///
 /// Similarly, the return value type can be one of the following:
@@ -135,11 +234,27 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// - `Result<T>`: Indicates that an error may occur, but a null value will not be returned.
 /// - `Result<Option<T>>`: Indicates that a null value may be returned, and an error may also occur.
 ///
+/// If the function returns `Result`, the output batch contains an extra `error` column of type
+/// `Struct<row: int32, code: int32, message: utf8, details: utf8>`, where `row` is the index of the
+/// input row. The error type must implement `Display`. If it also implements [`ErrorCode`], its
+/// code and details are stored in the column. Otherwise, the code is 0.
+///
+/// If the caller sets `ErrorPolicy::FailFast` in the [context](#context), scalar functions return
+/// an error on the first failed row instead, and the error column is always null. Table functions
+/// yield the error and end the iteration.
+///
+/// [`ErrorCode`]: https://docs.rs/arrow_udf/latest/arrow_udf/trait.ErrorCode.html
+///
 /// ## Optimization
 ///
-/// When all input and output types of the function are *primitive type* (int2, int4, int8, float4, float8)
-/// and do not contain any Option or Result, the `#[function]` macro will automatically
-/// generate SIMD vectorized execution code.
+/// When all input and output types of the function are *primitive type* (int*, uint*, float*),
+/// `boolean`, `date32`, `timestamp` or `timestamptz`, and the arguments are not `Option`,
+/// the `#[function]` macro will automatically generate vectorized execution code.
+/// The null bitmap of the output is computed by the bitwise AND of the validity of all inputs.
+///
+/// If the function returns `T` and takes at most 2 arguments of *primitive type*, it is also
+/// evaluated on null slots so that the loop can be auto-vectorized by the compiler.
+/// Otherwise, the function is only evaluated on valid slots.
 ///
 /// Therefore, try to avoid returning `Option` and `Result` whenever possible.
 ///
@@ -179,6 +294,71 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// }
 /// ```
 ///
+/// ## Async Functions
+///
+/// Scalar functions can be `async`. The generated function returns a future of the output batch,
+/// and can be registered as an [`AsyncScalarFunction`]:
+///
+/// ```ignore
+/// #[function("lookup(int64) -> string", concurrency = "32")]
+/// async fn lookup(key: i64) -> Result<Option<String>> {
+///     CACHE.get(key).await
+/// }
+///
+/// let output = lookup_int64_string_eval(&input).await?;
+/// ```
+///
+/// Rows are evaluated concurrently, and the output preserves the order of input rows.
+/// The `concurrency` attribute limits the number of rows being evaluated at the same time.
+/// It defaults to 16, and can be overridden by the caller with `Context::with_concurrency`.
+/// Async functions are not exported through FFI, and can not be table functions or use the
+/// writer style.
+///
+/// [`AsyncScalarFunction`]: https://docs.rs/arrow_udf/latest/arrow_udf/type.AsyncScalarFunction.html
+///
+/// ## Context
+///
+/// Functions can take a `&Context` argument after the SQL arguments to read the per-query
+/// [`Context`], such as the session time zone and configuration:
+///
+/// ```ignore
+/// #[function("timezone() -> string")]
+/// fn timezone(ctx: &Context) -> String {
+///     ctx.timezone().unwrap_or("UTC").to_string()
+/// }
+///
+/// let ctx = Context::new().with_timezone("Asia/Shanghai");
+/// let output = ctx.scope(|| timezone_string_eval(&input))?;
+/// ```
+///
+/// The context is installed by the caller with `Context::scope`. Outside a scope, an empty
+/// context is used. All generated functions return an error if the deadline of the context
+/// is exceeded when they are called.
+///
+/// [`Context`]: https://docs.rs/arrow_udf/latest/arrow_udf/struct.Context.html
+///
+/// ## Vectorized Functions
+///
+/// With the `vectorized` attribute, the function takes whole input arrays instead of values,
+/// and returns an array of the same length. This is useful for operations on the whole column,
+/// such as normalization or prefix sums:
+///
+/// ```ignore
+/// #[function("zscore(float64) -> float64", vectorized)]
+/// fn zscore(a: &Float64Array) -> Float64Array {
+///     let n = (a.len() - a.null_count()) as f64;
+///     let mean = a.iter().flatten().sum::<f64>() / n;
+///     let std = (a.iter().flatten().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+///     a.unary(|x| (x - mean) / std)
+/// }
+/// ```
+///
+/// Arguments are passed as the concrete array types in the [type matrix](#appendix-type-matrix),
+/// and variadic arguments are passed as `&[ArrayRef]`, or `&[&XxxArray]` if they are typed.
+/// Null values are not filtered out.
+/// If the function returns `Result`, an error fails all rows and is reported in the error column.
+/// Vectorized functions can not be table functions or use the writer style.
+///
 /// # Table Function
 ///
 /// A table function is a special kind of function that can return multiple values instead of just
@@ -200,6 +380,23 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// - `Result<impl Iterator<Item = T>>`
 /// - `Result<impl Iterator<Item = Result<Option<T>>>>`
 ///
+/// The output batch has a `row` column with the index of the input row, the value columns, and an
+/// `error` column if the function returns `Result`. Struct values are flattened into one column per
+/// field, like SQL `RETURNS TABLE` functions. The columns can also be declared with `table(..)`,
+/// in which case the iterator yields tuples:
+///
+/// ```ignore
+/// #[function("split_pairs(string) -> table(key string, value int32)")]
+/// fn split_pairs(s: &str) -> impl Iterator<Item = (&str, Option<i32>)> {
+///     s.split(',').filter_map(|kv| kv.split_once('=')).map(|(k, v)| (k, v.parse().ok()))
+/// }
+/// ```
+///
+/// The return type in the signature is a struct of the columns.
+///
+/// The generated function returns an iterator of `Result<RecordBatch>`, where an error of building
+/// a batch ends the iteration.
+///
 /// # Registration and Invocation
 ///
 /// Every function defined by `#[function]` is automatically registered in the global function registry.
@@ -213,6 +410,53 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// let sig = REGISTRY.get("add", &[Int32, Int32], &Int32).unwrap();
 /// ```
 ///
+/// Or lookup the function by name and argument types only, which also returns the return type.
+/// This is useful for functions returning `any` or `anyarray`:
+///
+/// ```ignore
+/// let (sig, return_type) = REGISTRY.resolve("array_first", &[List(Int32)]).unwrap();
+/// ```
+///
+/// ## Function Metadata
+///
+/// The signature also describes the function for query planners and catalogs:
+///
+/// - `volatility`: `Immutable` by default. Functions with a `&Context` argument are `Stable`.
+///   Use the `stable` or `volatile` property to override it.
+/// - `strict`: whether the function returns null on any null argument without being called.
+///   It is `true` unless some argument is `Option` or the function is vectorized.
+/// - `description`: the doc comment of the Rust function.
+/// - `examples`: example invocations given by the `example` property, which can be repeated.
+///
+/// ```ignore
+/// /// Returns the greatest common divisor of two integers.
+/// #[function("gcd(int, int) -> int", example = "gcd(15, 25) = 5")]
+/// fn gcd(a: i32, b: i32) -> i32 { ... }
+///
+/// #[function("random() -> float64", volatile)]
+/// fn random() -> f64 { ... }
+/// ```
+///
+/// ## Named Arguments
+///
+/// Arguments are named after the parameters of the Rust function, and the names are stored in the
+/// fields of `arg_types`. Use the `arg_names` property to override them:
+///
+/// ```ignore
+/// #[function("repeat(string, int32) -> string", arg_names = "str, count")]
+/// fn repeat(s: &str, n: i32) -> String { ... }
+/// ```
+///
+/// Functions can then be called with named arguments, e.g. `repeat(count => 3, str => 'ab')`:
+///
+/// ```ignore
+/// let (sig, return_type) = REGISTRY.resolve_named("repeat", &[count, str]).unwrap();
+/// let input = sig.bind_named_args(&input)?;
+/// ```
+///
+/// `bind_named_args` reorders the columns of the input by name and fills omitted arguments
+/// with their default values. Variadic functions can not be called with named arguments.
+///
 /// # Appendix: Type Matrix
 ///
 /// ## Base Types
@@ -229,11 +473,27 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// | `date32`             | `date`             | [`chrono::NaiveDate`]          | [`chrono::NaiveDate`]          |
 /// | `time64`             | `time`             | [`chrono::NaiveTime`]          | [`chrono::NaiveTime`]          |
 /// | `timestamp`          |                    | [`chrono::NaiveDateTime`]      | [`chrono::NaiveDateTime`]      |
-/// | `timestamptz`        |                    | not supported yet              | not supported yet              |
+/// | `timestamptz`        |                    | [`chrono::DateTime<Utc>`]      | [`chrono::DateTime<Utc>`], `DateTime<FixedOffset>` |
 /// | `interval`           |                    | [`arrow_udf::types::Interval`] | [`arrow_udf::types::Interval`] |
+/// | `decimal(p, s)`      | `numeric(p, s)`    | [`rust_decimal::Decimal`]      | [`rust_decimal::Decimal`]      |
 /// | `string`             | `varchar`          | `&str`                         | `impl AsRef<str>`, e.g. `String`, `Box<str>`, `&str`     |
 /// | `binary`             | `bytea`            | `&[u8]`                        | `impl AsRef<[u8]>`, e.g. `Vec<u8>`, `Box<[u8]>`, `&[u8]` |
 ///
+/// `timestamptz` is mapped to `Timestamp(Microsecond, Some("UTC"))`, but arguments with any time zone are accepted.
+/// If a function returns `timestamptz`, the time zone of its first `timestamptz` argument is preserved in the output.
+///
+/// `decimal(p, s)` is mapped to `Decimal128(p, s)` if `p <= 38`, otherwise `Decimal256(p, s)`.
+/// The scale can not exceed 28, which is the limit of [`rust_decimal::Decimal`].
+/// Return values are rescaled to `s`.
+/// Values that do not fit in [`rust_decimal::Decimal`], and return values that do not fit in the
+/// precision `p`, are reported in the error column, which is present for all functions with
+/// `decimal(p, s)` arguments or return values.
+/// Such values can not be used as map keys, and in arguments they can only be nested in a list.
+///
+/// Dictionary-encoded arguments, as well as `Utf8View` and `BinaryView` arguments, are cast to the
+/// declared types before evaluation. Functions with a single non-`Option` argument that return `T`
+/// are evaluated once per distinct dictionary value, and return a dictionary-encoded array.
+///
 /// ## Extension Types
 ///
 /// We also support the following extension types that are not part of the Arrow data types:
@@ -243,6 +503,10 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// | `decimal`   | `arrowudf.decimal`  | [`rust_decimal::Decimal`]      | [`rust_decimal::Decimal`]      |
 /// | `json`      | `arrowudf.json`     | [`serde_json::Value`]          | [`serde_json::Value`]          |
 ///
+/// With the `serde` feature of `arrow-udf`, `json` arguments of type `Json<T>` are deserialized
+/// into `T`, and return values of type `Json<T>` are serialized from `T`. Arguments that can not be
+/// deserialized are reported in the error column.
+///
 /// ## Array Types
 ///
 /// | SQL type              | Rust type as argument     | Rust type as return value      |
@@ -257,19 +521,63 @@ pub fn struct_type(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 /// | `binary[]`            | [`&BinaryArray`]          | `impl Iterator<Item = &[u8]>`  |
 /// | `largestring[]`       | [`&LargeStringArray`]     | `impl Iterator<Item = &str>`   |
 /// | `largebinary[]`       | [`&LargeBinaryArray`]     | `impl Iterator<Item = &[u8]>`  |
-/// | `others[]`            | not supported yet         | not supported yet              |
+/// | `others[]`            | `impl Iterator<Item = Option<T>>` | `impl Iterator<Item = T>` |
+///
+/// `T` is the Rust type of the element type. For example, `date32[]` is read as
+/// `impl Iterator<Item = Option<NaiveDate>>` and `struct Point[]` as `impl Iterator<Item = Option<Point>>`.
+/// For nested lists, the inner list is read in the same way, e.g. `int32[][]` is read as
+/// `impl Iterator<Item = Option<&[i32]>>`. Return values can be any `IntoIterator`, and can be nested
+/// as well, e.g. `impl Iterator<Item = Vec<i32>>` for `int32[][]`.
 ///
 /// ## Composite Types
 ///
 /// | SQL type              | Rust type as argument     | Rust type as return value      |
 /// | --------------------- | ------------------------- | ------------------------------ |
 /// | `struct<..>`          | `UserDefinedStruct`       | `UserDefinedStruct`            |
+/// | `map<K,V>`            | `impl Iterator<Item = (K, Option<V>)>` | `impl IntoIterator<Item = (K, V)>` |
+///
+/// `K` and `V` are the Rust types of the key and value types. For example, `map<string,int64>`
+/// is read as `impl Iterator<Item = (&str, Option<i64>)>`. Map keys are non-nullable.
+///
+/// Functions taking or returning structs with `decimal(p, s)` fields also have an error column,
+/// where values that fail to convert are reported, see [base types](#base-types).
+///
+/// With the `serde` feature of `arrow-udf`, `Serde<T>` can be used as a struct type for any `T`
+/// implementing `Serialize` and `Deserialize`. Name it with a type alias to use it in signatures,
+/// e.g. `type OrderStruct = Serde<Order>;` and `struct OrderStruct`.
+///
+/// ## Polymorphic Types
+///
+/// | SQL type              | Aliases   | Rust type as argument     | Rust type as return value      |
+/// | --------------------- | --------- | ------------------------- | ------------------------------ |
+/// | `any`                 |           | `ArrayRef` of length 1    | `ArrayRef` of length 1         |
+/// | `anyarray`            | `any[]`   | `ArrayRef` of elements    | `ArrayRef` of elements         |
+///
+/// `any` accepts arguments of any type, and `anyarray` accepts lists of any type. In a function
+/// signature, all `any` arguments and the elements of `anyarray` arguments must have the same type.
+///
+/// The concrete return type is computed from the argument types by the `type_infer` function,
+/// with the signature `fn(&[DataType]) -> Result<DataType>`. If not specified, it is inferred
+/// from the first `any` or `anyarray` argument:
+///
+/// ```ignore
+/// #[function("array_first(anyarray) -> any")]
+/// fn array_first(array: ArrayRef) -> Option<ArrayRef> {
+///     (!array.is_empty()).then(|| array.slice(0, 1))
+/// }
+///
+/// #[function("to_int64(any) -> any", type_infer = "|_| Ok(DataType::Int64)")]
+/// fn to_int64(value: ArrayRef) -> Result<ArrayRef, ArrowError> {
+///     arrow_cast::cast(&value, &DataType::Int64)
+/// }
+/// ```
 ///
 /// [type matrix]: #appendix-type-matrix
 /// [`rust_decimal::Decimal`]: https://docs.rs/rust_decimal/1.33.1/rust_decimal/struct.Decimal.html
 /// [`chrono::NaiveDate`]: https://docs.rs/chrono/0.4.31/chrono/naive/struct.NaiveDate.html
 /// [`chrono::NaiveTime`]: https://docs.rs/chrono/0.4.31/chrono/naive/struct.NaiveTime.html
 /// [`chrono::NaiveDateTime`]: https://docs.rs/chrono/0.4.31/chrono/naive/struct.NaiveDateTime.html
+/// [`chrono::DateTime<Utc>`]: https://docs.rs/chrono/0.4.31/chrono/struct.DateTime.html
 /// [`arrow_udf::types::Interval`]: https://docs.rs/arrow_udf/0.1.0/arrow_udf/types/struct.Interval.html
 /// [`serde_json::Value`]: https://docs.rs/serde_json/1.0.108/serde_json/enum.Value.html
 /// [`&StringArray`]: https://docs.rs/arrow/50.0.0/arrow/array/type.StringArray.html
@@ -294,12 +602,116 @@ pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/// Defining an aggregate function on Arrow arrays.
+///
+/// The signature follows the same pattern as [`#[function]`](macro@function):
+///
+/// ```ignore
+/// #[aggregate("sum(int64) -> int64")]
+/// fn sum(state: i64, value: i64) -> i64 {
+///     state + value
+/// }
+/// ```
+///
+/// The Rust function accumulates one row into the state and returns the new state.
+/// Its first argument is the state, followed by the arguments in the signature.
+///
+/// The macro generates a constant of [`arrow_udf::AggregateFunction`] which operates on state arrays:
+///
+/// ```ignore
+/// let state = (sum_int64_int64_eval.create_state)()?;
+/// let state = (sum_int64_int64_eval.accumulate)(&state, &input)?;
+/// let output = (sum_int64_int64_eval.finish)(&state)?;
+/// ```
+///
+/// # Properties
+///
+/// The following properties can be specified after the signature:
+///
+/// - `state = "type"`: The type of the state. If not specified, it will be the same as the return type.
+/// - `init_state = "expr"`: A Rust expression for the initial state. If not specified, it will be NULL.
+/// - `merge = "function"`: A Rust function `fn(State, State) -> State` to merge two states.
+/// - `finish = "function"`: A Rust function `fn(State) -> T` to get the result from the state.
+///   If not specified, the state is returned as the result. In this case, the state type must be the same as the return type.
+/// - `append_only`: The function never retracts values.
+/// - `output`, `visibility`, `volatile`, `stable` and `example`: The same as [`#[function]`](macro@function).
+///
+/// For example:
+///
+/// ```ignore
+/// #[aggregate("count(int32) -> int64", init_state = "0i64", merge = "count_merge")]
+/// fn count(state: i64, _: i32) -> i64 {
+///     state + 1
+/// }
+///
+/// fn count_merge(state1: i64, state2: i64) -> i64 {
+///     state1 + state2
+/// }
+///
+/// #[aggregate("sum_i32(int32) -> int32", state = "int64", finish = "sum_i32_finish")]
+/// fn sum_i32(state: i64, value: i32) -> i64 {
+///     state + value as i64
+/// }
+///
+/// fn sum_i32_finish(state: i64) -> i32 {
+///     state.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+/// }
+/// ```
+///
+/// # Null State
+///
+/// If the state argument is `Option<T>`, the function is called with a null state at the beginning.
+/// Otherwise, if there is no `init_state`, the first accumulated value becomes the state. This
+/// requires the function to take exactly one argument besides the state.
+///
+/// The function may also return `Option<T>` to reset the state to null, or `Result` to abort the
+/// accumulation with an error.
+///
+/// # Retraction
+///
+/// If the last argument of the function is `retract: bool`, the function supports retraction and
+/// `accumulate_or_retract` will be generated:
+///
+/// ```ignore
+/// #[aggregate("sum(int64) -> int64", init_state = "0i64")]
+/// fn sum(state: i64, value: i64, retract: bool) -> i64 {
+///     if retract {
+///         state - value
+///     } else {
+///         state + value
+///     }
+/// }
+/// ```
+///
+/// [`arrow_udf::AggregateFunction`]: https://docs.rs/arrow_udf/latest/arrow_udf/struct.AggregateFunction.html
+#[proc_macro_attribute]
+pub fn aggregate(attr: TokenStream, item: TokenStream) -> TokenStream {
+    fn inner(attr: TokenStream, item: TokenStream) -> Result<TokenStream2> {
+        let fn_attr: FunctionAttr = syn::parse(attr)?;
+        let user_fn: UserFunctionAttr = syn::parse(item.clone())?;
+
+        let mut tokens: TokenStream2 = item.into();
+        for attr in fn_attr.expand() {
+            tokens.extend(attr.generate_aggregate_descriptor(&user_fn)?);
+        }
+        Ok(tokens)
+    }
+    match inner(attr, item) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 struct FunctionAttr {
     /// Function name
     name: String,
     /// Input argument types
     args: Vec<String>,
+    /// Default values of the last `defaults.len()` arguments
+    defaults: Vec<String>,
+    /// Argument names. If empty, the names of the Rust arguments are used.
+    arg_names: Vec<String>,
     /// Return type
     ret: String,
     /// Whether it is a table function
@@ -314,17 +726,30 @@ struct FunctionAttr {
     /// Initial state value for aggregate function.
     /// If not specified, it will be NULL.
     init_state: Option<String>,
+    /// Function to merge two states for aggregate function.
+    merge: Option<String>,
+    /// Function to get the result from state for aggregate function.
+    /// If not specified, the state is returned as the result.
+    finish: Option<String>,
     /// Type inference function.
     type_infer: Option<String>,
     /// Generic type.
     generic: Option<String>,
     /// Whether the function is volatile.
     volatile: bool,
+    /// Whether the function is stable.
+    stable: bool,
+    /// Example invocations of the function.
+    examples: Vec<String>,
+    /// Whether the user function takes whole arrays as arguments.
+    vectorized: bool,
     /// Generated batch function name.
     /// If not specified, the macro will not generate batch function.
     output: Option<String>,
     /// Customized function visibility.
     visibility: Option<String>,
+    /// The maximum number of rows evaluated concurrently by an async function.
+    concurrency: Option<String>,
 }
 
 /// Attributes from function signature `fn(..)`
@@ -333,6 +758,8 @@ struct FunctionAttr {
 struct UserFunctionAttr {
     /// Function name
     name: String,
+    /// The doc comment of the function.
+    doc: Option<String>,
     /// Whether the function is async.
     async_: bool,
     /// Whether contains argument `&Context`.
@@ -341,14 +768,22 @@ struct UserFunctionAttr {
     write: bool,
     /// Whether the last argument type is `retract: bool`.
     retract: bool,
+    /// Names of the arguments, excluding `&Context` and `&mut impl Write`.
+    arg_names: Vec<String>,
     /// Whether each argument type is `Option<T>`.
     args_option: Vec<bool>,
+    /// Whether each argument type is `&[Option<T>]`.
+    args_option_slice: Vec<bool>,
+    /// Whether each argument type is `Json<T>`.
+    args_json: Vec<bool>,
     /// If the first argument type is `&mut T`, then `Some(T)`.
     first_mut_ref_arg: Option<String>,
     /// The return type kind.
     return_type_kind: ReturnTypeKind,
     /// The kind of inner type `T` in `impl Iterator<Item = T>`
     iterator_item_kind: Option<ReturnTypeKind>,
+    /// Whether each element is `Option` if `T` in `impl Iterator<Item = T>` is a tuple.
+    iterator_item_tuple_option: Vec<bool>,
     /// The core return type without `Option` or `Result`.
     core_return_type: String,
     /// The number of generic types.
@@ -377,9 +812,9 @@ impl FunctionAttr {
     fn ident_name(&self) -> String {
         format!("{}_{}_{}", self.name, self.args.join("_"), self.ret)
             .replace("[]", "array")
-            .replace("...", "variadic")
-            .replace(['<', ' ', ',', ':'], "_")
-            .replace('>', "")
+            .replace("...", "_variadic")
+            .replace(['<', ' ', ',', ':', '('], "_")
+            .replace(['>', ')'], "")
             .replace("__", "_")
     }
 
@@ -395,6 +830,21 @@ impl FunctionAttr {
     }
 }
 
+impl FunctionAttr {
+    /// Returns true if the last argument is variadic, i.e. `...` or `type...`.
+    fn is_variadic(&self) -> bool {
+        self.args.last().is_some_and(|ty| ty.ends_with("..."))
+    }
+
+    /// Returns the type of variadic arguments, or `None` if they can be of any type.
+    fn variadic_type(&self) -> Option<&str> {
+        self.args
+            .last()?
+            .strip_suffix("...")
+            .filter(|ty| !ty.is_empty())
+    }
+}
+
 impl UserFunctionAttr {
     /// Returns true if the function is like `fn(T1, T2, .., Tn) -> T`.
     fn is_pure(&self) -> bool {
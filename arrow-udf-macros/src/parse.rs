@@ -15,6 +15,7 @@
 //! Parse the tokens of the macro.
 
 use quote::ToTokens;
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
 use syn::{LitStr, Token};
@@ -35,24 +36,134 @@ impl Parse for FunctionAttr {
         let (name, args) = name_args
             .split_once('(')
             .ok_or_else(|| Error::new_spanned(&sig, "expected '('"))?;
-        let args = args.trim_start().trim_end_matches([')', ' ']);
+        let args = args
+            .trim()
+            .strip_suffix(')')
+            .ok_or_else(|| Error::new_spanned(&sig, "expected ')'"))?
+            .trim();
         let (is_table_function, ret) = match ret.trim_start() {
             s if s.starts_with("setof") => (true, &s[5..]), // -> setof
             s if s.starts_with('>') => (true, &s[1..]),     // ->>
+            s if s.starts_with("table") => (true, s),       // -> table(..)
             _ => (false, ret),
         };
         parsed.name = name.trim().to_string();
-        parsed.args = if args.is_empty() {
-            vec![]
-        } else {
-            args.split(',')
-                .map(|s| types::normalize_type(s.trim()))
-                .collect()
-        };
+        if !args.is_empty() {
+            for arg in types::split_types(args) {
+                let (ty, default) = match arg.split_once('=') {
+                    Some((ty, default)) => (ty, Some(default.trim())),
+                    None => (arg, None),
+                };
+                let ty = types::normalize_type(ty.trim());
+                match default {
+                    Some(default) => {
+                        check_default(&ty, default, &sig)?;
+                        parsed.defaults.push(default.to_string());
+                    }
+                    None if !parsed.defaults.is_empty() && ty.ends_with("...") => {
+                        return Err(Error::new_spanned(
+                            &sig,
+                            "variadic functions can not have default values",
+                        ));
+                    }
+                    None if !parsed.defaults.is_empty() => {
+                        return Err(Error::new_spanned(
+                            &sig,
+                            "arguments after an argument with a default value must also have default values",
+                        ));
+                    }
+                    None => {}
+                }
+                parsed.args.push(ty);
+            }
+        }
         parsed.ret = types::normalize_type(ret.trim());
         parsed.is_table_function = is_table_function;
 
+        let fixed_args = &parsed.args[..parsed.args.len().saturating_sub(1)];
+        if fixed_args.iter().any(|ty| ty.ends_with("...")) {
+            return Err(Error::new_spanned(
+                &sig,
+                "only the last argument can be variadic",
+            ));
+        }
+        if parsed.ret.ends_with("...") {
+            return Err(Error::new_spanned(
+                &sig,
+                "the return type can not be variadic",
+            ));
+        }
+
+        let mut types = parsed.args.clone();
+        if parsed.ret.starts_with("table") {
+            let Some(columns) = types::table_columns(&parsed.ret).filter(|c| !c.is_empty()) else {
+                return Err(Error::new_spanned(
+                    &sig,
+                    format!(
+                        "invalid table type: {}. expected `table(name type, ..)`",
+                        parsed.ret
+                    ),
+                ));
+            };
+            for (_, ty) in columns {
+                if ty.starts_with("struct") || types::is_polymorphic(ty) {
+                    return Err(Error::new_spanned(
+                        &sig,
+                        format!("unsupported table column type: {ty}"),
+                    ));
+                }
+                types.push(ty.to_string());
+            }
+        } else {
+            types.push(parsed.ret.clone());
+        }
+        for ty in &types {
+            let ty = ty.trim_end_matches("...").trim_end_matches("[]");
+            if ty.starts_with("map<") && types::map_key_value(ty).is_none() {
+                return Err(Error::new_spanned(
+                    &sig,
+                    format!("invalid map type: {ty}. expected `map<key_type,value_type>`"),
+                ));
+            }
+            if !ty.starts_with("decimal(") {
+                continue;
+            }
+            match types::decimal_precision_scale(ty) {
+                Some((p, s)) if (1..=76).contains(&p) && (0..=28).contains(&s) && s as u8 <= p => {}
+                _ => {
+                    return Err(Error::new_spanned(
+                        &sig,
+                        format!("invalid decimal type: {ty}. precision must be in 1..=76 and scale in 0..=min(28, precision)"),
+                    ))
+                }
+            }
+        }
+        // values that may fail to convert are only supported at the top level of arguments or in
+        // lists, where errors are reported for the row, and never as map keys.
+        for ty in &types {
+            let ty = ty.trim_end_matches("...").trim_end_matches("[]");
+            let key_ty = types::map_key_value(ty).map(|(k, _)| k);
+            if key_ty.is_some_and(types::is_converted) {
+                return Err(Error::new_spanned(
+                    &sig,
+                    format!("unsupported map key type: {ty}"),
+                ));
+            }
+        }
+        for ty in &parsed.args {
+            let ty = ty.trim_end_matches("...");
+            let elem_ty = ty.strip_suffix("[]").unwrap_or(ty);
+            let nested = elem_ty.ends_with("[]") || elem_ty.starts_with("map<");
+            if nested && (elem_ty.contains("decimal(") || elem_ty.contains("struct ")) {
+                return Err(Error::new_spanned(
+                    &sig,
+                    format!("unsupported argument type: {ty}"),
+                ));
+            }
+        }
+
         if input.parse::<Token![,]>().is_err() {
+            check_type_infer(&parsed, &sig)?;
             return Ok(parsed);
         }
 
@@ -74,6 +185,10 @@ impl Parse for FunctionAttr {
                 parsed.state = Some(get_value()?);
             } else if meta.path().is_ident("init_state") {
                 parsed.init_state = Some(get_value()?);
+            } else if meta.path().is_ident("merge") {
+                parsed.merge = Some(get_value()?);
+            } else if meta.path().is_ident("finish") {
+                parsed.finish = Some(get_value()?);
             } else if meta.path().is_ident("type_infer") {
                 parsed.type_infer = Some(get_value()?);
             } else if meta.path().is_ident("generic") {
@@ -82,10 +197,22 @@ impl Parse for FunctionAttr {
                 parsed.output = Some(get_value()?);
             } else if meta.path().is_ident("volatile") {
                 parsed.volatile = true;
+            } else if meta.path().is_ident("stable") {
+                parsed.stable = true;
+            } else if meta.path().is_ident("arg_names") {
+                parsed.arg_names = (get_value()?.split(','))
+                    .map(|name| name.trim().to_string())
+                    .collect();
+            } else if meta.path().is_ident("example") {
+                parsed.examples.push(get_value()?);
+            } else if meta.path().is_ident("vectorized") {
+                parsed.vectorized = true;
             } else if meta.path().is_ident("append_only") {
                 parsed.append_only = true;
             } else if meta.path().is_ident("visibility") {
                 parsed.visibility = Some(get_value()?);
+            } else if meta.path().is_ident("concurrency") {
+                parsed.concurrency = Some(get_value()?);
             } else {
                 return Err(Error::new(
                     meta.span(),
@@ -93,19 +220,220 @@ impl Parse for FunctionAttr {
                 ));
             }
         }
+        let num_args = parsed.args.len() - parsed.is_variadic() as usize;
+        if !parsed.arg_names.is_empty() && parsed.arg_names.len() != num_args {
+            return Err(Error::new_spanned(
+                &sig,
+                format!(
+                    "expect {num_args} names in `arg_names`, but got {}",
+                    parsed.arg_names.len()
+                ),
+            ));
+        }
+        if parsed.volatile && parsed.stable {
+            return Err(Error::new_spanned(
+                &sig,
+                "`volatile` and `stable` can not be used together",
+            ));
+        }
+        check_type_infer(&parsed, &sig)?;
         Ok(parsed)
     }
 }
 
+/// Check that the default value is a literal of the argument type.
+fn check_default(ty: &str, default: &str, sig: &LitStr) -> Result<()> {
+    let is_null = default.eq_ignore_ascii_case("null");
+    let is_quoted = default.len() >= 2 && default.starts_with('\'') && default.ends_with('\'');
+    let is_unquoted = !default.is_empty()
+        && (default.chars())
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | '_'));
+    if !(is_null || is_quoted || is_unquoted) {
+        return Err(Error::new_spanned(
+            sig,
+            format!("invalid default value: {default}. expected a number, boolean, quoted string or null"),
+        ));
+    }
+    if ty.ends_with("...") {
+        return Err(Error::new_spanned(
+            sig,
+            "variadic arguments can not have default values",
+        ));
+    }
+    let nested = ty.ends_with("[]") || ty.starts_with("struct ") || ty.starts_with("map<");
+    if !is_null && (nested || types::is_polymorphic(ty)) {
+        return Err(Error::new_spanned(
+            sig,
+            format!("the default value of type {ty} can only be null"),
+        ));
+    }
+    if !is_null {
+        check_default_value(ty, default).map_err(|msg| {
+            Error::new_spanned(
+                sig,
+                format!("invalid default value {default} for type {ty}: {msg}"),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Check that a non-null default value can be cast to the type.
+///
+/// Numbers, booleans, strings and dates are parsed the same way as `arrow-cast` does when the
+/// defaults are filled. Other types are only checked at runtime.
+fn check_default_value(ty: &str, default: &str) -> std::result::Result<(), String> {
+    let quoted = default.len() >= 2 && default.starts_with('\'') && default.ends_with('\'');
+    let value = match quoted {
+        true => {
+            let value = &default[1..default.len() - 1];
+            if value.replace("''", "").contains('\'') {
+                return Err("quotes in strings must be escaped as ''".into());
+            }
+            value.replace("''", "'")
+        }
+        false => default.to_string(),
+    };
+    let valid = match ty {
+        "int8" => value.parse::<i8>().is_ok(),
+        "int16" => value.parse::<i16>().is_ok(),
+        "int32" => value.parse::<i32>().is_ok(),
+        "int64" => value.parse::<i64>().is_ok(),
+        "uint8" => value.parse::<u8>().is_ok(),
+        "uint16" => value.parse::<u16>().is_ok(),
+        "uint32" => value.parse::<u32>().is_ok(),
+        "uint64" => value.parse::<u64>().is_ok(),
+        "float32" | "float64" => value.parse::<f64>().is_ok(),
+        "boolean" => matches!(
+            value.to_ascii_lowercase().trim(),
+            "t" | "tr"
+                | "tru"
+                | "true"
+                | "y"
+                | "ye"
+                | "yes"
+                | "on"
+                | "1"
+                | "f"
+                | "fa"
+                | "fal"
+                | "fals"
+                | "false"
+                | "n"
+                | "no"
+                | "of"
+                | "off"
+                | "0"
+        ),
+        "string" | "largestring" | "binary" | "largebinary" if !quoted => {
+            return Err("expected a quoted string".into());
+        }
+        "date32" => is_valid_date(&value),
+        _ => true,
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(format!("can not parse {value:?}")),
+    }
+}
+
+/// Check if the string is a date in the format of `YYYY-MM-DD` or `YYYYMMDD`.
+///
+/// Longer strings are parsed as timestamps by `arrow-cast`, and only the date part is checked.
+fn is_valid_date(s: &str) -> bool {
+    let date = s.split(['T', ' ']).next().unwrap();
+    let parts: Vec<&str> = match date.len() == 8 && !date.contains('-') {
+        true => vec![&date[0..4], &date[4..6], &date[6..8]],
+        false => date.split('-').collect(),
+    };
+    let [year, month, day] = parts[..] else {
+        return false;
+    };
+    let all_digits = |s: &str, len: std::ops::RangeInclusive<usize>| {
+        len.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_digit())
+    };
+    if !all_digits(year, 4..=4) || !all_digits(month, 1..=2) || !all_digits(day, 1..=2) {
+        return false;
+    }
+    let (year, month, day) = (
+        year.parse::<u32>().unwrap(),
+        month.parse::<u32>().unwrap(),
+        day.parse::<u32>().unwrap(),
+    );
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// Check that the return type of `any` or `anyarray` can be inferred.
+fn check_type_infer(parsed: &FunctionAttr, sig: &LitStr) -> Result<()> {
+    let polymorphic_ret = types::is_polymorphic(&parsed.ret);
+    if parsed.type_infer.is_some() && !polymorphic_ret {
+        return Err(Error::new_spanned(
+            sig,
+            "`type_infer` can only be used for functions returning `any` or `anyarray`",
+        ));
+    }
+    if polymorphic_ret
+        && parsed.type_infer.is_none()
+        && !parsed.args.iter().any(|ty| types::is_polymorphic(ty))
+    {
+        return Err(Error::new_spanned(
+            sig,
+            "`type_infer` is required for functions returning `any` or `anyarray` without `any` or `anyarray` arguments",
+        ));
+    }
+    Ok(())
+}
+
 impl Parse for UserFunctionAttr {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
         let itemfn: syn::ItemFn = input.parse()?;
-        Ok(UserFunctionAttr::from(&itemfn.sig))
+        Ok(UserFunctionAttr {
+            doc: doc_comment(&itemfn.attrs),
+            ..UserFunctionAttr::from(&itemfn.sig)
+        })
     }
 }
 
+/// Returns the doc comment of the item, or `None` if it is empty.
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| {
+            let syn::Meta::NameValue(kv) = &attr.meta else {
+                return None;
+            };
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) = &kv.value
+            else {
+                return None;
+            };
+            let line = lit.value();
+            Some(
+                line.strip_prefix(' ')
+                    .unwrap_or(&line)
+                    .trim_end()
+                    .to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
 impl From<&syn::Signature> for UserFunctionAttr {
     fn from(sig: &syn::Signature) -> Self {
+        let mut iterator_item_tuple_option = vec![];
         let (return_type_kind, iterator_item_kind, core_return_type) = match &sig.output {
             syn::ReturnType::Default => (ReturnTypeKind::T, None, "()".into()),
             syn::ReturnType::Type(_, ty) => {
@@ -113,6 +441,11 @@ impl From<&syn::Signature> for UserFunctionAttr {
                 match strip_iterator(inner) {
                     Some(ty) => {
                         let (inner_kind, inner) = check_type(ty);
+                        if let syn::Type::Tuple(tuple) = inner {
+                            iterator_item_tuple_option = (tuple.elems.iter())
+                                .map(|ty| strip_outer_type(ty, "Option").is_some())
+                                .collect();
+                        }
                         (kind, Some(inner_kind), inner.to_token_stream().to_string())
                     }
                     None => (kind, None, inner.to_token_stream().to_string()),
@@ -121,14 +454,22 @@ impl From<&syn::Signature> for UserFunctionAttr {
         };
         UserFunctionAttr {
             name: sig.ident.to_string(),
+            doc: None,
             async_: sig.asyncness.is_some(),
             write: sig.inputs.iter().any(arg_is_write),
             context: sig.inputs.iter().any(arg_is_context),
             retract: last_arg_is_retract(sig),
+            arg_names: (sig.inputs.iter())
+                .filter(|arg| !arg_is_context(arg) && !arg_is_write(arg))
+                .map(arg_name)
+                .collect(),
             args_option: sig.inputs.iter().map(arg_is_option).collect(),
+            args_option_slice: sig.inputs.iter().map(arg_is_option_slice).collect(),
+            args_json: sig.inputs.iter().map(arg_is_json).collect(),
             first_mut_ref_arg: first_mut_ref_arg(sig),
             return_type_kind,
             iterator_item_kind,
+            iterator_item_tuple_option,
             core_return_type,
             generic: sig.generics.params.len(),
             return_type_span: sig.output.span(),
@@ -184,6 +525,17 @@ fn last_arg_is_retract(sig: &syn::Signature) -> bool {
     pat.ident.to_string().contains("retract")
 }
 
+/// Returns the name of the argument, or an empty string if it is not an identifier.
+fn arg_name(arg: &syn::FnArg) -> String {
+    let syn::FnArg::Typed(arg) = arg else {
+        return String::new();
+    };
+    let syn::Pat::Ident(pat) = &*arg.pat else {
+        return String::new();
+    };
+    pat.ident.unraw().to_string()
+}
+
 /// Check if the argument is `Option`.
 fn arg_is_option(arg: &syn::FnArg) -> bool {
     let syn::FnArg::Typed(arg) = arg else {
@@ -198,6 +550,40 @@ fn arg_is_option(arg: &syn::FnArg) -> bool {
     seg.ident == "Option"
 }
 
+/// Check if the argument is `&[Option<T>]`.
+fn arg_is_option_slice(arg: &syn::FnArg) -> bool {
+    let syn::FnArg::Typed(arg) = arg else {
+        return false;
+    };
+    let syn::Type::Reference(syn::TypeReference { elem, .. }) = arg.ty.as_ref() else {
+        return false;
+    };
+    let syn::Type::Slice(syn::TypeSlice { elem, .. }) = elem.as_ref() else {
+        return false;
+    };
+    let syn::Type::Path(path) = elem.as_ref() else {
+        return false;
+    };
+    let Some(seg) = path.path.segments.last() else {
+        return false;
+    };
+    seg.ident == "Option"
+}
+
+/// Check if the argument is `Json<T>`.
+fn arg_is_json(arg: &syn::FnArg) -> bool {
+    let syn::FnArg::Typed(arg) = arg else {
+        return false;
+    };
+    let syn::Type::Path(path) = arg.ty.as_ref() else {
+        return false;
+    };
+    let Some(seg) = path.path.segments.last() else {
+        return false;
+    };
+    seg.ident == "Json"
+}
+
 /// Returns `T` if the first argument (except `self`) is `&mut T`.
 fn first_mut_ref_arg(sig: &syn::Signature) -> Option<String> {
     let arg = match sig.inputs.first()? {
@@ -277,3 +663,34 @@ fn strip_iterator(ty: &syn::Type) -> Option<&syn::Type> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_default_value() {
+        assert!(check_default_value("int32", "0").is_ok());
+        assert!(check_default_value("int32", "-12").is_ok());
+        assert!(check_default_value("int32", "'7'").is_ok());
+        assert!(check_default_value("int8", "128").is_err());
+        assert!(check_default_value("int32", "abc").is_err());
+        assert!(check_default_value("uint32", "-1").is_err());
+        assert!(check_default_value("float64", "1.5").is_ok());
+        assert!(check_default_value("float64", "1.5.2").is_err());
+        assert!(check_default_value("boolean", "true").is_ok());
+        assert!(check_default_value("boolean", "OFF").is_ok());
+        assert!(check_default_value("boolean", "maybe").is_err());
+        assert!(check_default_value("string", "' '").is_ok());
+        assert!(check_default_value("string", "'it''s'").is_ok());
+        assert!(check_default_value("string", "'it's'").is_err());
+        assert!(check_default_value("string", "abc").is_err());
+        assert!(check_default_value("date32", "'2024-01-01'").is_ok());
+        assert!(check_default_value("date32", "'2024-2-29'").is_ok());
+        assert!(check_default_value("date32", "'20240101'").is_ok());
+        assert!(check_default_value("date32", "'2023-02-29'").is_err());
+        assert!(check_default_value("date32", "'x'").is_err());
+        // other types are checked at runtime
+        assert!(check_default_value("timestamp", "'2024-01-01 00:00:00'").is_ok());
+    }
+}
@@ -13,56 +13,41 @@
 // limitations under the License.
 
 use itertools::Itertools;
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
-use syn::{Data, DeriveInput, Result};
+use syn::{Data, DataEnum, DeriveInput, Generics, Result};
 
 use crate::{gen, types};
 
 pub fn gen(tokens: TokenStream) -> Result<TokenStream> {
     let input: DeriveInput = syn::parse2(tokens)?;
+    match &input.data {
+        Data::Struct(struct_) => gen_struct(&input, &struct_.fields),
+        Data::Enum(enum_) => gen_enum(&input, enum_),
+        Data::Union(_) => Err(syn::Error::new_spanned(input, "expect struct or enum")),
+    }
+}
 
+/// Generate `StructType` and `FromStructArray` for a struct or tuple struct.
+fn gen_struct(input: &DeriveInput, fields: &syn::Fields) -> Result<TokenStream> {
     let struct_name = &input.ident;
     let generics = &input.generics;
-    let Data::Struct(struct_) = &input.data else {
-        return Err(syn::Error::new_spanned(input, "expect struct"));
-    };
-
-    let fields = struct_
-        .fields
-        .iter()
-        .map(Field::parse)
-        .collect::<Result<Vec<Field>>>()?;
+    let (fields, skipped) = Field::parse_all(fields)?;
 
-    let fields0 = fields.iter().map(|f| gen::field(&f.name, &f.type_));
+    let fields0 = fields.iter().map(|f| f.arrow_field());
     let append_values = fields.iter().enumerate().map(|(i, f)| {
-        let field = &f.ident;
-        let append_value = gen::gen_append_value(&f.type_);
-        let append_null = gen::gen_append_null(&f.type_);
-        let builder_type = gen::builder_type(&f.type_);
-        match f.option {
-            false => quote! {{
-                let builder = builder.field_builder::<#builder_type>(#i).unwrap();
-                let v = self.#field;
-                #append_value
-            }},
-            true => quote! {{
-                let builder = builder.field_builder::<#builder_type>(#i).unwrap();
-                match self.#field {
-                    Some(v) => #append_value,
-                    None => #append_null,
-                }
-            }},
-        }
-    });
-    let append_nulls = fields.iter().enumerate().map(|(i, f)| {
-        let builder_type = gen::builder_type(&f.type_);
-        let append_null = gen::gen_append_null(&f.type_);
-        quote! {{
-            let builder = builder.field_builder::<#builder_type>(#i).unwrap();
-            #append_null
-        }}
+        let member = &f.member;
+        gen_append_field(i, f, quote! { self.#member })
     });
+    let fallible = gen_fallible(&fields);
+    let append_nulls = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| gen_append_field_null(i, f));
+    let (from_generics, lifetime) = from_generics(generics, &fields);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (from_impl_generics, _, from_where_clause) = from_generics.split_for_impl();
+    let read_values = fields.iter().enumerate().map(|(i, f)| gen_read_field(i, f));
     let static_name = format_ident!("{}_METADATA", struct_name.to_string().to_uppercase());
     let export_name = format!(
         "arrowudt_{}",
@@ -82,72 +67,644 @@ pub fn gen(tokens: TokenStream) -> Result<TokenStream> {
         #[export_name = #export_name]
         static #static_name: () = ();
 
-        impl #generics ::arrow_udf::types::StructType for #struct_name #generics {
+        impl #impl_generics ::arrow_udf::types::StructType for #struct_name #ty_generics #where_clause {
+            #fallible
             fn fields() -> ::arrow_udf::codegen::arrow_schema::Fields {
                 use ::arrow_udf::codegen::arrow_schema::{self, Field, TimeUnit, IntervalUnit};
                 vec![#(#fields0),*].into()
             }
-            fn append_to(self, builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder) {
+            fn append_to(self, builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder) -> ::arrow_udf::Result<()> {
+                use ::arrow_udf::codegen::arrow_array::builder::*;
+                let mut result = ::arrow_udf::Result::Ok(());
+                #(result = result.and(#append_values);)*
+                builder.append(result.is_ok());
+                result
+            }
+            fn append_null(builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder) {
+                use ::arrow_udf::codegen::arrow_array::builder::*;
+                #(#append_nulls)*
+                builder.append_null();
+            }
+        }
+
+        impl #from_impl_generics ::arrow_udf::types::FromStructArray<#lifetime> for #struct_name #ty_generics #from_where_clause {
+            fn from_struct_array(array: &#lifetime ::arrow_udf::codegen::arrow_array::StructArray, index: usize) -> ::arrow_udf::Result<Self> {
+                use ::arrow_udf::codegen::arrow_array;
+                use ::arrow_udf::codegen::arrow_array::array::*;
+                use ::arrow_udf::codegen::arrow_array::cast::AsArray;
+                use ::arrow_udf::codegen::chrono;
+                use ::arrow_udf::codegen::rust_decimal;
+                use ::arrow_udf::codegen::serde_json;
+                Ok(Self {
+                    #(#read_values,)*
+                    #(#skipped: Default::default(),)*
+                })
+            }
+        }
+    })
+}
+
+/// Generate `StructType` and `FromStructArray` for an enum.
+///
+/// Fieldless enums are encoded as dictionary-encoded strings of variant names.
+/// Other enums are encoded as dense unions, with a struct child for each variant with fields
+/// and a null child for each variant without fields.
+fn gen_enum(input: &DeriveInput, enum_: &DataEnum) -> Result<TokenStream> {
+    let enum_name = &input.ident;
+    let generics = &input.generics;
+    if enum_.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "expect at least one variant",
+        ));
+    }
+    if enum_.variants.len() > i8::MAX as usize {
+        return Err(syn::Error::new_spanned(input, "too many variants"));
+    }
+    let variants = enum_
+        .variants
+        .iter()
+        .map(Variant::parse)
+        .collect::<Result<Vec<Variant>>>()?;
+    let all_fields = variants.iter().flat_map(|v| &v.fields).collect_vec();
+    let (from_generics, lifetime) = from_generics(generics, all_fields.iter().copied());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (from_impl_generics, _, from_where_clause) = from_generics.split_for_impl();
+    let patterns = variants.iter().map(|v| v.pattern()).collect_vec();
+    let constructors = variants.iter().map(|v| v.constructor()).collect_vec();
+
+    let fieldless = variants.iter().all(|v| v.fields.is_empty() && !v.tuple);
+    if fieldless {
+        let names = variants.iter().map(|v| &v.name).collect_vec();
+        let indices = (0..variants.len() as i32).collect_vec();
+        let field = gen::field("variant", "int32");
+        return Ok(quote! {
+            impl #impl_generics ::arrow_udf::types::StructType for #enum_name #ty_generics #where_clause {
+                fn fields() -> ::arrow_udf::codegen::arrow_schema::Fields {
+                    use ::arrow_udf::codegen::arrow_schema;
+                    vec![#field].into()
+                }
+                fn append_to(self, builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder) -> ::arrow_udf::Result<()> {
+                    use ::arrow_udf::codegen::arrow_array::builder::*;
+                    let index = match self {
+                        #(#patterns => #indices,)*
+                    };
+                    builder.field_builder::<Int32Builder>(0).unwrap().append_value(index);
+                    builder.append(true);
+                    Ok(())
+                }
+                fn append_null(builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder) {
+                    use ::arrow_udf::codegen::arrow_array::builder::*;
+                    builder.field_builder::<Int32Builder>(0).unwrap().append_null();
+                    builder.append_null();
+                }
+                fn data_type() -> ::arrow_udf::codegen::arrow_schema::DataType {
+                    use ::arrow_udf::codegen::arrow_schema::DataType;
+                    DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+                }
+                fn encode(array: ::arrow_udf::codegen::arrow_array::StructArray) -> ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::ArrayRef> {
+                    ::arrow_udf::codegen::struct_to_dictionary(array, &[#(#names),*])
+                }
+                fn decode(array: &::arrow_udf::codegen::arrow_array::ArrayRef) -> ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::ArrayRef> {
+                    ::arrow_udf::codegen::dictionary_to_struct(array, &[#(#names),*], Self::fields())
+                }
+            }
+
+            impl #from_impl_generics ::arrow_udf::types::FromStructArray<#lifetime> for #enum_name #ty_generics #from_where_clause {
+                fn from_struct_array(array: &#lifetime ::arrow_udf::codegen::arrow_array::StructArray, index: usize) -> ::arrow_udf::Result<Self> {
+                    use ::arrow_udf::codegen::arrow_array::cast::AsArray;
+                    use ::arrow_udf::codegen::arrow_array::types::Int32Type;
+                    Ok(match array.column(0).as_primitive::<Int32Type>().value(index) {
+                        #(#indices => #constructors,)*
+                        i => return Err(::arrow_udf::Error::InvalidArgumentError(format!(
+                            "invalid variant index: {i}"
+                        ))),
+                    })
+                }
+            }
+        });
+    }
+
+    // the first field is the type id, followed by a field for each variant
+    let variant_fields = variants.iter().map(|v| {
+        let name = &v.name;
+        if v.fields.is_empty() {
+            return gen::field(name, "null");
+        }
+        let fields = v.fields.iter().map(|f| f.arrow_field());
+        quote! {
+            arrow_schema::Field::new(#name, arrow_schema::DataType::Struct(vec![#(#fields),*].into()), true)
+        }
+    });
+    let type_id_field = gen::field("type_id", "int8");
+    // append a null value for the variant at `index`
+    let append_variant_null = |index: usize, v: &Variant| {
+        let child = index + 1;
+        if v.fields.is_empty() {
+            return quote! {
+                builder.field_builder::<NullBuilder>(#child).unwrap().append_null();
+            };
+        }
+        let append_nulls = v
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| gen_append_field_null(i, f));
+        quote! {{
+            let builder = builder.field_builder::<StructBuilder>(#child).unwrap();
+            #(#append_nulls)*
+            builder.append_null();
+        }}
+    };
+    let append_values = (0..variants.len()).map(|index| {
+        let type_id = index as i8;
+        let children = variants.iter().enumerate().map(|(j, v)| {
+            if j != index {
+                return append_variant_null(j, v);
+            }
+            let child = j + 1;
+            if v.fields.is_empty() {
+                return quote! {
+                    builder.field_builder::<NullBuilder>(#child).unwrap().append_empty_value();
+                };
+            }
+            let append_fields = v.fields.iter().enumerate().map(|(i, f)| {
+                let binding = &f.binding;
+                gen_append_field(i, f, quote! { #binding })
+            });
+            quote! {{
+                let builder = builder.field_builder::<StructBuilder>(#child).unwrap();
+                #(result = result.and(#append_fields);)*
+                builder.append(result.is_ok());
+            }}
+        });
+        quote! {{
+            builder.field_builder::<Int8Builder>(0).unwrap().append_value(#type_id);
+            #(#children)*
+        }}
+    });
+    // null values are stored as a null value of the first variant with fields
+    let null_type_id = variants.iter().position(|v| !v.fields.is_empty()).unwrap() as i8;
+    let append_nulls = variants
+        .iter()
+        .enumerate()
+        .map(|(j, v)| append_variant_null(j, v));
+    let type_ids = (0..variants.len() as i8).collect_vec();
+    let fallible = gen_fallible(all_fields.iter().copied());
+    let read_variants = variants.iter().enumerate().map(|(index, v)| {
+        let path = &v.path;
+        let child = index + 1;
+        let read_fields = v
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| gen_read_field(i, f));
+        if v.fields.is_empty() {
+            return v.constructor();
+        }
+        let skipped = &v.skipped;
+        quote! {{
+            let array = array.column(#child).as_struct();
+            #path {
+                #(#read_fields,)*
+                #(#skipped: Default::default(),)*
+            }
+        }}
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::arrow_udf::types::StructType for #enum_name #ty_generics #where_clause {
+            #fallible
+            fn fields() -> ::arrow_udf::codegen::arrow_schema::Fields {
+                use ::arrow_udf::codegen::arrow_schema::{self, Field, TimeUnit, IntervalUnit};
+                vec![#type_id_field, #(#variant_fields),*].into()
+            }
+            fn append_to(self, builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder) -> ::arrow_udf::Result<()> {
                 use ::arrow_udf::codegen::arrow_array::builder::*;
-                #(#append_values)*
-                builder.append(true);
+                let mut result = ::arrow_udf::Result::Ok(());
+                match self {
+                    #(#patterns => #append_values)*
+                }
+                builder.append(result.is_ok());
+                result
             }
             fn append_null(builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder) {
                 use ::arrow_udf::codegen::arrow_array::builder::*;
+                builder.field_builder::<Int8Builder>(0).unwrap().append_value(#null_type_id);
                 #(#append_nulls)*
                 builder.append_null();
             }
+            fn data_type() -> ::arrow_udf::codegen::arrow_schema::DataType {
+                use ::arrow_udf::codegen::arrow_schema::{DataType, UnionMode};
+                DataType::Union(::arrow_udf::codegen::union_fields(&Self::fields()), UnionMode::Dense)
+            }
+            fn encode(array: ::arrow_udf::codegen::arrow_array::StructArray) -> ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::ArrayRef> {
+                ::arrow_udf::codegen::struct_to_union(array)
+            }
+            fn decode(array: &::arrow_udf::codegen::arrow_array::ArrayRef) -> ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::ArrayRef> {
+                ::arrow_udf::codegen::union_to_struct(array, Self::fields())
+            }
+        }
+
+        impl #from_impl_generics ::arrow_udf::types::FromStructArray<#lifetime> for #enum_name #ty_generics #from_where_clause {
+            fn from_struct_array(array: &#lifetime ::arrow_udf::codegen::arrow_array::StructArray, index: usize) -> ::arrow_udf::Result<Self> {
+                use ::arrow_udf::codegen::arrow_array;
+                use ::arrow_udf::codegen::arrow_array::array::*;
+                use ::arrow_udf::codegen::arrow_array::cast::AsArray;
+                use ::arrow_udf::codegen::arrow_array::types::Int8Type;
+                use ::arrow_udf::codegen::chrono;
+                use ::arrow_udf::codegen::rust_decimal;
+                use ::arrow_udf::codegen::serde_json;
+                Ok(match array.column(0).as_primitive::<Int8Type>().value(index) {
+                    #(#type_ids => #read_variants,)*
+                    t => return Err(::arrow_udf::Error::InvalidArgumentError(format!(
+                        "invalid type id: {t}"
+                    ))),
+                })
+            }
         }
     })
 }
 
+/// Returns the generics and lifetime of the `FromStructArray` trait.
+///
+/// The first lifetime of the type is reused if any.
+/// Nested structs should also implement `FromStructArray`.
+fn from_generics<'f>(
+    generics: &Generics,
+    fields: impl IntoIterator<Item = &'f Field>,
+) -> (Generics, syn::Lifetime) {
+    let mut from_generics = generics.clone();
+    let lifetime = match generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lifetime = syn::Lifetime::new("'a", Span::call_site());
+            from_generics
+                .params
+                .insert(0, syn::LifetimeParam::new(lifetime.clone()).into());
+            lifetime
+        }
+    };
+    let where_clause = from_generics.make_where_clause();
+    for f in fields {
+        if f.type_.starts_with("struct ") {
+            let ty = &f.rust_type;
+            where_clause
+                .predicates
+                .push(syn::parse_quote! { #ty: ::arrow_udf::types::FromStructArray<#lifetime> });
+        }
+    }
+    (from_generics, lifetime)
+}
+
+/// Generate code to append `value` of the `index`-th field to `builder: &mut StructBuilder`.
+fn gen_append_field(index: usize, f: &Field, value: TokenStream) -> TokenStream {
+    let append_value = gen::gen_append_value(&f.type_);
+    let append_null = gen::gen_append_null(&f.type_);
+    let builder_type = gen::builder_type(&f.type_);
+    match f.option {
+        false => quote! {{
+            let builder = builder.field_builder::<#builder_type>(#index).unwrap();
+            let v = #value;
+            #append_value
+        }},
+        true => quote! {{
+            let builder = builder.field_builder::<#builder_type>(#index).unwrap();
+            match #value {
+                Some(v) => #append_value,
+                None => {
+                    #append_null;
+                    ::arrow_udf::Result::Ok(())
+                }
+            }
+        }},
+    }
+}
+
+/// Generate the `FALLIBLE` constant of `StructType` if any field may fail to convert.
+fn gen_fallible<'f>(fields: impl IntoIterator<Item = &'f Field>) -> Option<TokenStream> {
+    let fallible = fields
+        .into_iter()
+        .filter_map(|f| {
+            if types::is_fallible(f.type_.trim_end_matches("[]")) || f.is_serde_json() {
+                Some(quote! { true })
+            } else if f.type_.starts_with("struct ") {
+                let ty = &f.rust_type;
+                Some(quote! { <#ty as ::arrow_udf::types::StructType>::FALLIBLE })
+            } else {
+                None
+            }
+        })
+        .collect_vec();
+    (!fallible.is_empty()).then(|| quote! { const FALLIBLE: bool = #(#fallible)||*; })
+}
+
+/// Generate code to append null to the `index`-th field of `builder: &mut StructBuilder`.
+fn gen_append_field_null(index: usize, f: &Field) -> TokenStream {
+    let builder_type = gen::builder_type(&f.type_);
+    let append_null = gen::gen_append_null(&f.type_);
+    quote! {{
+        let builder = builder.field_builder::<#builder_type>(#index).unwrap();
+        #append_null
+    }}
+}
+
+/// Generate code to initialize the `index`-th field from `array: &StructArray`.
+fn gen_read_field(index: usize, f: &Field) -> TokenStream {
+    let member = &f.member;
+    let read_value = match f.is_serde_json() {
+        true => quote! {{
+            let v = array.as_string::<i32>().value(index);
+            ::arrow_udf::codegen::parse_json(v)?
+        }},
+        false => gen_read_value(&f.type_),
+    };
+    match f.option {
+        false => quote! {
+            #member: {
+                let array = array.column(#index);
+                #read_value
+            }
+        },
+        true => quote! {
+            #member: {
+                let array = array.column(#index);
+                match array.is_null(index) {
+                    true => None,
+                    false => Some(#read_value),
+                }
+            }
+        },
+    }
+}
+
+/// Generate code to read the value at `index` of `array: &ArrayRef` as the field type.
+fn gen_read_value(ty: &str) -> TokenStream {
+    if ty == "null" {
+        return quote! { () };
+    }
+    let Some(elem_ty) = ty.strip_suffix("[]") else {
+        if ty.starts_with("struct ") {
+            return quote! {
+                ::arrow_udf::types::FromStructArray::from_struct_array(array.as_struct(), index)?
+            };
+        }
+        let array_type = format_ident!("{}", types::array_type(ty));
+        let transform = gen::transform_input(&format_ident!("v"), ty);
+        let transform = match types::is_fallible(ty) {
+            true => quote! { #transform? },
+            false => transform,
+        };
+        return quote! {{
+            let v = array.as_any().downcast_ref::<#array_type>().expect("downcast struct field").value(index);
+            (#transform).into()
+        }};
+    };
+    // list values are read from the offsets of the child array
+    let read_elements = if types::is_primitive(elem_ty) {
+        let array_type = format_ident!("{}", types::array_type(elem_ty));
+        quote! {
+            let values = list.values().as_any().downcast_ref::<#array_type>().expect("downcast list values");
+            values.values()[start..end].into()
+        }
+    } else if elem_ty.starts_with("struct ") {
+        quote! {
+            let values = list.values().as_struct();
+            (start..end)
+                .map(|j| ::arrow_udf::types::FromStructArray::from_struct_array(values, j))
+                .collect::<::arrow_udf::Result<_>>()?
+        }
+    } else if types::is_fallible(elem_ty) {
+        let array_type = format_ident!("{}", types::array_type(elem_ty));
+        let transform = gen::transform_input(&format_ident!("v"), elem_ty);
+        quote! {
+            let values = list.values().as_any().downcast_ref::<#array_type>().expect("downcast list values");
+            (start..end).map(|j| {
+                let v = values.value(j);
+                #transform.map(Into::into)
+            }).collect::<::arrow_udf::Result<_>>()?
+        }
+    } else {
+        let array_type = format_ident!("{}", types::array_type(elem_ty));
+        let transform = gen::transform_input(&format_ident!("v"), elem_ty);
+        quote! {
+            let values = list.values().as_any().downcast_ref::<#array_type>().expect("downcast list values");
+            (start..end).map(|j| {
+                let v = values.value(j);
+                (#transform).into()
+            }).collect()
+        }
+    };
+    quote! {{
+        let list = array.as_list::<i32>();
+        let start = list.value_offsets()[index] as usize;
+        let end = list.value_offsets()[index + 1] as usize;
+        #read_elements
+    }}
+}
+
 /// Parsed field of a struct.
 #[derive(Debug)]
 struct Field {
-    /// The identifier in source code.
-    ident: syn::Ident,
-    /// The name of the field. `r#` is stripped.
+    /// The member in source code. e.g. `key` or `0`.
+    member: syn::Member,
+    /// The identifier to bind the field in patterns. e.g. `key` or `f0`.
+    binding: syn::Ident,
+    /// The name of the field. `r#` is stripped. Fields of tuple structs are named `f0`, `f1`, ...
     name: String,
     /// The normalized type of the field. e.g. `int4` for `i32`.
     type_: String,
     /// Whether the field is nullable.
     option: bool,
+    /// The Rust type of the field with `Option` and `Vec` stripped.
+    rust_type: syn::Type,
 }
 
 impl Field {
-    /// Parses a field from a `syn::Field`.
-    fn parse(field: &syn::Field) -> Result<Self> {
-        let ident = field
-            .ident
-            .clone()
-            .ok_or_else(|| syn::Error::new_spanned(field, "expect field name"))?;
-        let mut name = ident.to_string();
-        if name.starts_with("r#") {
-            // strip leading `r#`
-            name = name[2..].to_string();
+    /// Parses all fields of a struct or an enum variant.
+    ///
+    /// Returns the fields and the members of skipped fields.
+    fn parse_all(fields: &syn::Fields) -> Result<(Vec<Self>, Vec<syn::Member>)> {
+        let mut parsed = vec![];
+        let mut skipped = vec![];
+        for (i, field) in fields.iter().enumerate() {
+            let attr = FieldAttr::parse(&field.attrs)?;
+            if attr.skip {
+                skipped.push(match &field.ident {
+                    Some(ident) => syn::Member::Named(ident.clone()),
+                    None => syn::Member::Unnamed(i.into()),
+                });
+                continue;
+            }
+            parsed.push(Self::parse(i, field, attr)?);
+        }
+        Ok((parsed, skipped))
+    }
+
+    /// Parses the `index`-th field from a `syn::Field`.
+    fn parse(index: usize, field: &syn::Field, attr: FieldAttr) -> Result<Self> {
+        let (member, binding, mut name) = match &field.ident {
+            Some(ident) => {
+                let mut name = ident.to_string();
+                if name.starts_with("r#") {
+                    // strip leading `r#`
+                    name = name[2..].to_string();
+                }
+                (syn::Member::Named(ident.clone()), ident.clone(), name)
+            }
+            None => (
+                syn::Member::Unnamed(index.into()),
+                format_ident!("f{index}"),
+                format!("f{index}"),
+            ),
+        };
+        if let Some(rename) = attr.rename {
+            name = rename;
         }
         let ty = &field.ty;
         let (option, ty) = match strip_outer_type(ty, "Option") {
             Some(ty) => (true, ty),
             None => (false, ty),
         };
-        let (list, ty) = match strip_outer_type(ty, "Vec") {
-            // exclude `Vec<u8>` from list
-            Some(ty) if ty.to_token_stream().to_string() != "u8" => (true, ty),
-            _ => (false, ty),
+        let (type_, ty) = match attr.ty {
+            Some(type_) => {
+                let type_ = types::normalize_type(&type_);
+                if types::is_polymorphic(&type_) || type_.starts_with("map<") {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        format!("unsupported field type: {type_}"),
+                    ));
+                }
+                let ty = match type_.ends_with("[]") {
+                    true => strip_outer_type(ty, "Vec").unwrap_or(ty),
+                    false => ty,
+                };
+                (type_, ty)
+            }
+            None => {
+                let (list, ty) = match strip_outer_type(ty, "Vec") {
+                    // exclude `Vec<u8>` from list
+                    Some(ty) if ty.to_token_stream().to_string() != "u8" => (true, ty),
+                    _ => (false, ty),
+                };
+                let mut type_ =
+                    types::type_of(&ty.to_token_stream().to_string().replace(' ', "")).to_string();
+                if list {
+                    type_ += "[]";
+                }
+                (type_, ty)
+            }
         };
-        let mut type_ =
-            types::type_of(&ty.to_token_stream().to_string().replace(' ', "")).to_string();
-        if list {
-            type_ += "[]";
-        }
         Ok(Self {
-            ident,
+            member,
+            binding,
             name,
             type_,
             option,
+            rust_type: ty.clone(),
         })
     }
+
+    /// Returns the arrow `Field` of the field.
+    ///
+    /// Only `Option` fields are nullable, except that `null` fields are always nullable.
+    fn arrow_field(&self) -> TokenStream {
+        let nullable = self.option || self.type_ == "null";
+        gen::field_with_nullable(&self.name, &self.type_, nullable)
+    }
+
+    /// Returns true if the field is a `json` of type `Json<T>`.
+    fn is_serde_json(&self) -> bool {
+        self.type_ == "json"
+            && matches!(&self.rust_type, syn::Type::Path(path)
+                if path.path.segments.last().is_some_and(|seg| seg.ident == "Json"))
+    }
+}
+
+/// Parsed variant of an enum.
+#[derive(Debug)]
+struct Variant {
+    /// The path of the variant. e.g. `Self::Circle`.
+    path: TokenStream,
+    /// The name of the variant.
+    name: String,
+    /// Whether the variant is a tuple variant. e.g. `Circle(f64)`.
+    tuple: bool,
+    /// The fields of the variant.
+    fields: Vec<Field>,
+    /// The members of skipped fields.
+    skipped: Vec<syn::Member>,
+}
+
+impl Variant {
+    /// Parses a variant from a `syn::Variant`.
+    fn parse(variant: &syn::Variant) -> Result<Self> {
+        let ident = &variant.ident;
+        let (fields, skipped) = Field::parse_all(&variant.fields)?;
+        Ok(Self {
+            path: quote! { Self::#ident },
+            name: ident.to_string(),
+            tuple: matches!(variant.fields, syn::Fields::Unnamed(_)),
+            fields,
+            skipped,
+        })
+    }
+
+    /// Returns the pattern that binds all fields of the variant.
+    /// e.g. `Self::Rect { width, height }` or `Self::Circle { 0: f0 }`.
+    fn pattern(&self) -> TokenStream {
+        let path = &self.path;
+        let fields = self.fields.iter().map(|f| match &f.member {
+            syn::Member::Named(ident) => quote! { #ident },
+            member => {
+                let binding = &f.binding;
+                quote! { #member: #binding }
+            }
+        });
+        let rest = (!self.skipped.is_empty()).then(|| quote! { .. });
+        quote! { #path { #(#fields,)* #rest } }
+    }
+
+    /// Returns the expression to construct the variant without fields.
+    /// Skipped fields are set to default values.
+    fn constructor(&self) -> TokenStream {
+        let path = &self.path;
+        let skipped = &self.skipped;
+        quote! { #path { #(#skipped: Default::default()),* } }
+    }
+}
+
+/// Attributes of a field. e.g. `#[struct_type(rename = "type", ty = "json")]`.
+#[derive(Debug, Default)]
+struct FieldAttr {
+    /// The name of the field in the struct type.
+    rename: Option<String>,
+    /// Whether the field is skipped. Skipped fields are set to `Default::default()` when reading.
+    skip: bool,
+    /// Overrides the type inferred from the Rust type.
+    ty: Option<String>,
+}
+
+impl FieldAttr {
+    /// Parses `#[struct_type(...)]` attributes.
+    fn parse(attrs: &[syn::Attribute]) -> Result<Self> {
+        let mut parsed = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("struct_type")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    parsed.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.path.is_ident("skip") {
+                    parsed.skip = true;
+                } else if meta.path.is_ident("ty") {
+                    parsed.ty = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else {
+                    return Err(meta.error("unknown attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
 }
 
 /// Check if the type is `type_<T>` and return `T`.
@@ -187,4 +744,14 @@ mod tests {
         let expected = expect_test::expect_file!["testdata/struct.output.rs"];
         expected.assert_eq(&output);
     }
+
+    #[test]
+    fn test_enum_type() {
+        let code = include_str!("testdata/enum.input.rs");
+        let input: TokenStream = str::parse(code).unwrap();
+        let output = super::gen(input).unwrap();
+        let output = pretty_print(output);
+        let expected = expect_test::expect_file!["testdata/enum.output.rs"];
+        expected.assert_eq(&output);
+    }
 }
@@ -0,0 +1,7 @@
+#[derive(StructType)]
+enum Shape<'a> {
+    Circle(f64),
+    Rect { width: f64, height: f64 },
+    Label(&'a str, Option<Point>),
+    Empty,
+}
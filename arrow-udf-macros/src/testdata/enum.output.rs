@@ -0,0 +1,430 @@
+impl<'a> ::arrow_udf::types::StructType for Shape<'a> {
+    const FALLIBLE: bool = <Point as ::arrow_udf::types::StructType>::FALLIBLE;
+    fn fields() -> ::arrow_udf::codegen::arrow_schema::Fields {
+        use ::arrow_udf::codegen::arrow_schema::{self, Field, TimeUnit, IntervalUnit};
+        vec![
+            arrow_schema::Field::new("type_id", arrow_schema::DataType::Int8, true),
+            arrow_schema::Field::new("Circle",
+            arrow_schema::DataType::Struct(vec![arrow_schema::Field::new("f0",
+            arrow_schema::DataType::Float64, false)] .into()), true),
+            arrow_schema::Field::new("Rect",
+            arrow_schema::DataType::Struct(vec![arrow_schema::Field::new("width",
+            arrow_schema::DataType::Float64, false), arrow_schema::Field::new("height",
+            arrow_schema::DataType::Float64, false)] .into()), true),
+            arrow_schema::Field::new("Label",
+            arrow_schema::DataType::Struct(vec![arrow_schema::Field::new("f0",
+            arrow_schema::DataType::Utf8, false), arrow_schema::Field::new("f1",
+            arrow_schema::DataType::Struct(Point::fields()), true)] .into()), true),
+            arrow_schema::Field::new("Empty", arrow_schema::DataType::Null, true)
+        ]
+            .into()
+    }
+    fn append_to(
+        self,
+        builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder,
+    ) -> ::arrow_udf::Result<()> {
+        use ::arrow_udf::codegen::arrow_array::builder::*;
+        let mut result = ::arrow_udf::Result::Ok(());
+        match self {
+            Self::Circle { 0: f0 } => {
+                builder.field_builder::<Int8Builder>(0).unwrap().append_value(0i8);
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(1usize)
+                        .unwrap();
+                    result = result
+                        .and({
+                            let builder = builder
+                                .field_builder::<Float64Builder>(0usize)
+                                .unwrap();
+                            let v = f0;
+                            {
+                                builder.append_value(v);
+                                ::arrow_udf::Result::Ok(())
+                            }
+                        });
+                    builder.append(result.is_ok());
+                }
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(2usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(1usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    builder.append_null();
+                }
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(3usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<StringBuilder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    {
+                        let builder = builder
+                            .field_builder::<StructBuilder>(1usize)
+                            .unwrap();
+                        Point::append_null(builder)
+                    }
+                    builder.append_null();
+                }
+                builder.field_builder::<NullBuilder>(4usize).unwrap().append_null();
+            }
+            Self::Rect { width, height } => {
+                builder.field_builder::<Int8Builder>(0).unwrap().append_value(1i8);
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(1usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    builder.append_null();
+                }
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(2usize)
+                        .unwrap();
+                    result = result
+                        .and({
+                            let builder = builder
+                                .field_builder::<Float64Builder>(0usize)
+                                .unwrap();
+                            let v = width;
+                            {
+                                builder.append_value(v);
+                                ::arrow_udf::Result::Ok(())
+                            }
+                        });
+                    result = result
+                        .and({
+                            let builder = builder
+                                .field_builder::<Float64Builder>(1usize)
+                                .unwrap();
+                            let v = height;
+                            {
+                                builder.append_value(v);
+                                ::arrow_udf::Result::Ok(())
+                            }
+                        });
+                    builder.append(result.is_ok());
+                }
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(3usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<StringBuilder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    {
+                        let builder = builder
+                            .field_builder::<StructBuilder>(1usize)
+                            .unwrap();
+                        Point::append_null(builder)
+                    }
+                    builder.append_null();
+                }
+                builder.field_builder::<NullBuilder>(4usize).unwrap().append_null();
+            }
+            Self::Label { 0: f0, 1: f1 } => {
+                builder.field_builder::<Int8Builder>(0).unwrap().append_value(2i8);
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(1usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    builder.append_null();
+                }
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(2usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(1usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    builder.append_null();
+                }
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(3usize)
+                        .unwrap();
+                    result = result
+                        .and({
+                            let builder = builder
+                                .field_builder::<StringBuilder>(0usize)
+                                .unwrap();
+                            let v = f0;
+                            {
+                                builder.append_value(v);
+                                ::arrow_udf::Result::Ok(())
+                            }
+                        });
+                    result = result
+                        .and({
+                            let builder = builder
+                                .field_builder::<StructBuilder>(1usize)
+                                .unwrap();
+                            match f1 {
+                                Some(v) => v.append_to(builder),
+                                None => {
+                                    Point::append_null(builder);
+                                    ::arrow_udf::Result::Ok(())
+                                }
+                            }
+                        });
+                    builder.append(result.is_ok());
+                }
+                builder.field_builder::<NullBuilder>(4usize).unwrap().append_null();
+            }
+            Self::Empty {} => {
+                builder.field_builder::<Int8Builder>(0).unwrap().append_value(3i8);
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(1usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    builder.append_null();
+                }
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(2usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    {
+                        let builder = builder
+                            .field_builder::<Float64Builder>(1usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    builder.append_null();
+                }
+                {
+                    let builder = builder
+                        .field_builder::<StructBuilder>(3usize)
+                        .unwrap();
+                    {
+                        let builder = builder
+                            .field_builder::<StringBuilder>(0usize)
+                            .unwrap();
+                        builder.append_null()
+                    }
+                    {
+                        let builder = builder
+                            .field_builder::<StructBuilder>(1usize)
+                            .unwrap();
+                        Point::append_null(builder)
+                    }
+                    builder.append_null();
+                }
+                builder
+                    .field_builder::<NullBuilder>(4usize)
+                    .unwrap()
+                    .append_empty_value();
+            }
+        }
+        builder.append(result.is_ok());
+        result
+    }
+    fn append_null(
+        builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder,
+    ) {
+        use ::arrow_udf::codegen::arrow_array::builder::*;
+        builder.field_builder::<Int8Builder>(0).unwrap().append_value(0i8);
+        {
+            let builder = builder.field_builder::<StructBuilder>(1usize).unwrap();
+            {
+                let builder = builder.field_builder::<Float64Builder>(0usize).unwrap();
+                builder.append_null()
+            }
+            builder.append_null();
+        }
+        {
+            let builder = builder.field_builder::<StructBuilder>(2usize).unwrap();
+            {
+                let builder = builder.field_builder::<Float64Builder>(0usize).unwrap();
+                builder.append_null()
+            }
+            {
+                let builder = builder.field_builder::<Float64Builder>(1usize).unwrap();
+                builder.append_null()
+            }
+            builder.append_null();
+        }
+        {
+            let builder = builder.field_builder::<StructBuilder>(3usize).unwrap();
+            {
+                let builder = builder.field_builder::<StringBuilder>(0usize).unwrap();
+                builder.append_null()
+            }
+            {
+                let builder = builder.field_builder::<StructBuilder>(1usize).unwrap();
+                Point::append_null(builder)
+            }
+            builder.append_null();
+        }
+        builder.field_builder::<NullBuilder>(4usize).unwrap().append_null();
+        builder.append_null();
+    }
+    fn data_type() -> ::arrow_udf::codegen::arrow_schema::DataType {
+        use ::arrow_udf::codegen::arrow_schema::{DataType, UnionMode};
+        DataType::Union(
+            ::arrow_udf::codegen::union_fields(&Self::fields()),
+            UnionMode::Dense,
+        )
+    }
+    fn encode(
+        array: ::arrow_udf::codegen::arrow_array::StructArray,
+    ) -> ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::ArrayRef> {
+        ::arrow_udf::codegen::struct_to_union(array)
+    }
+    fn decode(
+        array: &::arrow_udf::codegen::arrow_array::ArrayRef,
+    ) -> ::arrow_udf::Result<::arrow_udf::codegen::arrow_array::ArrayRef> {
+        ::arrow_udf::codegen::union_to_struct(array, Self::fields())
+    }
+}
+impl<'a> ::arrow_udf::types::FromStructArray<'a> for Shape<'a>
+where
+    Point: ::arrow_udf::types::FromStructArray<'a>,
+{
+    fn from_struct_array(
+        array: &'a ::arrow_udf::codegen::arrow_array::StructArray,
+        index: usize,
+    ) -> ::arrow_udf::Result<Self> {
+        use ::arrow_udf::codegen::arrow_array;
+        use ::arrow_udf::codegen::arrow_array::array::*;
+        use ::arrow_udf::codegen::arrow_array::cast::AsArray;
+        use ::arrow_udf::codegen::arrow_array::types::Int8Type;
+        use ::arrow_udf::codegen::chrono;
+        use ::arrow_udf::codegen::rust_decimal;
+        use ::arrow_udf::codegen::serde_json;
+        Ok(
+            match array.column(0).as_primitive::<Int8Type>().value(index) {
+                0i8 => {
+                    let array = array.column(1usize).as_struct();
+                    Self::Circle {
+                        0: {
+                            let array = array.column(0usize);
+                            {
+                                let v = array
+                                    .as_any()
+                                    .downcast_ref::<Float64Array>()
+                                    .expect("downcast struct field")
+                                    .value(index);
+                                (v).into()
+                            }
+                        },
+                    }
+                }
+                1i8 => {
+                    let array = array.column(2usize).as_struct();
+                    Self::Rect {
+                        width: {
+                            let array = array.column(0usize);
+                            {
+                                let v = array
+                                    .as_any()
+                                    .downcast_ref::<Float64Array>()
+                                    .expect("downcast struct field")
+                                    .value(index);
+                                (v).into()
+                            }
+                        },
+                        height: {
+                            let array = array.column(1usize);
+                            {
+                                let v = array
+                                    .as_any()
+                                    .downcast_ref::<Float64Array>()
+                                    .expect("downcast struct field")
+                                    .value(index);
+                                (v).into()
+                            }
+                        },
+                    }
+                }
+                2i8 => {
+                    let array = array.column(3usize).as_struct();
+                    Self::Label {
+                        0: {
+                            let array = array.column(0usize);
+                            {
+                                let v = array
+                                    .as_any()
+                                    .downcast_ref::<StringArray>()
+                                    .expect("downcast struct field")
+                                    .value(index);
+                                (v).into()
+                            }
+                        },
+                        1: {
+                            let array = array.column(1usize);
+                            match array.is_null(index) {
+                                true => None,
+                                false => {
+                                    Some(
+                                        ::arrow_udf::types::FromStructArray::from_struct_array(
+                                            array.as_struct(),
+                                            index,
+                                        )?,
+                                    )
+                                }
+                            }
+                        },
+                    }
+                }
+                3i8 => Self::Empty {},
+                t => {
+                    return Err(
+                        ::arrow_udf::Error::InvalidArgumentError(
+                            format!("invalid type id: {t}"),
+                        ),
+                    );
+                }
+            },
+        )
+    }
+}
@@ -16,6 +16,7 @@ struct Data {
     date: NaiveDate,
     time: NaiveTime,
     timestamp: NaiveDateTime,
+    timestamptz: DateTime<Utc>,
     interval: Interval,
     json: serde_json::Value,
     string: String,
@@ -1,188 +1,306 @@
-#[export_name = "arrowudt_RGF0YT1udWxsOm51bGwsYm9vbGVhbjpib29sZWFuLGludDg6aW50OCxpbnQxNjppbnQxNixpbnQzMjppbnQzMixpbnQ2NDppbnQ2NCx1aW50ODp1aW50OCx1aW50MTY6dWludDE2LHVpbnQzMjp1aW50MzIsdWludDY0OnVpbnQ2NCxmbG9hdDMyOmZsb2F0MzIsZmxvYXQ2NDpmbG9hdDY0LGRlY2ltYWw6ZGVjaW1hbCxkYXRlOmRhdGUzMix0aW1lOnRpbWU2NCx0aW1lc3RhbXA6dGltZXN0YW1wLGludGVydmFsOmludGVydmFsLGpzb246anNvbixzdHJpbmc6c3RyaW5nLGJpbmFyeTpiaW5hcnksc3RyaW5nX2FycmF5OnN0cmluZ1tdLHN0cnVjdF86c3RydWN0IEtleVZhbHVl"]
+#[export_name = "arrowudt_RGF0YT1udWxsOm51bGwsYm9vbGVhbjpib29sZWFuLGludDg6aW50OCxpbnQxNjppbnQxNixpbnQzMjppbnQzMixpbnQ2NDppbnQ2NCx1aW50ODp1aW50OCx1aW50MTY6dWludDE2LHVpbnQzMjp1aW50MzIsdWludDY0OnVpbnQ2NCxmbG9hdDMyOmZsb2F0MzIsZmxvYXQ2NDpmbG9hdDY0LGRlY2ltYWw6ZGVjaW1hbCxkYXRlOmRhdGUzMix0aW1lOnRpbWU2NCx0aW1lc3RhbXA6dGltZXN0YW1wLHRpbWVzdGFtcHR6OnRpbWVzdGFtcHR6LGludGVydmFsOmludGVydmFsLGpzb246anNvbixzdHJpbmc6c3RyaW5nLGJpbmFyeTpiaW5hcnksc3RyaW5nX2FycmF5OnN0cmluZ1tdLHN0cnVjdF86c3RydWN0IEtleVZhbHVl"]
 static DATA_METADATA: () = ();
 impl ::arrow_udf::types::StructType for Data {
+    const FALLIBLE: bool = <KeyValue<
+        'static,
+    > as ::arrow_udf::types::StructType>::FALLIBLE;
     fn fields() -> ::arrow_udf::codegen::arrow_schema::Fields {
         use ::arrow_udf::codegen::arrow_schema::{self, Field, TimeUnit, IntervalUnit};
         vec![
             arrow_schema::Field::new("null", arrow_schema::DataType::Null, true),
-            arrow_schema::Field::new("boolean", arrow_schema::DataType::Boolean, true),
-            arrow_schema::Field::new("int8", arrow_schema::DataType::Int8, true),
-            arrow_schema::Field::new("int16", arrow_schema::DataType::Int16, true),
-            arrow_schema::Field::new("int32", arrow_schema::DataType::Int32, true),
-            arrow_schema::Field::new("int64", arrow_schema::DataType::Int64, true),
-            arrow_schema::Field::new("uint8", arrow_schema::DataType::UInt8, true),
-            arrow_schema::Field::new("uint16", arrow_schema::DataType::UInt16, true),
-            arrow_schema::Field::new("uint32", arrow_schema::DataType::UInt32, true),
-            arrow_schema::Field::new("uint64", arrow_schema::DataType::UInt64, true),
-            arrow_schema::Field::new("float32", arrow_schema::DataType::Float32, true),
-            arrow_schema::Field::new("float64", arrow_schema::DataType::Float64, true),
-            arrow_schema::Field::new("decimal", arrow_schema::DataType::Utf8, true)
-            .with_metadata([("ARROW:extension:name".into(), "arrowudf.decimal".into())]
-            .into()), arrow_schema::Field::new("date", arrow_schema::DataType::Date32,
-            true), arrow_schema::Field::new("time",
-            arrow_schema::DataType::Time64(TimeUnit::Microsecond), true),
+            arrow_schema::Field::new("boolean", arrow_schema::DataType::Boolean, false),
+            arrow_schema::Field::new("int8", arrow_schema::DataType::Int8, false),
+            arrow_schema::Field::new("int16", arrow_schema::DataType::Int16, false),
+            arrow_schema::Field::new("int32", arrow_schema::DataType::Int32, false),
+            arrow_schema::Field::new("int64", arrow_schema::DataType::Int64, false),
+            arrow_schema::Field::new("uint8", arrow_schema::DataType::UInt8, false),
+            arrow_schema::Field::new("uint16", arrow_schema::DataType::UInt16, false),
+            arrow_schema::Field::new("uint32", arrow_schema::DataType::UInt32, false),
+            arrow_schema::Field::new("uint64", arrow_schema::DataType::UInt64, false),
+            arrow_schema::Field::new("float32", arrow_schema::DataType::Float32, false),
+            arrow_schema::Field::new("float64", arrow_schema::DataType::Float64, false),
+            arrow_schema::Field::new("decimal", arrow_schema::DataType::Utf8, false)
+            .with_metadata(std::collections::HashMap::from([("ARROW:extension:name"
+            .to_string(), "arrowudf.decimal".to_string())])),
+            arrow_schema::Field::new("date", arrow_schema::DataType::Date32, false),
+            arrow_schema::Field::new("time",
+            arrow_schema::DataType::Time64(TimeUnit::Microsecond), false),
             arrow_schema::Field::new("timestamp",
-            arrow_schema::DataType::Timestamp(TimeUnit::Microsecond, None), true),
-            arrow_schema::Field::new("interval",
-            arrow_schema::DataType::Interval(IntervalUnit::MonthDayNano), true),
-            arrow_schema::Field::new("json", arrow_schema::DataType::Utf8, true)
-            .with_metadata([("ARROW:extension:name".into(), "arrowudf.json".into())]
-            .into()), arrow_schema::Field::new("string", arrow_schema::DataType::Utf8,
-            true), arrow_schema::Field::new("binary", arrow_schema::DataType::Binary,
-            true), arrow_schema::Field::new("string_array",
+            arrow_schema::DataType::Timestamp(TimeUnit::Microsecond, None), false),
+            arrow_schema::Field::new("timestamptz",
+            arrow_schema::DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false), arrow_schema::Field::new("interval",
+            arrow_schema::DataType::Interval(IntervalUnit::MonthDayNano), false),
+            arrow_schema::Field::new("json", arrow_schema::DataType::Utf8, false)
+            .with_metadata(std::collections::HashMap::from([("ARROW:extension:name"
+            .to_string(), "arrowudf.json".to_string())])),
+            arrow_schema::Field::new("string", arrow_schema::DataType::Utf8, false),
+            arrow_schema::Field::new("binary", arrow_schema::DataType::Binary, false),
+            arrow_schema::Field::new("string_array",
             arrow_schema::DataType::List(Arc::new(arrow_schema::Field::new("item",
-            arrow_schema::DataType::Utf8, true))), true),
+            arrow_schema::DataType::Utf8, true))), false),
             arrow_schema::Field::new("struct_",
-            arrow_schema::DataType::Struct(KeyValue::fields()), true)
+            arrow_schema::DataType::Struct(KeyValue::fields()), false)
         ]
             .into()
     }
     fn append_to(
         self,
         builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder,
-    ) {
+    ) -> ::arrow_udf::Result<()> {
         use ::arrow_udf::codegen::arrow_array::builder::*;
-        {
-            let builder = builder.field_builder::<NullBuilder>(0usize).unwrap();
-            let v = self.null;
-            builder.append_empty_value()
-        }
-        {
-            let builder = builder.field_builder::<BooleanBuilder>(1usize).unwrap();
-            let v = self.boolean;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<Int8Builder>(2usize).unwrap();
-            let v = self.int8;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<Int16Builder>(3usize).unwrap();
-            let v = self.int16;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<Int32Builder>(4usize).unwrap();
-            let v = self.int32;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<Int64Builder>(5usize).unwrap();
-            let v = self.int64;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<UInt8Builder>(6usize).unwrap();
-            let v = self.uint8;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<UInt16Builder>(7usize).unwrap();
-            let v = self.uint16;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<UInt32Builder>(8usize).unwrap();
-            let v = self.uint32;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<UInt64Builder>(9usize).unwrap();
-            let v = self.uint64;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<Float32Builder>(10usize).unwrap();
-            let v = self.float32;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<Float64Builder>(11usize).unwrap();
-            let v = self.float64;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<StringBuilder>(12usize).unwrap();
-            let v = self.decimal;
-            builder.append_value(v.to_string())
-        }
-        {
-            let builder = builder.field_builder::<Date32Builder>(13usize).unwrap();
-            let v = self.date;
-            builder.append_value(arrow_array::types::Date32Type::from_naive_date(v))
-        }
-        {
-            let builder = builder
-                .field_builder::<Time64MicrosecondBuilder>(14usize)
-                .unwrap();
-            let v = self.time;
-            builder.append_value(arrow_array::temporal_conversions::time_to_time64us(v))
-        }
-        {
-            let builder = builder
-                .field_builder::<TimestampMicrosecondBuilder>(15usize)
-                .unwrap();
-            let v = self.timestamp;
-            builder.append_value(v.and_utc().timestamp_micros())
-        }
-        {
-            let builder = builder
-                .field_builder::<IntervalMonthDayNanoBuilder>(16usize)
-                .unwrap();
-            let v = self.interval;
-            builder
-                .append_value({
-                    let v: arrow_udf::types::Interval = v.into();
-                    arrow_array::types::IntervalMonthDayNanoType::make_value(
-                        v.months,
-                        v.days,
-                        v.nanos,
-                    )
-                })
-        }
-        {
-            let builder = builder.field_builder::<StringBuilder>(17usize).unwrap();
-            let v = self.json;
-            {
-                use std::fmt::Write;
-                write!(builder, "{}", v).expect("write json");
-                builder.append_value("");
-            }
-        }
-        {
-            let builder = builder.field_builder::<StringBuilder>(18usize).unwrap();
-            let v = self.string;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder.field_builder::<BinaryBuilder>(19usize).unwrap();
-            let v = self.binary;
-            builder.append_value(v)
-        }
-        {
-            let builder = builder
-                .field_builder::<ListBuilder<Box<dyn ArrayBuilder>>>(20usize)
-                .unwrap();
-            let v = self.string_array;
-            {
-                let value_builder = builder
-                    .values()
-                    .as_any_mut()
-                    .downcast_mut::<StringBuilder>()
-                    .expect("downcast list value builder");
-                value_builder.extend(v.into_iter().map(Some));
-                builder.append(true);
-            }
-        }
-        {
-            let builder = builder.field_builder::<StructBuilder>(21usize).unwrap();
-            let v = self.struct_;
-            {
-                v.append_to(builder);
-            }
-        }
-        builder.append(true);
+        let mut result = ::arrow_udf::Result::Ok(());
+        result = result
+            .and({
+                let builder = builder.field_builder::<NullBuilder>(0usize).unwrap();
+                let v = self.null;
+                {
+                    builder.append_empty_value();
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<BooleanBuilder>(1usize).unwrap();
+                let v = self.boolean;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<Int8Builder>(2usize).unwrap();
+                let v = self.int8;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<Int16Builder>(3usize).unwrap();
+                let v = self.int16;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<Int32Builder>(4usize).unwrap();
+                let v = self.int32;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<Int64Builder>(5usize).unwrap();
+                let v = self.int64;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<UInt8Builder>(6usize).unwrap();
+                let v = self.uint8;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<UInt16Builder>(7usize).unwrap();
+                let v = self.uint16;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<UInt32Builder>(8usize).unwrap();
+                let v = self.uint32;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<UInt64Builder>(9usize).unwrap();
+                let v = self.uint64;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<Float32Builder>(10usize).unwrap();
+                let v = self.float32;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<Float64Builder>(11usize).unwrap();
+                let v = self.float64;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<StringBuilder>(12usize).unwrap();
+                let v = self.decimal;
+                {
+                    builder.append_value(v.to_string());
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<Date32Builder>(13usize).unwrap();
+                let v = self.date;
+                {
+                    builder
+                        .append_value(
+                            arrow_array::types::Date32Type::from_naive_date(v),
+                        );
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder
+                    .field_builder::<Time64MicrosecondBuilder>(14usize)
+                    .unwrap();
+                let v = self.time;
+                {
+                    builder
+                        .append_value(
+                            arrow_array::temporal_conversions::time_to_time64us(v),
+                        );
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder
+                    .field_builder::<TimestampMicrosecondBuilder>(15usize)
+                    .unwrap();
+                let v = self.timestamp;
+                {
+                    builder.append_value(v.and_utc().timestamp_micros());
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder
+                    .field_builder::<TimestampMicrosecondBuilder>(16usize)
+                    .unwrap();
+                let v = self.timestamptz;
+                {
+                    builder
+                        .append_value(
+                            chrono::DateTime::<chrono::Utc>::from(v).timestamp_micros(),
+                        );
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder
+                    .field_builder::<IntervalMonthDayNanoBuilder>(17usize)
+                    .unwrap();
+                let v = self.interval;
+                {
+                    builder
+                        .append_value({
+                            let v: arrow_udf::types::Interval = v.into();
+                            arrow_array::types::IntervalMonthDayNanoType::make_value(
+                                v.months,
+                                v.days,
+                                v.nanos,
+                            )
+                        });
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<StringBuilder>(18usize).unwrap();
+                let v = self.json;
+                {
+                    use std::fmt::Write;
+                    write!(builder, "{}", v).expect("write json");
+                    builder.append_value("");
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<StringBuilder>(19usize).unwrap();
+                let v = self.string;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<BinaryBuilder>(20usize).unwrap();
+                let v = self.binary;
+                {
+                    builder.append_value(v);
+                    ::arrow_udf::Result::Ok(())
+                }
+            });
+        result = result
+            .and({
+                let builder = builder
+                    .field_builder::<ListBuilder<Box<dyn ArrayBuilder>>>(21usize)
+                    .unwrap();
+                let v = self.string_array;
+                {
+                    let value_builder = builder
+                        .values()
+                        .as_any_mut()
+                        .downcast_mut::<StringBuilder>()
+                        .expect("downcast list value builder");
+                    let mut result = ::arrow_udf::Result::Ok(());
+                    for v in v {
+                        let builder = &mut *value_builder;
+                        result = {
+                            builder.append_value(v);
+                            ::arrow_udf::Result::Ok(())
+                        };
+                        if result.is_err() {
+                            break;
+                        }
+                    }
+                    builder.append(result.is_ok());
+                    result
+                }
+            });
+        result = result
+            .and({
+                let builder = builder.field_builder::<StructBuilder>(22usize).unwrap();
+                let v = self.struct_;
+                v.append_to(builder)
+            });
+        builder.append(result.is_ok());
+        result
     }
     fn append_null(
         builder: &mut ::arrow_udf::codegen::arrow_array::builder::StructBuilder,
@@ -258,12 +376,14 @@ impl ::arrow_udf::types::StructType for Data {
         }
         {
             let builder = builder
-                .field_builder::<IntervalMonthDayNanoBuilder>(16usize)
+                .field_builder::<TimestampMicrosecondBuilder>(16usize)
                 .unwrap();
             builder.append_null()
         }
         {
-            let builder = builder.field_builder::<StringBuilder>(17usize).unwrap();
+            let builder = builder
+                .field_builder::<IntervalMonthDayNanoBuilder>(17usize)
+                .unwrap();
             builder.append_null()
         }
         {
@@ -271,19 +391,316 @@ impl ::arrow_udf::types::StructType for Data {
             builder.append_null()
         }
         {
-            let builder = builder.field_builder::<BinaryBuilder>(19usize).unwrap();
+            let builder = builder.field_builder::<StringBuilder>(19usize).unwrap();
+            builder.append_null()
+        }
+        {
+            let builder = builder.field_builder::<BinaryBuilder>(20usize).unwrap();
             builder.append_null()
         }
         {
             let builder = builder
-                .field_builder::<ListBuilder<Box<dyn ArrayBuilder>>>(20usize)
+                .field_builder::<ListBuilder<Box<dyn ArrayBuilder>>>(21usize)
                 .unwrap();
             builder.append_null()
         }
         {
-            let builder = builder.field_builder::<StructBuilder>(21usize).unwrap();
+            let builder = builder.field_builder::<StructBuilder>(22usize).unwrap();
             KeyValue::append_null(builder)
         }
         builder.append_null();
     }
 }
+impl<'a> ::arrow_udf::types::FromStructArray<'a> for Data
+where
+    KeyValue<'static>: ::arrow_udf::types::FromStructArray<'a>,
+{
+    fn from_struct_array(
+        array: &'a ::arrow_udf::codegen::arrow_array::StructArray,
+        index: usize,
+    ) -> ::arrow_udf::Result<Self> {
+        use ::arrow_udf::codegen::arrow_array;
+        use ::arrow_udf::codegen::arrow_array::array::*;
+        use ::arrow_udf::codegen::arrow_array::cast::AsArray;
+        use ::arrow_udf::codegen::chrono;
+        use ::arrow_udf::codegen::rust_decimal;
+        use ::arrow_udf::codegen::serde_json;
+        Ok(Self {
+            null: {
+                let array = array.column(0usize);
+                ()
+            },
+            boolean: {
+                let array = array.column(1usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<BooleanArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            int8: {
+                let array = array.column(2usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<Int8Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            int16: {
+                let array = array.column(3usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<Int16Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            int32: {
+                let array = array.column(4usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<Int32Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            int64: {
+                let array = array.column(5usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<Int64Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            uint8: {
+                let array = array.column(6usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<UInt8Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            uint16: {
+                let array = array.column(7usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<UInt16Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            uint32: {
+                let array = array.column(8usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<UInt32Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            uint64: {
+                let array = array.column(9usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<UInt64Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            float32: {
+                let array = array.column(10usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<Float32Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            float64: {
+                let array = array.column(11usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<Float64Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            decimal: {
+                let array = array.column(12usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v.parse::<rust_decimal::Decimal>().expect("invalid decimal")).into()
+                }
+            },
+            date: {
+                let array = array.column(13usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<Date32Array>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (arrow_array::types::Date32Type::to_naive_date_opt(v)
+                        .expect("invalid date"))
+                        .into()
+                }
+            },
+            time: {
+                let array = array.column(14usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<Time64MicrosecondArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (arrow_array::temporal_conversions::as_time::<
+                        arrow_array::types::Time64MicrosecondType,
+                    >(v)
+                        .expect("invalid time"))
+                        .into()
+                }
+            },
+            timestamp: {
+                let array = array.column(15usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<TimestampMicrosecondArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (arrow_array::temporal_conversions::as_datetime::<
+                        arrow_array::types::TimestampMicrosecondType,
+                    >(v)
+                        .expect("invalid timestamp"))
+                        .into()
+                }
+            },
+            timestamptz: {
+                let array = array.column(16usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<TimestampMicrosecondArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (arrow_array::temporal_conversions::as_datetime::<
+                        arrow_array::types::TimestampMicrosecondType,
+                    >(v)
+                        .expect("invalid timestamp")
+                        .and_utc())
+                        .into()
+                }
+            },
+            interval: {
+                let array = array.column(17usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<IntervalMonthDayNanoArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    ({
+                        let (months, days, nanos) = arrow_array::types::IntervalMonthDayNanoType::to_parts(
+                            v,
+                        );
+                        arrow_udf::types::Interval {
+                            months,
+                            days,
+                            nanos,
+                        }
+                    })
+                        .into()
+                }
+            },
+            json: {
+                let array = array.column(18usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v.parse::<serde_json::Value>().expect("invalid json")).into()
+                }
+            },
+            string: {
+                let array = array.column(19usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            binary: {
+                let array = array.column(20usize);
+                {
+                    let v = array
+                        .as_any()
+                        .downcast_ref::<BinaryArray>()
+                        .expect("downcast struct field")
+                        .value(index);
+                    (v).into()
+                }
+            },
+            string_array: {
+                let array = array.column(21usize);
+                {
+                    let list = array.as_list::<i32>();
+                    let start = list.value_offsets()[index] as usize;
+                    let end = list.value_offsets()[index + 1] as usize;
+                    let values = list
+                        .values()
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .expect("downcast list values");
+                    (start..end)
+                        .map(|j| {
+                            let v = values.value(j);
+                            (v).into()
+                        })
+                        .collect()
+                }
+            },
+            struct_: {
+                let array = array.column(22usize);
+                ::arrow_udf::types::FromStructArray::from_struct_array(
+                    array.as_struct(),
+                    index,
+                )?
+            },
+        })
+    }
+}
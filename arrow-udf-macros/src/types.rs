@@ -31,15 +31,21 @@ const TYPE_MATRIX: &str = "
     date32      _       NaiveDate       Date32                  Date32
     time64      _       NaiveTime       Time64Microsecond       Time64(TimeUnit::Microsecond)
     timestamp   _       NaiveDateTime   TimestampMicrosecond    Timestamp(TimeUnit::Microsecond,None)
+    timestamptz _       DateTime<Utc>,DateTime<FixedOffset> TimestampMicrosecond Timestamp(TimeUnit::Microsecond,Some(\"UTC\".into()))
     interval    _       Interval        IntervalMonthDayNano    Interval(IntervalUnit::MonthDayNano)
     decimal     _       Decimal         String                  Utf8
+    decimal128  _       _               Decimal128              Decimal128
+    decimal256  _       _               Decimal256              Decimal256
     json        _       Value           String                  Utf8
     string      _       String,str      String                  Utf8
     binary      _       Vec<u8>,[u8]    Binary                  Binary
     largestring _       String,str      LargeString             LargeUtf8
     largebinary _       Vec<u8>,[u8]    LargeBinary             LargeBinary
     array       _       _               List                    List
+    map         _       _               Map                     Map
     struct      _       _               Struct                  Struct
+    any         _       _               Any                     Null
+    anyarray    _       _               List                    Null
 ";
 
 /// Maps a data type to its corresponding data type name.
@@ -62,6 +68,27 @@ pub fn is_primitive(ty: &str) -> bool {
     lookup_matrix(ty, 1) == "y"
 }
 
+/// Checks if a data type can be evaluated by the vectorized fast path.
+///
+/// Values of these types are stored as native values or bits in Arrow arrays.
+pub fn is_vectorizable(ty: &str) -> bool {
+    is_primitive(ty) || matches!(ty, "boolean" | "date32" | "timestamp" | "timestamptz")
+}
+
+/// Checks if converting values of a data type can fail.
+///
+/// `decimal(p, s)` values may not fit in `rust_decimal::Decimal` or in the precision `p`.
+pub fn is_fallible(ty: &str) -> bool {
+    ty.starts_with("decimal(")
+}
+
+/// Checks if arguments of a data type are converted by `Result`, including structs and lists of
+/// fallible values.
+pub fn is_converted(ty: &str) -> bool {
+    let elem_ty = ty.strip_suffix("[]").unwrap_or(ty);
+    is_fallible(elem_ty) || elem_ty.starts_with("struct ")
+}
+
 /// Maps a Rust type to its corresponding data type name.
 pub fn type_of(rust_type: &str) -> String {
     if let Some(ty) = TYPE_MATRIX.trim().lines().find_map(|line| {
@@ -87,8 +114,16 @@ pub fn type_of(rust_type: &str) -> String {
 fn lookup_matrix(mut ty: &str, idx: usize) -> &str {
     if ty.ends_with("[]") {
         ty = "array";
-    } else if ty.starts_with("struct") {
+    } else if ty.starts_with("struct") || ty.starts_with("table(") {
         ty = "struct";
+    } else if ty.starts_with("map<") {
+        ty = "map";
+    } else if let Some((precision, _)) = decimal_precision_scale(ty) {
+        ty = if precision <= 38 {
+            "decimal128"
+        } else {
+            "decimal256"
+        };
     }
     let s = TYPE_MATRIX.trim().lines().find_map(|line| {
         let mut parts = line.split_whitespace();
@@ -101,6 +136,61 @@ fn lookup_matrix(mut ty: &str, idx: usize) -> &str {
     s.unwrap_or_else(|| panic!("unknown type: {}", ty))
 }
 
+/// Returns the precision and scale of a `decimal(p, s)` type.
+///
+/// The scale defaults to 0 if omitted.
+pub fn decimal_precision_scale(ty: &str) -> Option<(u8, i8)> {
+    let params = ty.strip_prefix("decimal(")?.strip_suffix(')')?;
+    let (precision, scale) = params.split_once(',').unwrap_or((params, "0"));
+    Some((precision.trim().parse().ok()?, scale.trim().parse().ok()?))
+}
+
+/// Returns the key and value types of a `map<k,v>` type.
+pub fn map_key_value(ty: &str) -> Option<(&str, &str)> {
+    let kv = ty.strip_prefix("map<")?.strip_suffix('>')?;
+    match split_types(kv).as_slice() {
+        [k, v] => Some((k.trim(), v.trim())),
+        _ => None,
+    }
+}
+
+/// Returns the names and types of the columns of a `table(name type, ..)` type.
+pub fn table_columns(ty: &str) -> Option<Vec<(&str, &str)>> {
+    let columns = ty.strip_prefix("table(")?.strip_suffix(')')?;
+    split_types(columns)
+        .into_iter()
+        .map(|column| {
+            let (name, ty) = column.trim().split_once(' ')?;
+            Some((name, ty.trim()))
+        })
+        .collect()
+}
+
+/// Split a comma-separated list of types, ignoring commas nested in `()`, `<>` or quotes.
+///
+/// e.g. `"int, decimal(10, 2), string = 'a,b'"` => `["int", " decimal(10, 2)", " string = 'a,b'"]`
+pub fn split_types(s: &str) -> Vec<&str> {
+    let mut types = vec![];
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            _ if quoted => {}
+            '(' | '<' => depth += 1,
+            ')' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                types.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    types.push(&s[start..]);
+    types
+}
+
 /// Normalizes a data type string.
 ///
 /// # Examples
@@ -108,14 +198,42 @@ fn lookup_matrix(mut ty: &str, idx: usize) -> &str {
 /// "int" => "int32"
 /// "int[]" => "int32[]"
 /// "struct  Key" => "struct Key"
+/// "numeric(10, 2)" => "decimal(10,2)"
+/// "map<varchar, int>" => "map<string,int32>"
+/// "any[]" => "anyarray"
+/// "int..." => "int32..."
+/// "table(a int, b varchar)" => "table(a int32,b string)"
 /// ```
 pub fn normalize_type(ty: &str) -> String {
+    if let Some(columns) = table_columns(ty) {
+        let columns = columns
+            .into_iter()
+            .map(|(name, ty)| format!("{name} {}", normalize_type(ty)))
+            .collect::<Vec<_>>();
+        return format!("table({})", columns.join(","));
+    }
+    if let Some(t) = ty.strip_suffix("...").filter(|t| !t.trim().is_empty()) {
+        return format!("{}...", normalize_type(t.trim()));
+    }
     if let Some(t) = ty.strip_suffix("[]") {
-        return format!("{}[]", normalize_type(t));
+        return match normalize_type(t).as_str() {
+            "any" => "anyarray".to_string(),
+            t => format!("{t}[]"),
+        };
     }
     if let Some(s) = ty.strip_prefix("struct ") {
         return format!("struct {}", s.trim());
     }
+    if let Some((k, v)) = map_key_value(ty) {
+        return format!("map<{},{}>", normalize_type(k), normalize_type(v));
+    }
+    if let Some(params) = ty
+        .strip_prefix("decimal")
+        .or_else(|| ty.strip_prefix("numeric"))
+        .and_then(|s| s.trim_start().strip_prefix('('))
+    {
+        return format!("decimal({}", params.replace(' ', ""));
+    }
     match ty {
         "bool" => "boolean",
         "smallint" => "int16",
@@ -134,6 +252,11 @@ pub fn normalize_type(ty: &str) -> String {
     .to_string()
 }
 
+/// Checks if a data type is `any` or `anyarray`.
+pub fn is_polymorphic(ty: &str) -> bool {
+    matches!(ty, "any" | "anyarray")
+}
+
 /// Expands a type wildcard string into a list of concrete types.
 pub fn expand_type_wildcard(ty: &str) -> Vec<&str> {
     match ty {
@@ -141,7 +264,12 @@ pub fn expand_type_wildcard(ty: &str) -> Vec<&str> {
             .trim()
             .lines()
             .map(|l| l.split_whitespace().next().unwrap())
-            .filter(|l| *l != "any" && *l != "null")
+            .filter(|l| {
+                !matches!(
+                    *l,
+                    "any" | "anyarray" | "null" | "decimal128" | "decimal256" | "map"
+                )
+            })
             .collect(),
         "int*" => vec!["int8", "int16", "int32", "int64"],
         "uint*" => vec!["uint8", "uint16", "uint32", "uint64"],
@@ -168,5 +296,38 @@ mod tests {
         assert_eq!(normalize_type("jsonb"), "json");
         assert_eq!(normalize_type("int[]"), "int32[]");
         assert_eq!(normalize_type("struct   Key"), "struct Key");
+        assert_eq!(normalize_type("numeric(10, 2)"), "decimal(10,2)");
+        assert_eq!(normalize_type("decimal (38,10)[]"), "decimal(38,10)[]");
+        assert_eq!(normalize_type("map<varchar, int>"), "map<string,int32>");
+        assert_eq!(normalize_type("any[]"), "anyarray");
+        assert_eq!(
+            normalize_type("table(a int, b numeric(10, 2))"),
+            "table(a int32,b decimal(10,2))"
+        );
+        assert_eq!(
+            normalize_type("map<string, map<int, decimal(10, 2)>>"),
+            "map<string,map<int32,decimal(10,2)>>"
+        );
+    }
+
+    #[test]
+    fn test_split_types() {
+        assert_eq!(
+            split_types("int, decimal(10, 2), map<string, int>"),
+            ["int", " decimal(10, 2)", " map<string, int>"]
+        );
+        assert_eq!(
+            split_types("string, string = 'a,(b'"),
+            ["string", " string = 'a,(b'"]
+        );
+    }
+
+    #[test]
+    fn test_decimal_type() {
+        assert_eq!(decimal_precision_scale("decimal(10,2)"), Some((10, 2)));
+        assert_eq!(decimal_precision_scale("decimal(10)"), Some((10, 0)));
+        assert_eq!(decimal_precision_scale("decimal"), None);
+        assert_eq!(array_type("decimal(38,10)"), "Decimal128Array");
+        assert_eq!(array_type("decimal(76,10)"), "Decimal256Array");
     }
 }
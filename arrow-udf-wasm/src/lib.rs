@@ -61,6 +61,9 @@ struct Instance {
     dealloc: TypedFunc<(u32, u32, u32), ()>,
     // extern "C" fn(iter: *mut RecordBatchIter, out: *mut CSlice)
     record_batch_iterator_next: TypedFunc<(u32, u32), ()>,
+    // extern "C" fn(iter: *mut RecordBatchIter, out: *mut CSlice) -> i32
+    // available since abi version 3.1
+    record_batch_iterator_try_next: Option<TypedFunc<(u32, u32), i32>>,
     // extern "C" fn(iter: *mut RecordBatchIter)
     record_batch_iterator_drop: TypedFunc<u32, ()>,
     // extern "C" fn(ptr: *const u8, len: usize, out: *mut CSlice) -> i32
@@ -288,6 +291,9 @@ impl Instance {
         let dealloc = instance.get_typed_func(&mut store, "dealloc")?;
         let record_batch_iterator_next =
             instance.get_typed_func(&mut store, "record_batch_iterator_next")?;
+        let record_batch_iterator_try_next = instance
+            .get_typed_func(&mut store, "record_batch_iterator_try_next")
+            .ok();
         let record_batch_iterator_drop =
             instance.get_typed_func(&mut store, "record_batch_iterator_drop")?;
         let memory = instance
@@ -298,6 +304,7 @@ impl Instance {
             alloc,
             dealloc,
             record_batch_iterator_next,
+            record_batch_iterator_try_next,
             record_batch_iterator_drop,
             memory,
             store,
@@ -430,14 +437,22 @@ impl Instance {
         impl RecordBatchIter<'_> {
             /// Get the next record batch.
             fn next(&mut self) -> Result<Option<RecordBatch>> {
-                self.instance
-                    .record_batch_iterator_next
-                    .call(&mut self.instance.store, (self.ptr, self.alloc_ptr))?;
+                let errno = match &self.instance.record_batch_iterator_try_next {
+                    Some(try_next) => {
+                        try_next.call(&mut self.instance.store, (self.ptr, self.alloc_ptr))?
+                    }
+                    None => {
+                        self.instance
+                            .record_batch_iterator_next
+                            .call(&mut self.instance.store, (self.ptr, self.alloc_ptr))?;
+                        0
+                    }
+                };
                 // get return values
                 let out_ptr = self.instance.read_u32(self.alloc_ptr)?;
                 let out_len = self.instance.read_u32(self.alloc_ptr + 4)?;
 
-                if out_ptr == 0 {
+                if errno == 0 && out_ptr == 0 {
                     // end of iteration
                     return Ok(None);
                 }
@@ -449,14 +464,17 @@ impl Instance {
                     .data(&self.instance.store)
                     .get(out_ptr as usize..(out_ptr + out_len) as usize)
                     .context("output slice out of bounds")?;
-                let batch = decode_record_batch(out_bytes)?;
+                let result = match errno {
+                    0 => decode_record_batch(out_bytes).map(Some),
+                    _ => Err(anyhow!("{}", std::str::from_utf8(out_bytes)?)),
+                };
 
                 // dealloc output
                 self.instance
                     .dealloc
                     .call(&mut self.instance.store, (out_ptr, out_len, 1))?;
 
-                Ok(Some(batch))
+                result
             }
         }
 
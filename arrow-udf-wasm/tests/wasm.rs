@@ -103,13 +103,13 @@ fn test_division_by_zero() {
     check(
         &[output],
         expect![[r#"
-        +-----+------------------+
-        | div | error            |
-        +-----+------------------+
-        | 0   |                  |
-        |     | division by zero |
-        |     |                  |
-        +-----+------------------+"#]],
+        +-----+-------------------------------------------------+
+        | div | error                                           |
+        +-----+-------------------------------------------------+
+        | 0   |                                                 |
+        |     | {code: 0, message: division by zero, details: } |
+        |     |                                                 |
+        +-----+-------------------------------------------------+"#]],
     );
 }
 
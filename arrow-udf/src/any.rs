@@ -0,0 +1,178 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Builders for the return values of `any` and `anyarray` types.
+
+use std::sync::Arc;
+
+use arrow_array::{make_array, new_empty_array, new_null_array, Array, ArrayRef, ListArray};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use arrow_data::transform::MutableArrayData;
+use arrow_schema::{DataType, Field, FieldRef};
+
+use crate::{Error, Result};
+
+/// A builder for values of `any` type.
+///
+/// Each value is an array of length 1 with the inferred return type.
+#[derive(Debug)]
+pub struct AnyBuilder {
+    data_type: DataType,
+    values: Vec<Option<ArrayRef>>,
+}
+
+impl AnyBuilder {
+    /// Creates a new builder of the given data type.
+    pub fn new(data_type: DataType, capacity: usize) -> Self {
+        Self {
+            data_type,
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Appends a value.
+    pub fn append_value(&mut self, value: ArrayRef) {
+        self.values.push(Some(value));
+    }
+
+    /// Appends a null value.
+    pub fn append_null(&mut self) {
+        self.values.push(None);
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if there is no value.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Builds the array and resets the builder.
+    pub fn finish(&mut self) -> Result<ArrayRef> {
+        let values = std::mem::take(&mut self.values);
+        for value in values.iter().flatten() {
+            check_type(value, &self.data_type)?;
+            if value.len() != 1 {
+                return Err(Error::InvalidArgumentError(format!(
+                    "expect an array of length 1 for `any` value, but got {}",
+                    value.len()
+                )));
+            }
+        }
+        let data = values
+            .iter()
+            .flatten()
+            .map(|v| v.to_data())
+            .collect::<Vec<_>>();
+        if data.is_empty() {
+            return Ok(new_null_array(&self.data_type, values.len()));
+        }
+        let mut mutable = MutableArrayData::new(data.iter().collect(), true, values.len());
+        let mut index = 0;
+        for value in &values {
+            if value.is_some() {
+                mutable.try_extend(index, 0, 1)?;
+                index += 1;
+            } else {
+                mutable.try_extend_nulls(1)?;
+            }
+        }
+        Ok(make_array(mutable.freeze()))
+    }
+}
+
+/// A builder for values of `anyarray` type.
+///
+/// Each value is an array of the elements of a list.
+#[derive(Debug)]
+pub struct AnyArrayBuilder {
+    field: FieldRef,
+    values: Vec<Option<ArrayRef>>,
+}
+
+impl AnyArrayBuilder {
+    /// Creates a new builder of the given list type.
+    pub fn new(data_type: DataType, capacity: usize) -> Self {
+        let field = match data_type {
+            DataType::List(field) => field,
+            t => Arc::new(Field::new("item", t, true)),
+        };
+        Self {
+            field,
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Appends a list of elements.
+    pub fn append_value(&mut self, value: ArrayRef) {
+        self.values.push(Some(value));
+    }
+
+    /// Appends a null value.
+    pub fn append_null(&mut self) {
+        self.values.push(None);
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if there is no value.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Builds the list array and resets the builder.
+    pub fn finish(&mut self) -> Result<ArrayRef> {
+        let values = std::mem::take(&mut self.values);
+        for value in values.iter().flatten() {
+            check_type(value, self.field.data_type())?;
+        }
+        let offsets =
+            OffsetBuffer::from_lengths(values.iter().map(|v| v.as_ref().map_or(0, |v| v.len())));
+        let nulls = NullBuffer::from_iter(values.iter().map(|v| v.is_some()));
+        let data = values
+            .iter()
+            .flatten()
+            .map(|v| v.to_data())
+            .collect::<Vec<_>>();
+        let elements = if data.is_empty() {
+            new_empty_array(self.field.data_type())
+        } else {
+            let capacity = data.iter().map(|d| d.len()).sum();
+            let mut mutable = MutableArrayData::new(data.iter().collect(), true, capacity);
+            for (i, d) in data.iter().enumerate() {
+                mutable.try_extend(i, 0, d.len())?;
+            }
+            make_array(mutable.freeze())
+        };
+        let array = ListArray::try_new(self.field.clone(), offsets, elements, Some(nulls))?;
+        Ok(Arc::new(array))
+    }
+}
+
+/// Returns an error if the type of the value does not match the inferred type.
+fn check_type(value: &ArrayRef, data_type: &DataType) -> Result<()> {
+    if !value.data_type().equals_datatype(data_type) {
+        return Err(Error::InvalidArgumentError(format!(
+            "expect {data_type} for the return value, but got {}",
+            value.data_type()
+        )));
+    }
+    Ok(())
+}
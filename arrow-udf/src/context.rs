@@ -0,0 +1,169 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-query context for user-defined functions.
+//!
+//! # Example
+//!
+//! ```
+//! use arrow_udf::{function, Context};
+//!
+//! #[function("timezone() -> string")]
+//! fn timezone(ctx: &Context) -> String {
+//!     ctx.timezone().unwrap_or("UTC").to_string()
+//! }
+//!
+//! let ctx = Context::new().with_timezone("Asia/Shanghai");
+//! let tz = ctx.scope(|| Context::current().timezone().map(String::from));
+//! assert_eq!(tz.as_deref(), Some("Asia/Shanghai"));
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::ErrorPolicy;
+use crate::{Error, Result};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Context>>> = const { RefCell::new(None) };
+}
+
+/// The empty context used outside a [`Context::scope`], shared to avoid allocating per call.
+static EMPTY: once_cell::sync::Lazy<Arc<Context>> = once_cell::sync::Lazy::new(Default::default);
+
+/// The context of a function call.
+///
+/// A context is installed by the caller with [`Context::scope`], and is passed to functions
+/// that take a `&Context` argument. Generated functions also fail if the deadline is exceeded
+/// when they are called.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    timezone: Option<String>,
+    config: HashMap<String, String>,
+    deadline: Option<Instant>,
+    error_policy: ErrorPolicy,
+    concurrency: Option<usize>,
+}
+
+impl Context {
+    /// Create a new empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the session time zone, e.g. `Asia/Shanghai` or `+08:00`.
+    pub fn with_timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = Some(timezone.into());
+        self
+    }
+
+    /// Set a configuration value.
+    pub fn with_config(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the deadline of the call.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Set the deadline of the call to `timeout` from now.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Set how errors returned by functions are handled.
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    /// Set the number of rows evaluated at the same time by async functions.
+    ///
+    /// This overrides the `concurrency` attribute of the functions. Zero is treated as one.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency.max(1));
+        self
+    }
+
+    /// Returns the session time zone.
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
+    /// Returns the configuration value of `key`.
+    pub fn get_config(&self, key: &str) -> Option<&str> {
+        self.config.get(key).map(|s| s.as_str())
+    }
+
+    /// Returns all configuration values.
+    pub fn config(&self) -> &HashMap<String, String> {
+        &self.config
+    }
+
+    /// Returns the deadline of the call.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Returns the error policy.
+    pub fn error_policy(&self) -> ErrorPolicy {
+        self.error_policy
+    }
+
+    /// Returns the concurrency of async functions, if set.
+    pub fn concurrency(&self) -> Option<usize> {
+        self.concurrency
+    }
+
+    /// Returns true if the deadline is exceeded.
+    pub fn is_expired(&self) -> bool {
+        matches!(self.deadline, Some(deadline) if Instant::now() >= deadline)
+    }
+
+    /// Returns an error if the deadline is exceeded.
+    pub fn check_deadline(&self) -> Result<()> {
+        if self.is_expired() {
+            return Err(Error::ComputeError("deadline exceeded".into()));
+        }
+        Ok(())
+    }
+
+    /// Run `f` with this context as the current context.
+    ///
+    /// Functions called inside `f` read this context from [`Context::current`].
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        /// Restores the previous context on drop.
+        struct Guard(Option<Arc<Context>>);
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+            }
+        }
+        let prev = CURRENT.with(|c| c.borrow_mut().replace(Arc::new(self.clone())));
+        let _guard = Guard(prev);
+        f()
+    }
+
+    /// Returns the current context, or an empty context if not in a [`Context::scope`].
+    pub fn current() -> Arc<Context> {
+        CURRENT
+            .with(|c| c.borrow().clone())
+            .unwrap_or_else(|| EMPTY.clone())
+    }
+}
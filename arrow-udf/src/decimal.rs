@@ -0,0 +1,94 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between `decimal(p, s)` values and [`Decimal`].
+//!
+//! [`Decimal`] has a 96-bit mantissa, so values of `Decimal128(p, s)` with `p > 28` and
+//! `Decimal256(p, s)` may not fit. Results are rescaled to `s` and must fit in the precision `p`.
+
+use arrow_array::types::{Decimal128Type, Decimal256Type, DecimalType};
+use arrow_buffer::i256;
+use rust_decimal::Decimal;
+
+use crate::{Error, Result};
+
+/// Converts the mantissa of a `Decimal128(p, s)` value to a [`Decimal`].
+pub fn decimal_from_i128(mantissa: i128, precision: u8, scale: u32) -> Result<Decimal> {
+    Decimal::try_from_i128_with_scale(mantissa, scale).map_err(|_| {
+        let value = Decimal128Type::format_decimal(mantissa, precision, scale as i8);
+        Error::InvalidArgumentError(format!("decimal {value} is out of range"))
+    })
+}
+
+/// Converts the mantissa of a `Decimal256(p, s)` value to a [`Decimal`].
+pub fn decimal_from_i256(mantissa: i256, precision: u8, scale: u32) -> Result<Decimal> {
+    match mantissa.to_i128() {
+        Some(mantissa) => decimal_from_i128(mantissa, precision, scale),
+        None => {
+            let value = Decimal256Type::format_decimal(mantissa, precision, scale as i8);
+            Err(Error::InvalidArgumentError(format!(
+                "decimal {value} is out of range"
+            )))
+        }
+    }
+}
+
+/// Converts a [`Decimal`] to the mantissa of a `Decimal128(p, s)` value.
+pub fn decimal_to_i128(value: Decimal, precision: u8, scale: u32) -> Result<i128> {
+    let mantissa = rescale(value, scale)?;
+    check_precision(mantissa, precision, "Decimal128")?;
+    Ok(mantissa)
+}
+
+/// Converts a [`Decimal`] to the mantissa of a `Decimal256(p, s)` value.
+pub fn decimal_to_i256(value: Decimal, precision: u8, scale: u32) -> Result<i256> {
+    let mantissa = rescale(value, scale)?;
+    check_precision(mantissa, precision, "Decimal256")?;
+    Ok(i256::from_i128(mantissa))
+}
+
+/// Checks that the mantissa fits in the precision.
+///
+/// The bound is computed here, since the validation functions of `arrow-array` differ between
+/// versions. Mantissas of [`Decimal`] have less than 29 digits, so they always fit in a precision
+/// over 38.
+fn check_precision(mantissa: i128, precision: u8, type_name: &str) -> Result<()> {
+    let Some(max) = 10i128.checked_pow(precision as u32).map(|p| p - 1) else {
+        return Ok(());
+    };
+    if mantissa > max {
+        return Err(Error::InvalidArgumentError(format!(
+            "{mantissa} is too large to store in a {type_name} of precision {precision}. Max is {max}"
+        )));
+    }
+    if mantissa < -max {
+        return Err(Error::InvalidArgumentError(format!(
+            "{mantissa} is too small to store in a {type_name} of precision {precision}. Min is {}",
+            -max
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the mantissa of `value` with `scale`.
+fn rescale(mut value: Decimal, scale: u32) -> Result<i128> {
+    let original = value;
+    value.rescale(scale);
+    if value.scale() != scale {
+        return Err(Error::InvalidArgumentError(format!(
+            "decimal {original} can not be represented with scale {scale}"
+        )));
+    }
+    Ok(value.mantissa())
+}
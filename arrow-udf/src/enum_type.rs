@@ -0,0 +1,159 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between enum values and their Arrow encodings.
+//!
+//! Enums deriving `StructType` are built with a `StructBuilder` like structs:
+//!
+//! - Fieldless enums are built as `struct { variant: int32 }` and encoded as
+//!   `Dictionary(Int32, Utf8)` of the variant names.
+//! - Other enums are built as `struct { type_id: int8, <variant>: struct | null, .. }` and encoded
+//!   as a dense `Union` with one child per variant.
+
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Int32Type, Int8Type};
+use arrow_array::{
+    make_array, new_null_array, Array, ArrayRef, DictionaryArray, Int32Array, Int8Array,
+    StringArray, StructArray, UnionArray,
+};
+use arrow_buffer::{BooleanBufferBuilder, Buffer, NullBuffer};
+use arrow_data::transform::MutableArrayData;
+use arrow_data::ArrayData;
+use arrow_schema::{DataType, Fields, UnionFields, UnionMode};
+
+use crate::{Error, Result};
+
+/// Returns the union fields of an enum from the fields of its struct representation.
+pub fn union_fields(fields: &Fields) -> UnionFields {
+    (0..fields.len() as i8 - 1)
+        .zip(fields.iter().skip(1).cloned())
+        .collect()
+}
+
+/// Encodes the struct representation of fieldless enums as a dictionary of variant names.
+pub fn struct_to_dictionary(array: StructArray, names: &[&str]) -> Result<ArrayRef> {
+    let keys = array.column(0).as_primitive::<Int32Type>().clone();
+    let values = Arc::new(StringArray::from(names.to_vec()));
+    Ok(Arc::new(DictionaryArray::try_new(keys, values)?))
+}
+
+/// Decodes a dictionary or string array of variant names into the struct representation.
+pub fn dictionary_to_struct(array: &ArrayRef, names: &[&str], fields: Fields) -> Result<ArrayRef> {
+    let strings = arrow_cast::cast(array, &DataType::Utf8)?;
+    let keys = strings
+        .as_string::<i32>()
+        .iter()
+        .map(|s| {
+            s.map(|s| match names.iter().position(|name| *name == s) {
+                Some(i) => Ok(i as i32),
+                None => Err(Error::InvalidArgumentError(format!(
+                    "invalid variant: {s:?}, expect one of {names:?}"
+                ))),
+            })
+            .transpose()
+        })
+        .collect::<Result<Int32Array>>()?;
+    let nulls = keys.nulls().cloned();
+    Ok(Arc::new(StructArray::try_new(
+        fields,
+        vec![Arc::new(keys)],
+        nulls,
+    )?))
+}
+
+/// Encodes the struct representation of enums as a dense union.
+pub fn struct_to_union(array: StructArray) -> Result<ArrayRef> {
+    let union_fields = union_fields(array.fields());
+    let type_ids = array.column(0).as_primitive::<Int8Type>();
+    let mut counts = vec![0; array.num_columns() - 1];
+    let offsets = type_ids
+        .values()
+        .iter()
+        .map(|&t| {
+            let offset = counts[t as usize];
+            counts[t as usize] += 1;
+            offset
+        })
+        .collect::<Vec<i32>>();
+    let children = array.columns()[1..]
+        .iter()
+        .enumerate()
+        .map(|(k, column)| {
+            if column.data_type() == &DataType::Null {
+                return Ok(new_null_array(&DataType::Null, counts[k] as usize).to_data());
+            }
+            let data = column.to_data();
+            let mut child = MutableArrayData::new(vec![&data], true, counts[k] as usize);
+            for (i, &t) in type_ids.values().iter().enumerate() {
+                if t as usize == k {
+                    child.try_extend(0, i, i + 1)?;
+                }
+            }
+            Ok(child.freeze())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let data = ArrayData::builder(DataType::Union(union_fields, UnionMode::Dense))
+        .len(array.len())
+        .add_buffer(Buffer::from_slice_ref(type_ids.values()))
+        .add_buffer(Buffer::from_vec(offsets))
+        .child_data(children)
+        .build()?;
+    Ok(make_array(data))
+}
+
+/// Decodes a dense union into the struct representation.
+///
+/// A value is null if it is a null value of a variant with fields.
+pub fn union_to_struct(array: &ArrayRef, fields: Fields) -> Result<ArrayRef> {
+    let data_type = DataType::Union(union_fields(&fields), UnionMode::Dense);
+    if array.data_type() != &data_type {
+        return Err(Error::CastError(format!(
+            "expect {data_type}, but got {}",
+            array.data_type()
+        )));
+    }
+    let union = array.as_any().downcast_ref::<UnionArray>().unwrap();
+    let type_ids = Int8Array::from_iter_values((0..union.len()).map(|i| union.type_id(i)));
+    let mut validity = BooleanBufferBuilder::new(union.len());
+    for i in 0..union.len() {
+        let child = union.child(union.type_id(i));
+        validity.append(child.is_valid(union.value_offset(i)));
+    }
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(type_ids.clone())];
+    for k in 0..fields.len() - 1 {
+        let data = union.child(k as i8).to_data();
+        if data.data_type() == &DataType::Null {
+            columns.push(new_null_array(&DataType::Null, union.len()));
+            continue;
+        }
+        let mut child = MutableArrayData::new(vec![&data], true, union.len());
+        for (i, &t) in type_ids.values().iter().enumerate() {
+            if t as usize == k {
+                let offset = union.value_offset(i);
+                child.try_extend(0, offset, offset + 1)?;
+            } else {
+                child.try_extend_nulls(1)?;
+            }
+        }
+        columns.push(make_array(child.freeze()));
+    }
+    let nulls = NullBuffer::new(validity.finish());
+    Ok(Arc::new(StructArray::try_new(
+        fields,
+        columns,
+        Some(nulls),
+    )?))
+}
@@ -0,0 +1,229 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The error column of function outputs.
+//!
+//! Functions returning `Result` output an extra `error` column of type
+//! `Struct<row: int32, code: int32, message: utf8, details: utf8>`. The i-th value is the error of
+//! the i-th output row, or null if the row succeeded. `row` is the index of the input row that
+//! caused the error, which differs from the output row for table functions. The name of the
+//! function is stored in the `arrowudf.function` metadata of the column.
+//!
+//! Alternatively, the caller can choose to abort the batch on the first error by setting
+//! [`ErrorPolicy::FailFast`] in the [`Context`](crate::Context). The error column is still
+//! present in the output, but it is always null.
+//!
+//! # Example
+//!
+//! ```
+//! use arrow_udf::{function, ErrorCode};
+//!
+//! #[derive(Debug)]
+//! enum MathError {
+//!     DivisionByZero,
+//! }
+//!
+//! impl std::fmt::Display for MathError {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         write!(f, "division by zero")
+//!     }
+//! }
+//!
+//! impl ErrorCode for MathError {
+//!     fn code(&self) -> i32 {
+//!         22012
+//!     }
+//! }
+//!
+//! #[function("div(int, int) -> int")]
+//! fn div(x: i32, y: i32) -> Result<i32, MathError> {
+//!     x.checked_div(y).ok_or(MathError::DivisionByZero)
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use arrow_array::builder::{BooleanBufferBuilder, Int32Builder, StringBuilder};
+use arrow_array::{ArrayRef, StructArray};
+use arrow_buffer::NullBuffer;
+use arrow_schema::{DataType, Field, Fields};
+
+use crate::{Error, Result};
+
+/// The metadata key of the function name in the error column.
+pub const FUNCTION_METADATA_KEY: &str = "arrowudf.function";
+
+/// The code of errors that do not implement [`ErrorCode`].
+pub const UNKNOWN_ERROR_CODE: i32 = 0;
+
+/// A machine-readable code of an error returned by a function.
+///
+/// If the error type of a function implements this trait, the code and details are stored in the
+/// error column. Otherwise, the code is [`UNKNOWN_ERROR_CODE`].
+pub trait ErrorCode {
+    /// Returns the code of the error.
+    fn code(&self) -> i32;
+
+    /// Returns the optional details of the error.
+    fn details(&self) -> Option<String> {
+        None
+    }
+}
+
+/// How errors returned by scalar functions are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Store errors in the error column and continue with the next row.
+    #[default]
+    ErrorColumn,
+    /// Abort the batch with an [`Error::ComputeError`] on the first error.
+    ///
+    /// Table functions yield the error and end the iteration.
+    FailFast,
+}
+
+/// Returns the field of the error column of a function.
+pub fn error_field(function: &str) -> Field {
+    let metadata = HashMap::from([(FUNCTION_METADATA_KEY.to_string(), function.to_string())]);
+    Field::new("error", DataType::Struct(error_fields()), true).with_metadata(metadata)
+}
+
+/// Returns the fields of the error struct.
+fn error_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("row", DataType::Int32, false),
+        Field::new("code", DataType::Int32, false),
+        Field::new("message", DataType::Utf8, false),
+        Field::new("details", DataType::Utf8, true),
+    ])
+}
+
+/// A builder for the error column.
+#[derive(Debug)]
+pub struct ErrorBuilder {
+    rows: Int32Builder,
+    codes: Int32Builder,
+    messages: StringBuilder,
+    details: StringBuilder,
+    validity: BooleanBufferBuilder,
+    policy: ErrorPolicy,
+}
+
+impl ErrorBuilder {
+    /// Creates a new builder.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            rows: Int32Builder::with_capacity(capacity),
+            codes: Int32Builder::with_capacity(capacity),
+            messages: StringBuilder::with_capacity(capacity, capacity * 16),
+            details: StringBuilder::with_capacity(capacity, 0),
+            validity: BooleanBufferBuilder::new(capacity),
+            policy: ErrorPolicy::default(),
+        }
+    }
+
+    /// Sets the error policy.
+    pub fn with_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Appends an error caused by the input row `row`.
+    ///
+    /// Returns an error instead if the policy is [`ErrorPolicy::FailFast`].
+    pub fn append_error(
+        &mut self,
+        row: usize,
+        code: i32,
+        message: &str,
+        details: Option<&str>,
+    ) -> Result<()> {
+        if self.policy == ErrorPolicy::FailFast {
+            return Err(Error::ComputeError(match details {
+                Some(details) => format!("{message} (code {code}, row {row}): {details}"),
+                None => format!("{message} (code {code}, row {row})"),
+            }));
+        }
+        self.rows.append_value(row as i32);
+        self.codes.append_value(code);
+        self.messages.append_value(message);
+        self.details.append_option(details);
+        self.validity.append(true);
+        Ok(())
+    }
+
+    /// Appends a null value for a successful row.
+    pub fn append_null(&mut self) {
+        self.rows.append_value(0);
+        self.codes.append_value(UNKNOWN_ERROR_CODE);
+        self.messages.append_value("");
+        self.details.append_null();
+        self.validity.append(false);
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    /// Returns true if there is no value.
+    pub fn is_empty(&self) -> bool {
+        self.validity.is_empty()
+    }
+
+    /// Builds the struct array and resets the builder.
+    pub fn finish(&mut self) -> ArrayRef {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.rows.finish()),
+            Arc::new(self.codes.finish()),
+            Arc::new(self.messages.finish()),
+            Arc::new(self.details.finish()),
+        ];
+        let nulls = NullBuffer::new(self.validity.finish());
+        Arc::new(StructArray::new(error_fields(), columns, Some(nulls)))
+    }
+}
+
+/// A reference to an error, used to append the error code if the error type implements
+/// [`ErrorCode`].
+///
+/// The generated code calls `(&ErrorRef(&e)).append_to(builder, row)`. The method of
+/// [`AppendErrorCode`] is preferred by method resolution, and [`AppendError`] is the fallback.
+#[doc(hidden)]
+pub struct ErrorRef<'a, E>(pub &'a E);
+
+#[doc(hidden)]
+pub trait AppendErrorCode {
+    fn append_to(&self, builder: &mut ErrorBuilder, row: usize) -> Result<()>;
+}
+
+impl<E: ErrorCode + Display> AppendErrorCode for ErrorRef<'_, E> {
+    fn append_to(&self, builder: &mut ErrorBuilder, row: usize) -> Result<()> {
+        let details = self.0.details();
+        builder.append_error(row, self.0.code(), &self.0.to_string(), details.as_deref())
+    }
+}
+
+#[doc(hidden)]
+pub trait AppendError {
+    fn append_to(&self, builder: &mut ErrorBuilder, row: usize) -> Result<()>;
+}
+
+impl<E: Display> AppendError for &ErrorRef<'_, E> {
+    fn append_to(&self, builder: &mut ErrorBuilder, row: usize) -> Result<()> {
+        builder.append_error(row, UNKNOWN_ERROR_CODE, &self.0.to_string(), None)
+    }
+}
@@ -26,12 +26,13 @@ use arrow_ipc::{reader::FileReader, writer::FileWriter};
 ///
 /// # Changelog
 ///
+/// - 3.1: Add `record_batch_iterator_try_next` to report errors of table functions.
 /// - 3.0: Change type names in signatures.
 /// - 2.0: Add user defined struct type.
 /// - 1.0: Initial version.
 #[no_mangle]
 #[used]
-pub static ARROWUDF_VERSION_3_0: () = ();
+pub static ARROWUDF_VERSION_3_1: () = ();
 
 /// Allocate memory.
 ///
@@ -130,7 +131,7 @@ pub struct RecordBatchIter {
     /// The input record batch is borrowed by `iter`. Its lifetime must be longer than `iter`.
     _input: Box<RecordBatch>,
     /// This iterator borrows `input`.
-    iter: Box<dyn Iterator<Item = RecordBatch>>,
+    iter: Box<dyn Iterator<Item = Result<RecordBatch, Error>>>,
 }
 
 /// A wrapper for calling table functions from C.
@@ -194,30 +195,80 @@ fn call_table(function: TableFunction, input_bytes: &[u8]) -> Result<Box<RecordB
 /// The output record batch is written to the buffer pointed to by `out`.
 /// The caller is responsible for deallocating the output buffer.
 ///
+/// This function panics if the table function returns an error. Use
+/// [`record_batch_iterator_try_next`] instead, which is available since ABI version 3.1.
+///
 /// # Safety
 ///
 /// `iter` and `out` must be valid pointers.
 #[no_mangle]
 pub unsafe extern "C" fn record_batch_iterator_next(iter: *mut RecordBatchIter, out: *mut CSlice) {
     let iter = iter.as_mut().expect("null pointer");
-    if let Some(batch) = iter.iter.next() {
-        let mut buf = vec![];
-        let mut writer = FileWriter::try_new(&mut buf, &batch.schema()).unwrap();
-        writer.write(&batch).unwrap();
-        writer.finish().unwrap();
-        drop(writer);
-        let buf = buf.into_boxed_slice();
-
-        out.write(CSlice {
-            ptr: buf.as_ptr(),
-            len: buf.len(),
-        });
-        std::mem::forget(buf);
-    } else {
-        out.write(CSlice {
+    let buf = next_batch(iter).unwrap_or_else(|e| panic!("{e}"));
+    write_batch(buf, out);
+}
+
+/// Get the next record batch from the iterator.
+///
+/// The return value is 0 on success, -1 on error.
+/// If successful, the record batch is written to the buffer pointed to by `out`,
+/// or a null pointer at the end of the iteration.
+/// If failed, the error message is written to the buffer. The iteration ends after an error.
+/// The caller is responsible for deallocating the output buffer.
+///
+/// # Safety
+///
+/// `iter` and `out` must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn record_batch_iterator_try_next(
+    iter: *mut RecordBatchIter,
+    out: *mut CSlice,
+) -> i32 {
+    let iter = iter.as_mut().expect("null pointer");
+    match next_batch(iter) {
+        Ok(buf) => {
+            write_batch(buf, out);
+            0
+        }
+        Err(err) => {
+            let msg = err.to_string().into_boxed_str();
+            out.write(CSlice {
+                ptr: msg.as_ptr(),
+                len: msg.len(),
+            });
+            std::mem::forget(msg);
+            -1
+        }
+    }
+}
+
+/// Encode the next record batch of the iterator, or `None` at the end of the iteration.
+fn next_batch(iter: &mut RecordBatchIter) -> Result<Option<Box<[u8]>>, Error> {
+    let Some(batch) = iter.iter.next().transpose()? else {
+        return Ok(None);
+    };
+    let mut buf = vec![];
+    let mut writer = FileWriter::try_new(&mut buf, &batch.schema())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    drop(writer);
+    Ok(Some(buf.into_boxed_slice()))
+}
+
+/// Write the encoded record batch to `out`, or a null pointer if `None`.
+unsafe fn write_batch(buf: Option<Box<[u8]>>, out: *mut CSlice) {
+    match buf {
+        Some(buf) => {
+            out.write(CSlice {
+                ptr: buf.as_ptr(),
+                len: buf.len(),
+            });
+            std::mem::forget(buf);
+        }
+        None => out.write(CSlice {
             ptr: std::ptr::null(),
             len: 0,
-        });
+        }),
     }
 }
 
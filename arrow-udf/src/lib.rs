@@ -14,32 +14,107 @@
 
 #![doc = include_str!("../README.md")]
 
-use arrow_array::RecordBatch;
+use arrow_array::{Array, ArrayRef, BooleanArray, RecordBatch};
 pub use arrow_schema::ArrowError as Error;
-pub use arrow_udf_macros::function;
+pub use arrow_udf_macros::{aggregate, function};
+pub use context::Context;
+pub use error::{ErrorCode, ErrorPolicy};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 
 /// A specialized `Result` type for Arrow UDF operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
+mod any;
+mod context;
+mod decimal;
+mod enum_type;
+pub mod error;
 pub mod ffi;
-#[cfg(feature = "global_registry")]
+#[cfg(feature = "serde")]
+mod serde_type;
 pub mod sig;
+mod table;
 pub mod types;
 
 /// A scalar function that operates on a record batch.
 pub type ScalarFunction = fn(input: &RecordBatch) -> Result<RecordBatch>;
 
+/// An async scalar function that operates on a record batch.
+///
+/// Rows are evaluated concurrently, and the output preserves the order of input rows.
+pub type AsyncScalarFunction =
+    for<'a> fn(
+        input: &'a RecordBatch,
+    ) -> Pin<Box<dyn Future<Output = Result<RecordBatch>> + Send + 'a>>;
+
 /// A table function that operates on a record batch and returns an iterator of record batches.
-pub type TableFunction =
-    for<'a> fn(input: &'a RecordBatch) -> Result<Box<dyn Iterator<Item = RecordBatch> + 'a>>;
+///
+/// The iteration ends after an error is returned.
+pub type TableFunction = for<'a> fn(
+    input: &'a RecordBatch,
+) -> Result<Box<dyn Iterator<Item = Result<RecordBatch>> + 'a>>;
+
+/// A scalar function that may capture state, e.g. a function loaded at runtime.
+pub type DynScalarFunction = Arc<dyn Fn(&RecordBatch) -> Result<RecordBatch> + Send + Sync>;
+
+/// A table function that may capture state, e.g. a function loaded at runtime.
+pub type DynTableFunction = Arc<
+    dyn for<'a> Fn(&'a RecordBatch) -> Result<Box<dyn Iterator<Item = Result<RecordBatch>> + 'a>>
+        + Send
+        + Sync,
+>;
+
+/// An aggregate function that operates on state arrays.
+///
+/// A state array contains one state per row. Functions that produce a single state
+/// (`create_state`, `accumulate`, `accumulate_or_retract` and `merge`) return an array of length 1.
+#[derive(Debug, Clone, Copy)]
+pub struct AggregateFunction {
+    /// Create a new state.
+    pub create_state: fn() -> Result<ArrayRef>,
+    /// Accumulate the input rows into the state, returning the updated state.
+    pub accumulate: fn(state: &dyn Array, input: &RecordBatch) -> Result<ArrayRef>,
+    /// Accumulate or retract the input rows, returning the updated state.
+    ///
+    /// The `ops` is a boolean array that indicates whether to accumulate or retract each row.
+    /// `false` for accumulate and `true` for retract.
+    ///
+    /// `None` if the function does not support retraction.
+    #[allow(clippy::type_complexity)]
+    pub accumulate_or_retract:
+        Option<fn(state: &dyn Array, ops: &BooleanArray, input: &RecordBatch) -> Result<ArrayRef>>,
+    /// Merge all states into one.
+    ///
+    /// `None` if the function does not support merging.
+    pub merge: Option<fn(states: &dyn Array) -> Result<ArrayRef>>,
+    /// Get the result of each state.
+    pub finish: fn(states: &dyn Array) -> Result<ArrayRef>,
+}
 
 /// Internal APIs used by macros.
 #[doc(hidden)]
 pub mod codegen {
+    pub use crate::any::{AnyArrayBuilder, AnyBuilder};
+    pub use crate::decimal::{
+        decimal_from_i128, decimal_from_i256, decimal_to_i128, decimal_to_i256,
+    };
+    pub use crate::enum_type::{
+        dictionary_to_struct, struct_to_dictionary, struct_to_union, union_fields, union_to_struct,
+    };
+    pub use crate::error::{error_field, AppendError, AppendErrorCode, ErrorBuilder, ErrorRef};
+    #[cfg(feature = "serde")]
+    pub use crate::serde_type::parse_json;
+    pub use crate::sig::defaults::fill_defaults;
+    pub use crate::table::{flatten_struct_array, flatten_struct_field};
     pub use arrow_arith;
     pub use arrow_array;
+    pub use arrow_buffer;
+    pub use arrow_cast;
     pub use arrow_schema;
     pub use chrono;
+    pub use futures_util;
     pub use genawaiter;
     #[cfg(feature = "global_registry")]
     pub use linkme;
@@ -0,0 +1,776 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Serde types as `json` and struct values.
+//!
+//! [`Json<T>`] is stored as a `json` value. The struct type of [`Serde<T>`] is inferred by tracing
+//! the `Deserialize` implementation of `T`, and values are converted through
+//! [`serde_json::Value`]:
+//!
+//! | Serde Type                  | Data Type                      |
+//! | --------------------------- | ------------------------------ |
+//! | `bool`                      | `boolean`                      |
+//! | `i8` .. `i64`, `u8` .. `u64` | `int8` .. `int64`, `uint8` .. `uint64` |
+//! | `f32`, `f64`                | `float32`, `float64`           |
+//! | `char`, `String`            | `string`                       |
+//! | bytes                       | `binary`                       |
+//! | `Option<T>`                 | `T`                            |
+//! | `Vec<T>`                    | `T[]`                          |
+//! | struct                      | `struct`                       |
+//! | unit variants of enum       | `string`                       |
+//! | `serde_json::Value`         | `json`                         |
+
+use std::any::{type_name, TypeId};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
+
+use arrow_array::builder::*;
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::{Array, ArrayRef, StructArray};
+use arrow_schema::{DataType, Field, Fields};
+use once_cell::sync::Lazy;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::types::{FromStructArray, StructType};
+use crate::Error;
+
+/// The metadata of `json` fields.
+const JSON_EXTENSION: (&str, &str) = ("ARROW:extension:name", "arrowudf.json");
+
+/// The maximum depth of nested types, to stop tracing recursive types.
+const MAX_DEPTH: usize = 32;
+
+/// A wrapper to use a serde type as a `json` value.
+///
+/// Arguments of `json` type are deserialized into `T` if the argument type is `Json<T>`, and
+/// return values are serialized with `serde_json`. Functions taking `Json<T>` have an error column,
+/// where arguments that fail to deserialize are reported.
+///
+/// # Example
+///
+/// ```
+/// use arrow_udf::function;
+/// use arrow_udf::types::Json;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     retries: u32,
+/// }
+///
+/// #[function("retries(json) -> int")]
+/// fn retries(config: Json<Config>) -> i32 {
+///     config.0.retries as i32
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> Display for Json<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string(&self.0).map_err(|_| std::fmt::Error)?;
+        f.write_str(&s)
+    }
+}
+
+impl<T: Serialize> Serialize for Json<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Json<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Json)
+    }
+}
+
+/// Parses a `json` argument of type `Json<T>`.
+pub fn parse_json<T: DeserializeOwned>(s: &str) -> crate::Result<Json<T>> {
+    serde_json::from_str(s)
+        .map(Json)
+        .map_err(|e| Error::InvalidArgumentError(format!("invalid json: {e}")))
+}
+
+/// A wrapper to use a serde type as a struct type.
+///
+/// The fields are inferred from the `Deserialize` implementation of `T`, so `T` must be a struct
+/// whose fields are supported types. Values are serialized and deserialized by serde, so serde
+/// attributes like `rename` and `skip` apply.
+///
+/// The type is [`FALLIBLE`](StructType::FALLIBLE): values that fail to convert, e.g. a null in a
+/// non-`Option` field or a NaN, which is serialized as null, are reported in the error column.
+///
+/// If the fields can not be inferred, [`fields`](StructType::fields) is empty and functions using
+/// the type return an [`Error::InvalidArgumentError`].
+///
+/// # Example
+///
+/// ```
+/// use arrow_udf::function;
+/// use arrow_udf::types::Serde;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct User {
+///     name: String,
+///     age: Option<u32>,
+/// }
+///
+/// type UserStruct = Serde<User>;
+///
+/// #[function("user_name(struct UserStruct) -> string")]
+/// fn user_name(user: UserStruct) -> String {
+///     user.0.name
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Serde<T>(pub T);
+
+impl<T: Serialize + DeserializeOwned + 'static> StructType for Serde<T> {
+    const FALLIBLE: bool = true;
+
+    fn fields() -> Fields {
+        // the error is returned by `encode` and `decode` when the function is called
+        fields_of::<T>().unwrap_or_default()
+    }
+
+    fn encode(array: StructArray) -> crate::Result<ArrayRef> {
+        fields_of::<T>()?;
+        Ok(Arc::new(array))
+    }
+
+    fn decode(array: &ArrayRef) -> crate::Result<ArrayRef> {
+        fields_of::<T>()?;
+        Ok(array.clone())
+    }
+
+    fn append_to(self, builder: &mut StructBuilder) -> crate::Result<()> {
+        let fields = Self::fields();
+        // the value is checked before appending, so that builders are not partially appended
+        let map = serde_json::to_value(&self.0)
+            .map_err(|e| Error::InvalidArgumentError(format!("failed to serialize value: {e}")))
+            .and_then(|value| match value {
+                Value::Object(map) => check_object(&fields, &map).map(|_| map),
+                v => Err(Error::InvalidArgumentError(format!(
+                    "expect a struct for {}, but got {v}",
+                    type_name::<T>()
+                ))),
+            });
+        match map {
+            Ok(map) => {
+                append_object(builder, &fields, &map);
+                Ok(())
+            }
+            Err(e) => {
+                append_object_null(builder, &fields);
+                Err(e)
+            }
+        }
+    }
+
+    fn append_null(builder: &mut StructBuilder) {
+        append_object_null(builder, &Self::fields());
+    }
+}
+
+impl<'a, T: DeserializeOwned> FromStructArray<'a> for Serde<T> {
+    fn from_struct_array(array: &'a StructArray, index: usize) -> crate::Result<Self> {
+        let value = Value::Object(read_object(array, index)?);
+        serde_json::from_value(value).map(Serde).map_err(|e| {
+            Error::InvalidArgumentError(format!("failed to deserialize {}: {e}", type_name::<T>()))
+        })
+    }
+}
+
+/// Returns the fields of a serde struct. The result is cached for each type.
+fn fields_of<T: DeserializeOwned + 'static>() -> crate::Result<Fields> {
+    static CACHE: Lazy<RwLock<HashMap<TypeId, Fields>>> = Lazy::new(Default::default);
+
+    let id = TypeId::of::<T>();
+    if let Some(fields) = CACHE.read().unwrap().get(&id) {
+        return Ok(fields.clone());
+    }
+    let mut tracer = Tracer::default();
+    T::deserialize(&mut tracer).map_err(|e| {
+        Error::InvalidArgumentError(format!(
+            "failed to infer struct type of {}: {e}",
+            type_name::<T>()
+        ))
+    })?;
+    let fields = match tracer.field.map(|f| f.data_type().clone()) {
+        Some(DataType::Struct(fields)) => fields,
+        _ => {
+            return Err(Error::InvalidArgumentError(format!(
+                "expect a struct for {}",
+                type_name::<T>()
+            )))
+        }
+    };
+    CACHE.write().unwrap().insert(id, fields.clone());
+    Ok(fields)
+}
+
+/// Checks that a JSON object can be appended to a struct builder.
+fn check_object(fields: &Fields, map: &Map<String, Value>) -> crate::Result<()> {
+    for field in fields {
+        check_value(field, map.get(field.name()).unwrap_or(&Value::Null))?;
+    }
+    Ok(())
+}
+
+/// Checks that a JSON value can be appended to the builder of `field`.
+fn check_value(field: &Field, value: &Value) -> crate::Result<()> {
+    let valid = match (field.data_type(), value) {
+        (_, Value::Null) | (DataType::Null, _) => true,
+        (DataType::Boolean, v) => v.is_boolean(),
+        (DataType::Int8, v) => as_int::<i8>(v).is_some(),
+        (DataType::Int16, v) => as_int::<i16>(v).is_some(),
+        (DataType::Int32, v) => as_int::<i32>(v).is_some(),
+        (DataType::Int64, v) => v.is_i64(),
+        (DataType::UInt8, v) => as_uint::<u8>(v).is_some(),
+        (DataType::UInt16, v) => as_uint::<u16>(v).is_some(),
+        (DataType::UInt32, v) => as_uint::<u32>(v).is_some(),
+        (DataType::UInt64, v) => v.is_u64(),
+        (DataType::Float32 | DataType::Float64, v) => v.is_number(),
+        (DataType::Utf8, _) if is_json(field) => true,
+        (DataType::Utf8, v) => v.is_string(),
+        (DataType::Binary, Value::Array(bytes)) => bytes.iter().all(|b| as_uint::<u8>(b).is_some()),
+        (DataType::List(item), Value::Array(values)) => {
+            return values.iter().try_for_each(|v| check_value(item, v));
+        }
+        (DataType::Struct(fields), Value::Object(map)) => return check_object(fields, map),
+        _ => false,
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(mismatch(field, value)),
+    }
+}
+
+/// Appends a JSON object to a struct builder.
+///
+/// The object must be checked by [`check_object`].
+fn append_object(builder: &mut StructBuilder, fields: &Fields, map: &Map<String, Value>) {
+    for (i, field) in fields.iter().enumerate() {
+        let value = map.get(field.name()).unwrap_or(&Value::Null);
+        append_value(field_builder(builder, i, field.data_type()), field, value);
+    }
+    builder.append(true);
+}
+
+/// Appends a null value to a struct builder.
+fn append_object_null(builder: &mut StructBuilder, fields: &Fields) {
+    for (i, field) in fields.iter().enumerate() {
+        append_value(
+            field_builder(builder, i, field.data_type()),
+            field,
+            &Value::Null,
+        );
+    }
+    builder.append(false);
+}
+
+/// Appends a JSON value to the builder of `field`.
+///
+/// The value must be checked by [`check_value`].
+fn append_value(builder: &mut dyn ArrayBuilder, field: &Field, value: &Value) {
+    macro_rules! append_primitive {
+        ($builder:ty, $value:expr) => {{
+            let builder = downcast::<$builder>(builder);
+            match value {
+                Value::Null => builder.append_null(),
+                v => builder.append_value($value(v).expect("unchecked value")),
+            }
+        }};
+    }
+    match field.data_type() {
+        DataType::Null => downcast::<NullBuilder>(builder).append_null(),
+        DataType::Boolean => append_primitive!(BooleanBuilder, Value::as_bool),
+        DataType::Int8 => append_primitive!(Int8Builder, |v: &Value| as_int(v)),
+        DataType::Int16 => append_primitive!(Int16Builder, |v: &Value| as_int(v)),
+        DataType::Int32 => append_primitive!(Int32Builder, |v: &Value| as_int(v)),
+        DataType::Int64 => append_primitive!(Int64Builder, Value::as_i64),
+        DataType::UInt8 => append_primitive!(UInt8Builder, |v: &Value| as_uint(v)),
+        DataType::UInt16 => append_primitive!(UInt16Builder, |v: &Value| as_uint(v)),
+        DataType::UInt32 => append_primitive!(UInt32Builder, |v: &Value| as_uint(v)),
+        DataType::UInt64 => append_primitive!(UInt64Builder, Value::as_u64),
+        DataType::Float32 => {
+            append_primitive!(Float32Builder, |v: &Value| v.as_f64().map(|v| v as f32))
+        }
+        DataType::Float64 => append_primitive!(Float64Builder, Value::as_f64),
+        DataType::Utf8 if is_json(field) => {
+            let builder = downcast::<StringBuilder>(builder);
+            match value {
+                Value::Null => builder.append_null(),
+                v => builder.append_value(v.to_string()),
+            }
+        }
+        DataType::Utf8 => append_primitive!(StringBuilder, Value::as_str),
+        DataType::Binary => append_primitive!(BinaryBuilder, |v: &Value| v
+            .as_array()?
+            .iter()
+            .map(as_uint::<u8>)
+            .collect::<Option<Vec<u8>>>()),
+        DataType::List(item) => {
+            let builder = downcast::<ListBuilder<Box<dyn ArrayBuilder>>>(builder);
+            match value {
+                Value::Null => builder.append_null(),
+                Value::Array(values) => {
+                    for v in values {
+                        append_value(builder.values(), item, v);
+                    }
+                    builder.append(true);
+                }
+                _ => unreachable!("unchecked value"),
+            }
+        }
+        DataType::Struct(fields) => {
+            let builder = downcast::<StructBuilder>(builder);
+            match value {
+                Value::Null => append_object_null(builder, fields),
+                Value::Object(map) => append_object(builder, fields, map),
+                _ => unreachable!("unchecked value"),
+            }
+        }
+        t => unreachable!("unsupported type: {t}"),
+    }
+}
+
+/// Returns the `i`-th field builder of a struct builder.
+fn field_builder<'a>(
+    builder: &'a mut StructBuilder,
+    i: usize,
+    data_type: &DataType,
+) -> &'a mut dyn ArrayBuilder {
+    macro_rules! field_builder {
+        ($($variant:pat => $builder:ty),* $(,)?) => {
+            match data_type {
+                $($variant => builder.field_builder::<$builder>(i).unwrap(),)*
+                t => unreachable!("unsupported type: {t}"),
+            }
+        };
+    }
+    field_builder! {
+        DataType::Null => NullBuilder,
+        DataType::Boolean => BooleanBuilder,
+        DataType::Int8 => Int8Builder,
+        DataType::Int16 => Int16Builder,
+        DataType::Int32 => Int32Builder,
+        DataType::Int64 => Int64Builder,
+        DataType::UInt8 => UInt8Builder,
+        DataType::UInt16 => UInt16Builder,
+        DataType::UInt32 => UInt32Builder,
+        DataType::UInt64 => UInt64Builder,
+        DataType::Float32 => Float32Builder,
+        DataType::Float64 => Float64Builder,
+        DataType::Utf8 => StringBuilder,
+        DataType::Binary => BinaryBuilder,
+        DataType::List(_) => ListBuilder<Box<dyn ArrayBuilder>>,
+        DataType::Struct(_) => StructBuilder,
+    }
+}
+
+fn downcast<T: ArrayBuilder>(builder: &mut dyn ArrayBuilder) -> &mut T {
+    builder
+        .as_any_mut()
+        .downcast_mut::<T>()
+        .expect("downcast builder")
+}
+
+fn as_int<T: TryFrom<i64>>(value: &Value) -> Option<T> {
+    value.as_i64()?.try_into().ok()
+}
+
+fn as_uint<T: TryFrom<u64>>(value: &Value) -> Option<T> {
+    value.as_u64()?.try_into().ok()
+}
+
+fn mismatch(field: &Field, value: &Value) -> Error {
+    Error::InvalidArgumentError(format!(
+        "expect {} for field {:?}, but got {value}",
+        field.data_type(),
+        field.name()
+    ))
+}
+
+fn is_json(field: &Field) -> bool {
+    field.metadata().get(JSON_EXTENSION.0).map(|s| s.as_str()) == Some(JSON_EXTENSION.1)
+}
+
+/// Reads the value at `index` of a struct array as a JSON object.
+fn read_object(array: &StructArray, index: usize) -> crate::Result<Map<String, Value>> {
+    array
+        .fields()
+        .iter()
+        .zip(array.columns())
+        .map(|(field, column)| Ok((field.name().clone(), read_value(column, field, index)?)))
+        .collect()
+}
+
+/// Reads the value at `index` of an array as a JSON value.
+fn read_value(array: &dyn Array, field: &Field, index: usize) -> crate::Result<Value> {
+    if array.is_null(index) {
+        return Ok(Value::Null);
+    }
+    macro_rules! primitive {
+        ($type:ty) => {
+            Value::from(array.as_primitive::<$type>().value(index))
+        };
+    }
+    Ok(match array.data_type() {
+        DataType::Null => Value::Null,
+        DataType::Boolean => Value::Bool(array.as_boolean().value(index)),
+        DataType::Int8 => primitive!(Int8Type),
+        DataType::Int16 => primitive!(Int16Type),
+        DataType::Int32 => primitive!(Int32Type),
+        DataType::Int64 => primitive!(Int64Type),
+        DataType::UInt8 => primitive!(UInt8Type),
+        DataType::UInt16 => primitive!(UInt16Type),
+        DataType::UInt32 => primitive!(UInt32Type),
+        DataType::UInt64 => primitive!(UInt64Type),
+        DataType::Float32 => {
+            Number::from_f64(array.as_primitive::<Float32Type>().value(index) as f64)
+                .map_or(Value::Null, Value::Number)
+        }
+        DataType::Float64 => Number::from_f64(array.as_primitive::<Float64Type>().value(index))
+            .map_or(Value::Null, Value::Number),
+        DataType::Utf8 if is_json(field) => {
+            let s = array.as_string::<i32>().value(index);
+            s.parse()
+                .map_err(|e| Error::InvalidArgumentError(format!("invalid json: {e}")))?
+        }
+        DataType::Utf8 => Value::from(array.as_string::<i32>().value(index)),
+        DataType::Binary => Value::from(array.as_binary::<i32>().value(index)),
+        DataType::List(item) => {
+            let list = array.as_list::<i32>().value(index);
+            let values = (0..list.len()).map(|i| read_value(&list, item, i));
+            Value::Array(values.collect::<crate::Result<_>>()?)
+        }
+        DataType::Struct(_) => Value::Object(read_object(array.as_struct(), index)?),
+        t => {
+            return Err(Error::InvalidArgumentError(format!(
+                "unsupported type: {t}"
+            )))
+        }
+    })
+}
+
+/// A deserializer that records the data type of the deserialized value.
+///
+/// The visitor is fed with a sample value of each type: zero, empty string, one element for
+/// sequences and the first variant for enums.
+#[derive(Default)]
+struct Tracer {
+    /// The traced field named "item".
+    field: Option<Field>,
+    depth: usize,
+}
+
+#[derive(Debug)]
+struct TraceError(String);
+
+impl Display for TraceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl de::Error for TraceError {
+    fn custom<T: Display>(msg: T) -> Self {
+        TraceError(msg.to_string())
+    }
+}
+
+impl Tracer {
+    fn record(&mut self, data_type: DataType) {
+        self.field = Some(Field::new("item", data_type, true));
+    }
+
+    /// Traces a nested value with a new tracer.
+    fn trace<'de, S: de::DeserializeSeed<'de>>(
+        &self,
+        seed: S,
+    ) -> Result<(S::Value, Field), TraceError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(TraceError("recursive types are not supported".into()));
+        }
+        let mut tracer = Tracer {
+            field: None,
+            depth: self.depth + 1,
+        };
+        let value = seed.deserialize(&mut tracer)?;
+        let field = tracer
+            .field
+            .ok_or_else(|| TraceError("failed to infer type".into()))?;
+        Ok((value, field))
+    }
+}
+
+macro_rules! trace_primitive {
+    ($($method:ident => $data_type:expr, $visit:ident($($value:expr)?);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.record($data_type);
+                visitor.$visit($($value)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Tracer {
+    type Error = TraceError;
+
+    trace_primitive! {
+        deserialize_bool => DataType::Boolean, visit_bool(false);
+        deserialize_i8 => DataType::Int8, visit_i8(0);
+        deserialize_i16 => DataType::Int16, visit_i16(0);
+        deserialize_i32 => DataType::Int32, visit_i32(0);
+        deserialize_i64 => DataType::Int64, visit_i64(0);
+        deserialize_u8 => DataType::UInt8, visit_u8(0);
+        deserialize_u16 => DataType::UInt16, visit_u16(0);
+        deserialize_u32 => DataType::UInt32, visit_u32(0);
+        deserialize_u64 => DataType::UInt64, visit_u64(0);
+        deserialize_f32 => DataType::Float32, visit_f32(0.0);
+        deserialize_f64 => DataType::Float64, visit_f64(0.0);
+        deserialize_char => DataType::Utf8, visit_char(' ');
+        deserialize_str => DataType::Utf8, visit_str("");
+        deserialize_string => DataType::Utf8, visit_str("");
+        deserialize_bytes => DataType::Binary, visit_bytes(&[]);
+        deserialize_byte_buf => DataType::Binary, visit_bytes(&[]);
+        deserialize_unit => DataType::Null, visit_unit();
+    }
+
+    /// Self-describing types like `serde_json::Value` are stored as `json`.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.field = Some(
+            Field::new("item", DataType::Utf8, true).with_metadata(HashMap::from([(
+                JSON_EXTENSION.0.to_string(),
+                JSON_EXTENSION.1.to_string(),
+            )])),
+        );
+        visitor.visit_unit()
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut access = SeqTracer {
+            tracer: self,
+            item: None,
+        };
+        let value = visitor.visit_seq(&mut access)?;
+        let item = access
+            .item
+            .ok_or_else(|| TraceError("failed to infer element type".into()))?;
+        self.record(DataType::List(Arc::new(item)));
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(TraceError("tuples are not supported".into()))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(TraceError("tuple structs are not supported".into()))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(TraceError("maps are not supported".into()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let mut access = StructTracer {
+            tracer: self,
+            names: fields,
+            fields: vec![],
+        };
+        let value = visitor.visit_map(&mut access)?;
+        let fields = Fields::from(access.fields);
+        self.record(DataType::Struct(fields));
+        Ok(value)
+    }
+
+    /// Enums with only unit variants are stored as the variant names.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let variant = variants
+            .first()
+            .ok_or_else(|| TraceError("empty enums are not supported".into()))?;
+        self.record(DataType::Utf8);
+        visitor.visit_enum(EnumTracer(variant))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(TraceError("unexpected identifier".into()))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// Yields one element to trace the element type.
+struct SeqTracer<'a> {
+    tracer: &'a mut Tracer,
+    item: Option<Field>,
+}
+
+impl<'de> de::SeqAccess<'de> for &mut SeqTracer<'_> {
+    type Error = TraceError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.item.is_some() {
+            return Ok(None);
+        }
+        let (value, item) = self.tracer.trace(seed)?;
+        self.item = Some(item);
+        Ok(Some(value))
+    }
+}
+
+/// Yields all fields of a struct to trace their types.
+struct StructTracer<'a> {
+    tracer: &'a mut Tracer,
+    names: &'static [&'static str],
+    fields: Vec<Field>,
+}
+
+impl<'de> de::MapAccess<'de> for &mut StructTracer<'_> {
+    type Error = TraceError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some(name) = self.names.get(self.fields.len()) else {
+            return Ok(None);
+        };
+        seed.deserialize(name.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let name = self.names[self.fields.len()];
+        let (value, field) = self.tracer.trace(seed)?;
+        self.fields.push(
+            Field::new(name, field.data_type().clone(), true)
+                .with_metadata(field.metadata().clone()),
+        );
+        Ok(value)
+    }
+}
+
+/// Selects the first variant of an enum, which must be a unit variant.
+struct EnumTracer(&'static str);
+
+impl<'de> de::EnumAccess<'de> for EnumTracer {
+    type Error = TraceError;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), Self::Error> {
+        let value = seed.deserialize(self.0.into_deserializer())?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for EnumTracer {
+    type Error = TraceError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        _seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        Err(self.unsupported())
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported())
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported())
+    }
+}
+
+impl EnumTracer {
+    fn unsupported(&self) -> TraceError {
+        TraceError(format!(
+            "only unit variants are supported in enums, but got {:?}",
+            self.0
+        ))
+    }
+}
@@ -16,8 +16,11 @@
 //!
 //! # Example
 //!
+//! With the `global_registry` feature, functions defined by `#[function]` are registered in the
+//! global [`REGISTRY`]:
+//!
 //! ```
-//! use arrow_udf::{function, sig::REGISTRY};
+//! use arrow_udf::function;
 //! use arrow_schema::{DataType::Int32, Field};
 //!
 //! // define a function
@@ -27,15 +30,64 @@
 //! }
 //!
 //! // lookup the function by name and types
+//! # #[cfg(feature = "global_registry")]
+//! # {
 //! let int32 = Field::new("", Int32, true);
-//! let sig = REGISTRY.get("add", &[int32.clone(), int32.clone()], &int32).unwrap();
+//! let sig = arrow_udf::sig::REGISTRY.get("add", &[int32.clone(), int32.clone()], &int32).unwrap();
+//! # }
 //! ```
+//!
+//! Functions can also be registered at runtime. To combine them with the global registry, start
+//! from a clone of it:
+//!
+//! ```
+//! use std::sync::Arc;
+//! use arrow_schema::{DataType::Int32, Field};
+//! use arrow_udf::sig::{FunctionKind, FunctionRegistry, FunctionSignature, Volatility};
+//!
+//! let int32 = Field::new("", Int32, true);
+//! let mut registry = FunctionRegistry::new();
+//! registry.register_in(
+//!     "ext",
+//!     FunctionSignature {
+//!         name: "identity".into(),
+//!         arg_types: vec![int32.clone()].into(),
+//!         variadic: false,
+//!         variadic_type: None,
+//!         defaults: vec![],
+//!         return_type: int32.clone(),
+//!         type_infer: None,
+//!         function: FunctionKind::DynScalar(Arc::new(|input| Ok(input.clone()))),
+//!         volatility: Volatility::Immutable,
+//!         strict: true,
+//!         description: Some("Returns the argument.".into()),
+//!         examples: vec!["ext.identity(1) = 1".into()],
+//!     },
+//! );
+//! assert!(registry.get("ext.identity", &[int32.clone()], &int32).is_some());
+//! assert!(registry.get("identity", &[int32.clone()], &int32).is_none());
+//! ```
+//!
+//! [`FunctionRegistry::resolve_with_coercion`] looks up functions with implicit casts of arguments,
+//! e.g. `ext.identity` with an `int16` argument resolves to the `int32` signature above.
 
-use super::{ScalarFunction, TableFunction};
-use arrow_schema::{Field, Fields};
+mod catalog;
+mod coerce;
+pub(crate) mod defaults;
+mod named;
+
+use super::{
+    AggregateFunction, AsyncScalarFunction, DynScalarFunction, DynTableFunction, Result,
+    ScalarFunction, TableFunction,
+};
+use arrow_array::{RecordBatch, RecordBatchOptions};
+use arrow_schema::{DataType, Field, Fields, Schema};
+use std::borrow::{Borrow, Cow};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// A function signature.
+#[derive(Clone)]
 pub struct FunctionSignature {
     /// The name of the function.
     pub name: String,
@@ -46,17 +98,68 @@ pub struct FunctionSignature {
     /// Whether the function is variadic.
     pub variadic: bool,
 
+    /// The type of variadic arguments, or `None` if they can be of any type.
+    pub variadic_type: Option<Field>,
+
+    /// The default values of the last `defaults.len()` arguments, as SQL literals, e.g. `0` or `'abc'`.
+    ///
+    /// These arguments can be omitted in calls.
+    pub defaults: Vec<String>,
+
     /// The return type.
+    ///
+    /// For functions returning `any` or `anyarray`, the concrete type is inferred by `type_infer`.
     pub return_type: Field,
 
+    /// The function to infer the return type from argument types.
+    ///
+    /// `Some` if the function returns `any` or `anyarray`.
+    pub type_infer: Option<TypeInferFunction>,
+
     /// The function
     pub function: FunctionKind,
+
+    /// The volatility of the function.
+    pub volatility: Volatility,
+
+    /// Whether the function returns null if any argument is null, without being called.
+    pub strict: bool,
+
+    /// The description of the function, taken from the doc comment of `#[function]`.
+    pub description: Option<String>,
+
+    /// Example invocations of the function, e.g. `gcd(15, 25) = 5`.
+    pub examples: Vec<String>,
 }
 
+/// The volatility of a function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Volatility {
+    /// The function always returns the same result for the same arguments.
+    /// It can be evaluated at planning time.
+    #[default]
+    Immutable,
+    /// The function returns the same result for the same arguments within a query,
+    /// e.g. it depends on the [`Context`](crate::Context).
+    Stable,
+    /// The function may return different results for the same arguments, e.g. `random()`.
+    Volatile,
+}
+
+/// A function to infer the return type from argument types.
+pub type TypeInferFunction = fn(arg_types: &[DataType]) -> Result<DataType>;
+
 /// Function pointer.
+#[derive(Clone)]
 pub enum FunctionKind {
     Scalar(ScalarFunction),
+    AsyncScalar(AsyncScalarFunction),
     Table(TableFunction),
+    Aggregate(AggregateFunction),
+    /// A scalar function registered at runtime.
+    DynScalar(DynScalarFunction),
+    /// A table function registered at runtime.
+    DynTable(DynTableFunction),
 }
 
 impl FunctionKind {
@@ -65,11 +168,21 @@ impl FunctionKind {
         matches!(self, Self::Scalar(_))
     }
 
+    /// Check if the function is an async scalar function.
+    pub fn is_async_scalar(&self) -> bool {
+        matches!(self, Self::AsyncScalar(_))
+    }
+
     /// Check if the function is a table function.
     pub fn is_table(&self) -> bool {
         matches!(self, Self::Table(_))
     }
 
+    /// Check if the function is an aggregate function.
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self, Self::Aggregate(_))
+    }
+
     /// Convert to a scalar function.
     pub fn as_scalar(&self) -> Option<ScalarFunction> {
         match self {
@@ -78,6 +191,14 @@ impl FunctionKind {
         }
     }
 
+    /// Convert to an async scalar function.
+    pub fn as_async_scalar(&self) -> Option<AsyncScalarFunction> {
+        match self {
+            Self::AsyncScalar(f) => Some(*f),
+            _ => None,
+        }
+    }
+
     /// Convert to a table function.
     pub fn as_table(&self) -> Option<TableFunction> {
         match self {
@@ -85,55 +206,312 @@ impl FunctionKind {
             _ => None,
         }
     }
+
+    /// Convert to an aggregate function.
+    pub fn as_aggregate(&self) -> Option<AggregateFunction> {
+        match self {
+            Self::Aggregate(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// Convert a scalar function or a runtime scalar function to a runtime scalar function.
+    pub fn as_dyn_scalar(&self) -> Option<DynScalarFunction> {
+        match self {
+            Self::Scalar(f) => Some(Arc::new(*f)),
+            Self::DynScalar(f) => Some(f.clone()),
+            _ => None,
+        }
+    }
+
+    /// Convert a table function or a runtime table function to a runtime table function.
+    pub fn as_dyn_table(&self) -> Option<DynTableFunction> {
+        match self {
+            Self::Table(f) => Some(Arc::new(*f)),
+            Self::DynTable(f) => Some(f.clone()),
+            _ => None,
+        }
+    }
 }
 
 impl FunctionSignature {
     /// Check if the function signature matches the given argument types and return type.
     fn matches(&self, arg_types: &[Field], return_type: &Field) -> bool {
-        if !(self.return_type.data_type() == return_type.data_type()
-            && self.return_type.metadata() == return_type.metadata())
-        {
+        if !self.matches_args(arg_types) {
             return false;
         }
-        if arg_types.len() < self.arg_types.len() {
+        if self.type_infer.is_none() {
+            return type_matches(&self.return_type, return_type);
+        }
+        match self.infer_return_type(arg_types) {
+            Ok(ty) => data_type_matches(ty.data_type(), return_type.data_type()),
+            Err(_) => false,
+        }
+    }
+
+    /// Check if the function signature matches the given argument types.
+    ///
+    /// All `any` arguments and the elements of `anyarray` arguments must have the same type.
+    fn matches_args(&self, arg_types: &[Field]) -> bool {
+        if arg_types.len() < self.min_args() {
             return false;
         }
+        let mut any_type = None;
         for (target, ty) in self.arg_types.iter().zip(arg_types) {
-            if !(target.data_type() == ty.data_type() && target.metadata() == ty.metadata()) {
-                return false;
+            let elem_type = match extension_name(target) {
+                Some("arrowudf.any") => ty.data_type(),
+                Some("arrowudf.anyarray") => match ty.data_type() {
+                    DataType::List(field) => field.data_type(),
+                    _ => return false,
+                },
+                _ if type_matches(target, ty) => continue,
+                _ => return false,
+            };
+            match any_type {
+                Some(t) if t != elem_type => return false,
+                _ => any_type = Some(elem_type),
+            }
+        }
+        if !self.variadic {
+            return arg_types.len() <= self.arg_types.len();
+        }
+        match &self.variadic_type {
+            Some(target) => arg_types
+                .iter()
+                .skip(self.arg_types.len())
+                .all(|ty| type_matches(target, ty)),
+            None => true,
+        }
+    }
+
+    /// Check if the function signature matches the given argument types after implicit casts.
+    ///
+    /// Returns the total cost of the casts and the type to cast each argument to.
+    /// `any` and `anyarray` arguments and untyped variadic arguments are never cast.
+    fn coerce_args(&self, arg_types: &[Field]) -> Option<(u32, Vec<Option<DataType>>)> {
+        if arg_types.len() < self.min_args()
+            || (!self.variadic && arg_types.len() > self.arg_types.len())
+        {
+            return None;
+        }
+        let mut cost = 0;
+        let mut casts = vec![None; arg_types.len()];
+        let mut any_type = None;
+        for ((target, ty), cast) in self.arg_types.iter().zip(arg_types).zip(&mut casts) {
+            let elem_type = match extension_name(target) {
+                Some("arrowudf.any") => ty.data_type(),
+                Some("arrowudf.anyarray") => match ty.data_type() {
+                    DataType::List(field) => field.data_type(),
+                    _ => return None,
+                },
+                _ if type_matches(target, ty) => continue,
+                // extension types are never coerced
+                Some(_) => return None,
+                None => {
+                    cost += coerce::coercion_cost(ty.data_type(), target.data_type())?;
+                    *cast = Some(target.data_type().clone());
+                    continue;
+                }
+            };
+            match any_type {
+                Some(t) if t != elem_type => return None,
+                _ => any_type = Some(elem_type),
+            }
+        }
+        // typed variadic arguments are coerced to the variadic type
+        if let Some(target) = &self.variadic_type {
+            let extra_args = arg_types.iter().zip(&mut casts).skip(self.arg_types.len());
+            for (ty, cast) in extra_args {
+                if type_matches(target, ty) {
+                    continue;
+                }
+                if extension_name(target).is_some() {
+                    return None;
+                }
+                cost += coerce::coercion_cost(ty.data_type(), target.data_type())?;
+                *cast = Some(target.data_type().clone());
+            }
+        }
+        Some((cost, casts))
+    }
+
+    /// Returns the minimum number of arguments, excluding those with default values.
+    pub fn min_args(&self) -> usize {
+        self.arg_types.len().saturating_sub(self.defaults.len())
+    }
+
+    /// Fill the omitted arguments of `input` with their default values.
+    ///
+    /// Functions defined by `#[function]` fill default values themselves.
+    pub fn fill_defaults<'a>(&self, input: &'a RecordBatch) -> Result<Cow<'a, RecordBatch>> {
+        defaults::fill_defaults(input, &self.arg_types, &self.defaults)
+    }
+
+    /// Returns whether the function always returns the same result for the same arguments.
+    pub fn is_deterministic(&self) -> bool {
+        self.volatility == Volatility::Immutable
+    }
+
+    /// Returns the return type of the function for the given argument types.
+    ///
+    /// For functions returning `any` or `anyarray`, the type is inferred from the argument types.
+    pub fn infer_return_type(&self, arg_types: &[Field]) -> Result<Field> {
+        let Some(type_infer) = self.type_infer else {
+            return Ok(self.return_type.clone());
+        };
+        let arg_types = arg_types
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect::<Vec<_>>();
+        let data_type = type_infer(&arg_types)?;
+        Ok(Field::new(self.return_type.name(), data_type, true))
+    }
+}
+
+/// Returns the extension type name of the field.
+fn extension_name(field: &Field) -> Option<&str> {
+    field
+        .metadata()
+        .get("ARROW:extension:name")
+        .map(|s| s.as_str())
+}
+
+/// Check if the type of field `ty` matches the `target` type in signature.
+fn type_matches(target: &Field, ty: &Field) -> bool {
+    data_type_matches(target.data_type(), ty.data_type()) && target.metadata() == ty.metadata()
+}
+
+/// Check if the data type `ty` matches the `target` data type in signature.
+fn data_type_matches(target: &DataType, ty: &DataType) -> bool {
+    match (target, ty) {
+        // `timestamptz` accepts timestamps with any time zone
+        (DataType::Timestamp(unit1, Some(_)), DataType::Timestamp(unit2, Some(_))) => {
+            unit1 == unit2
+        }
+        // dictionary-encoded and view-typed inputs are cast to the target type
+        (t, DataType::Dictionary(_, value)) => data_type_matches(t, value),
+        (DataType::Utf8 | DataType::LargeUtf8, DataType::Utf8View) => true,
+        (DataType::Binary | DataType::LargeBinary, DataType::BinaryView) => true,
+        // the names of map entries differ between producers, e.g. `entries` and `key_value`
+        (DataType::Map(entries1, sorted1), DataType::Map(entries2, sorted2)) => {
+            match (entries1.data_type(), entries2.data_type()) {
+                (DataType::Struct(kv1), DataType::Struct(kv2)) => {
+                    sorted1 == sorted2
+                        && kv1.len() == kv2.len()
+                        && kv1
+                            .iter()
+                            .zip(kv2)
+                            .all(|(f1, f2)| data_type_matches(f1.data_type(), f2.data_type()))
+                }
+                _ => false,
             }
         }
-        if self.variadic {
-            true
-        } else {
-            arg_types.len() == self.arg_types.len()
+        // the nullability of struct fields is not checked, since producers may mark all fields
+        // as nullable, while only `Option` fields of `#[derive(StructType)]` are nullable
+        (DataType::Struct(fields1), DataType::Struct(fields2)) => {
+            fields1.len() == fields2.len()
+                && fields1
+                    .iter()
+                    .zip(fields2)
+                    .all(|(f1, f2)| f1.name() == f2.name() && type_matches(f1, f2))
         }
+        (DataType::List(f1), DataType::List(f2))
+        | (DataType::LargeList(f1), DataType::LargeList(f2)) => type_matches(f1, f2),
+        (t1, t2) => t1 == t2,
     }
 }
 
+/// Check if two signatures have identical argument types and return types.
+fn same_types(sig: &FunctionSignature, other: &FunctionSignature) -> bool {
+    sig.variadic == other.variadic
+        && same_fields(&sig.arg_types, &other.arg_types)
+        && same_fields(sig.variadic_type.as_slice(), other.variadic_type.as_slice())
+        && same_fields(&[&sig.return_type], &[&other.return_type])
+}
+
+/// Check if the types of two lists of fields are identical.
+fn same_fields(a: &[impl Borrow<Field>], b: &[impl Borrow<Field>]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            let (a, b) = (a.borrow(), b.borrow());
+            a.data_type() == b.data_type() && a.metadata() == b.metadata()
+        })
+}
+
 /// A collection of distributed `#[function]` signatures.
+#[cfg(feature = "global_registry")]
 #[doc(hidden)]
 #[linkme::distributed_slice]
 pub static SIGNATURES: [fn() -> FunctionSignature];
 
 /// Global function registry.
-pub static REGISTRY: once_cell::sync::Lazy<FunctionRegistry> = once_cell::sync::Lazy::new(|| {
-    let mut signatures = HashMap::<String, Vec<FunctionSignature>>::new();
-    for sig in SIGNATURES {
-        let sig = sig();
-        signatures.entry(sig.name.clone()).or_default().push(sig);
-    }
-    FunctionRegistry { signatures }
-});
+#[cfg(feature = "global_registry")]
+pub static REGISTRY: once_cell::sync::Lazy<FunctionRegistry> =
+    once_cell::sync::Lazy::new(|| SIGNATURES.iter().map(|sig| sig()).collect());
 
 /// Function registry.
-#[derive(Default)]
+///
+/// Functions are registered in the default schema or in a named schema. Functions in a named
+/// schema are looked up by the qualified name `schema.name`.
+#[derive(Default, Clone)]
 pub struct FunctionRegistry {
+    /// Signatures by qualified name.
     signatures: HashMap<String, Vec<FunctionSignature>>,
 }
 
 impl FunctionRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a function in the default schema.
+    ///
+    /// Returns the replaced function with the same name and types, if any.
+    pub fn register(&mut self, sig: FunctionSignature) -> Option<FunctionSignature> {
+        self.insert(sig.name.clone(), sig)
+    }
+
+    /// Register a function in `schema`. It can be looked up by `schema.name`.
+    ///
+    /// Returns the replaced function with the same name and types, if any.
+    pub fn register_in(
+        &mut self,
+        schema: &str,
+        sig: FunctionSignature,
+    ) -> Option<FunctionSignature> {
+        self.insert(format!("{schema}.{}", sig.name), sig)
+    }
+
+    fn insert(&mut self, key: String, sig: FunctionSignature) -> Option<FunctionSignature> {
+        let sigs = self.signatures.entry(key).or_default();
+        match sigs.iter_mut().find(|s| same_types(s, &sig)) {
+            Some(s) => Some(std::mem::replace(s, sig)),
+            None => {
+                sigs.push(sig);
+                None
+            }
+        }
+    }
+
+    /// Unregister the function with the given name and the same types as `sig`.
+    ///
+    /// The types are compared like in [`register`](Self::register), including the variadic
+    /// arguments. The name can be qualified by a schema, e.g. `schema.name`.
+    /// Returns the removed function, if any.
+    pub fn unregister(&mut self, name: &str, sig: &FunctionSignature) -> Option<FunctionSignature> {
+        let sigs = self.signatures.get_mut(name)?;
+        let index = sigs.iter().position(|s| same_types(s, sig))?;
+        let sig = sigs.remove(index);
+        if sigs.is_empty() {
+            self.signatures.remove(name);
+        }
+        Some(sig)
+    }
+
     /// Get the function signature by name and types.
+    ///
+    /// The name can be qualified by a schema, e.g. `schema.name`.
     pub fn get(
         &self,
         name: &str,
@@ -144,8 +522,152 @@ impl FunctionRegistry {
         sigs.iter().find(|sig| sig.matches(arg_types, return_type))
     }
 
+    /// Get the function signature by name and argument types, along with the return type.
+    ///
+    /// The name can be qualified by a schema, e.g. `schema.name`.
+    /// The return type of functions returning `any` or `anyarray` is inferred from the argument types.
+    pub fn resolve(&self, name: &str, arg_types: &[Field]) -> Option<(&FunctionSignature, Field)> {
+        let sigs = self.signatures.get(name)?;
+        sigs.iter()
+            .filter(|sig| sig.matches_args(arg_types))
+            .find_map(|sig| Some((sig, sig.infer_return_type(arg_types).ok()?)))
+    }
+
+    /// Get the function signature by name and argument types, allowing implicit casts of arguments.
+    ///
+    /// The name can be qualified by a schema, e.g. `schema.name`.
+    /// If no signature matches exactly, the one requiring the cheapest casts is chosen,
+    /// with ties broken by registration order.
+    ///
+    /// The allowed casts are integer widening, integer to float, `float32` to `float64`,
+    /// `string` to `largestring`, `binary` to `largebinary`, decimal precision widening,
+    /// and `null` to any type. Lists are cast if their elements can be cast.
+    /// Use [`CoercedSignature::cast_input`] to apply the casts before calling the function.
+    pub fn resolve_with_coercion(
+        &self,
+        name: &str,
+        arg_types: &[Field],
+    ) -> Option<CoercedSignature<'_>> {
+        let sigs = self.signatures.get(name)?;
+        sigs.iter()
+            .filter_map(|sig| {
+                let (cost, casts) = sig.coerce_args(arg_types)?;
+                let coerced_types = arg_types
+                    .iter()
+                    .zip(&casts)
+                    .map(|(field, cast)| match cast {
+                        Some(ty) => field.clone().with_data_type(ty.clone()),
+                        None => field.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                let return_type = sig.infer_return_type(&coerced_types).ok()?;
+                Some((
+                    cost,
+                    CoercedSignature {
+                        signature: sig,
+                        casts,
+                        return_type,
+                    },
+                ))
+            })
+            .min_by_key(|(cost, _)| *cost)
+            .map(|(_, sig)| sig)
+    }
+
     /// Iterate over all function signatures.
     pub fn iter(&self) -> impl Iterator<Item = &FunctionSignature> {
         self.signatures.values().flatten()
     }
+
+    /// Iterate over the function signatures in `schema`.
+    pub fn iter_schema<'a>(
+        &'a self,
+        schema: &'a str,
+    ) -> impl Iterator<Item = &'a FunctionSignature> + 'a {
+        self.signatures
+            .iter()
+            .filter(move |(key, _)| {
+                key.strip_prefix(schema)
+                    .is_some_and(|name| name.starts_with('.'))
+            })
+            .flat_map(|(_, sigs)| sigs)
+    }
+
+    /// Register all functions of `other` in the same schemas.
+    ///
+    /// Functions with the same name and types are replaced.
+    pub fn merge(&mut self, other: &FunctionRegistry) {
+        for (key, sigs) in &other.signatures {
+            for sig in sigs {
+                self.insert(key.clone(), sig.clone());
+            }
+        }
+    }
+}
+
+/// A function signature resolved with implicit casts of arguments.
+///
+/// Returned by [`FunctionRegistry::resolve_with_coercion`].
+pub struct CoercedSignature<'a> {
+    /// The function signature.
+    pub signature: &'a FunctionSignature,
+
+    /// The type to cast each argument to, or `None` if the argument is passed as is.
+    pub casts: Vec<Option<DataType>>,
+
+    /// The return type.
+    pub return_type: Field,
+}
+
+impl CoercedSignature<'_> {
+    /// Returns whether any argument needs to be cast.
+    pub fn needs_cast(&self) -> bool {
+        self.casts.iter().any(|cast| cast.is_some())
+    }
+
+    /// Cast the columns of `input` to the argument types of the function.
+    pub fn cast_input(&self, input: &RecordBatch) -> Result<RecordBatch> {
+        if !self.needs_cast() {
+            return Ok(input.clone());
+        }
+        let mut fields = Vec::with_capacity(input.num_columns());
+        let mut columns = Vec::with_capacity(input.num_columns());
+        for (i, (field, column)) in input
+            .schema()
+            .fields()
+            .iter()
+            .zip(input.columns())
+            .enumerate()
+        {
+            match self.casts.get(i).and_then(|cast| cast.as_ref()) {
+                Some(ty) => {
+                    columns.push(arrow_cast::cast(column, ty)?);
+                    fields.push(field.as_ref().clone().with_data_type(ty.clone()));
+                }
+                None => {
+                    columns.push(column.clone());
+                    fields.push(field.as_ref().clone());
+                }
+            }
+        }
+        let schema = Schema::new_with_metadata(fields, input.schema().metadata().clone());
+        let options = RecordBatchOptions::new().with_row_count(Some(input.num_rows()));
+        RecordBatch::try_new_with_options(Arc::new(schema), columns, &options)
+    }
+}
+
+impl Extend<FunctionSignature> for FunctionRegistry {
+    fn extend<T: IntoIterator<Item = FunctionSignature>>(&mut self, iter: T) {
+        for sig in iter {
+            self.register(sig);
+        }
+    }
+}
+
+impl FromIterator<FunctionSignature> for FunctionRegistry {
+    fn from_iter<T: IntoIterator<Item = FunctionSignature>>(iter: T) -> Self {
+        let mut registry = Self::new();
+        registry.extend(iter);
+        registry
+    }
 }
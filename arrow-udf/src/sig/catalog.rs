@@ -0,0 +1,287 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export the function registry as a catalog.
+//!
+//! Types are rendered with the names used in `#[function]` signatures, e.g. `int32`, `string[]`,
+//! `decimal(10,2)`, `json` or `map<string,int32>`. Struct types are rendered as
+//! `struct<name:type,..>`.
+
+use super::{FunctionKind, FunctionRegistry, FunctionSignature, Volatility};
+use crate::Result;
+use arrow_array::builder::{BooleanBuilder, ListBuilder, MapBuilder, StringBuilder};
+use arrow_array::{Array, ArrayRef, ListArray, RecordBatch, StructArray};
+use arrow_buffer::{BooleanBufferBuilder, NullBuffer, OffsetBuffer};
+use arrow_schema::{DataType, Field, Fields, Schema, TimeUnit};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+impl FunctionRegistry {
+    /// Export all function signatures as a JSON array.
+    ///
+    /// Each element describes a function:
+    ///
+    /// ```json
+    /// {
+    ///   "schema": null,
+    ///   "name": "gcd",
+    ///   "kind": "scalar",
+    ///   "arg_types": [
+    ///     { "name": "a", "type": "int32", "nullable": true, "metadata": {} },
+    ///     { "name": "b", "type": "int32", "nullable": true, "metadata": {} }
+    ///   ],
+    ///   "variadic": false,
+    ///   "variadic_type": null,
+    ///   "defaults": [],
+    ///   "return_type": { "name": "gcd", "type": "int32", "nullable": true, "metadata": {} },
+    ///   "volatility": "immutable",
+    ///   "strict": true,
+    ///   "description": "Returns the greatest common divisor of two integers.",
+    ///   "examples": ["gcd(15, 25) = 5"]
+    /// }
+    /// ```
+    ///
+    /// Functions are sorted by qualified name, and then by registration order.
+    pub fn to_json(&self) -> Value {
+        let functions = self
+            .catalog_entries()
+            .map(|(schema, sig)| {
+                json!({
+                    "schema": schema,
+                    "name": sig.name,
+                    "kind": kind_name(&sig.function),
+                    "arg_types": sig.arg_types.iter().map(|f| field_json(f)).collect::<Vec<_>>(),
+                    "variadic": sig.variadic,
+                    "variadic_type": sig.variadic_type.as_ref().map(field_json),
+                    "defaults": sig.defaults,
+                    "return_type": field_json(&sig.return_type),
+                    "volatility": volatility_name(sig.volatility),
+                    "strict": sig.strict,
+                    "description": sig.description,
+                    "examples": sig.examples,
+                })
+            })
+            .collect();
+        Value::Array(functions)
+    }
+
+    /// Export all function signatures as a record batch, one row per function.
+    ///
+    /// The columns are the same as the fields of [`to_json`](Self::to_json).
+    /// Argument, variadic and return types are structs of `name`, `type`, `nullable` and `metadata`.
+    /// The batch can be written to Arrow IPC with `arrow_ipc::writer`.
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        let entries = self.catalog_entries().collect::<Vec<_>>();
+        let mut schema_builder = StringBuilder::new();
+        let mut name_builder = StringBuilder::new();
+        let mut kind_builder = StringBuilder::new();
+        let mut variadic_builder = BooleanBuilder::new();
+        let mut volatility_builder = StringBuilder::new();
+        let mut strict_builder = BooleanBuilder::new();
+        let mut description_builder = StringBuilder::new();
+        let mut defaults_builder = ListBuilder::new(StringBuilder::new());
+        let mut examples_builder = ListBuilder::new(StringBuilder::new());
+        for (schema, sig) in &entries {
+            schema_builder.append_option(*schema);
+            name_builder.append_value(&sig.name);
+            kind_builder.append_value(kind_name(&sig.function));
+            variadic_builder.append_value(sig.variadic);
+            volatility_builder.append_value(volatility_name(sig.volatility));
+            strict_builder.append_value(sig.strict);
+            description_builder.append_option(sig.description.as_deref());
+            for default in &sig.defaults {
+                defaults_builder.values().append_value(default);
+            }
+            defaults_builder.append(true);
+            for example in &sig.examples {
+                examples_builder.values().append_value(example);
+            }
+            examples_builder.append(true);
+        }
+
+        let arg_types = fields_array(
+            (entries.iter()).flat_map(|(_, sig)| sig.arg_types.iter().map(|f| Some(f.as_ref()))),
+        )?;
+        let arg_types = ListArray::new(
+            Arc::new(Field::new("item", arg_types.data_type().clone(), false)),
+            OffsetBuffer::from_lengths(entries.iter().map(|(_, sig)| sig.arg_types.len())),
+            Arc::new(arg_types),
+            None,
+        );
+        let variadic_type =
+            fields_array(entries.iter().map(|(_, sig)| sig.variadic_type.as_ref()))?;
+        let return_type = fields_array(entries.iter().map(|(_, sig)| Some(&sig.return_type)))?;
+
+        let columns: Vec<(&str, ArrayRef, bool)> = vec![
+            ("schema", Arc::new(schema_builder.finish()), true),
+            ("name", Arc::new(name_builder.finish()), false),
+            ("kind", Arc::new(kind_builder.finish()), false),
+            ("arg_types", Arc::new(arg_types), false),
+            ("variadic", Arc::new(variadic_builder.finish()), false),
+            ("variadic_type", Arc::new(variadic_type), true),
+            ("defaults", Arc::new(defaults_builder.finish()), false),
+            ("return_type", Arc::new(return_type), false),
+            ("volatility", Arc::new(volatility_builder.finish()), false),
+            ("strict", Arc::new(strict_builder.finish()), false),
+            ("description", Arc::new(description_builder.finish()), true),
+            ("examples", Arc::new(examples_builder.finish()), false),
+        ];
+        let fields = (columns.iter())
+            .map(|(name, array, nullable)| Field::new(*name, array.data_type().clone(), *nullable))
+            .collect::<Vec<_>>();
+        let arrays = columns.into_iter().map(|(_, array, _)| array).collect();
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+    }
+
+    /// Returns the schema and signature of all functions in a stable order.
+    fn catalog_entries(&self) -> impl Iterator<Item = (Option<&str>, &FunctionSignature)> {
+        let mut keys = self.signatures.keys().collect::<Vec<_>>();
+        keys.sort();
+        keys.into_iter().flat_map(|key| {
+            self.signatures[key].iter().map(move |sig| {
+                let schema = key
+                    .strip_suffix(sig.name.as_str())
+                    .and_then(|s| s.strip_suffix('.'));
+                (schema, sig)
+            })
+        })
+    }
+}
+
+/// Returns the JSON representation of a field.
+fn field_json(field: &Field) -> Value {
+    json!({
+        "name": field.name(),
+        "type": type_name(field),
+        "nullable": field.is_nullable(),
+        "metadata": field.metadata().iter().collect::<BTreeMap<_, _>>(),
+    })
+}
+
+/// Returns a struct array of `name`, `type`, `nullable` and `metadata` of the fields.
+///
+/// `None` fields are null.
+fn fields_array<'a>(fields: impl IntoIterator<Item = Option<&'a Field>>) -> Result<StructArray> {
+    let mut name_builder = StringBuilder::new();
+    let mut type_builder = StringBuilder::new();
+    let mut nullable_builder = BooleanBuilder::new();
+    let mut metadata_builder = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+    let mut validity = BooleanBufferBuilder::new(0);
+    for field in fields {
+        validity.append(field.is_some());
+        let Some(field) = field else {
+            name_builder.append_value("");
+            type_builder.append_value("");
+            nullable_builder.append_value(false);
+            metadata_builder.append(true)?;
+            continue;
+        };
+        name_builder.append_value(field.name());
+        type_builder.append_value(type_name(field));
+        nullable_builder.append_value(field.is_nullable());
+        for (key, value) in field.metadata().iter().collect::<BTreeMap<_, _>>() {
+            metadata_builder.keys().append_value(key);
+            metadata_builder.values().append_value(value);
+        }
+        metadata_builder.append(true)?;
+    }
+    let metadata = metadata_builder.finish();
+    let fields = Fields::from(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("type", DataType::Utf8, false),
+        Field::new("nullable", DataType::Boolean, false),
+        Field::new("metadata", metadata.data_type().clone(), false),
+    ]);
+    StructArray::try_new(
+        fields,
+        vec![
+            Arc::new(name_builder.finish()),
+            Arc::new(type_builder.finish()),
+            Arc::new(nullable_builder.finish()),
+            Arc::new(metadata),
+        ],
+        Some(NullBuffer::new(validity.finish())),
+    )
+}
+
+/// Returns the type name of a field as in `#[function]` signatures.
+fn type_name(field: &Field) -> String {
+    match super::extension_name(field) {
+        Some("arrowudf.json") => return "json".into(),
+        Some("arrowudf.decimal") => return "decimal".into(),
+        Some("arrowudf.any") => return "any".into(),
+        Some("arrowudf.anyarray") => return "anyarray".into(),
+        _ => {}
+    }
+    match field.data_type() {
+        DataType::Null => "null".into(),
+        DataType::Boolean => "boolean".into(),
+        DataType::Int8 => "int8".into(),
+        DataType::Int16 => "int16".into(),
+        DataType::Int32 => "int32".into(),
+        DataType::Int64 => "int64".into(),
+        DataType::UInt8 => "uint8".into(),
+        DataType::UInt16 => "uint16".into(),
+        DataType::UInt32 => "uint32".into(),
+        DataType::UInt64 => "uint64".into(),
+        DataType::Float32 => "float32".into(),
+        DataType::Float64 => "float64".into(),
+        DataType::Date32 => "date32".into(),
+        DataType::Time64(TimeUnit::Microsecond) => "time64".into(),
+        DataType::Timestamp(TimeUnit::Microsecond, None) => "timestamp".into(),
+        DataType::Timestamp(TimeUnit::Microsecond, Some(_)) => "timestamptz".into(),
+        DataType::Interval(arrow_schema::IntervalUnit::MonthDayNano) => "interval".into(),
+        DataType::Decimal128(p, s) | DataType::Decimal256(p, s) => format!("decimal({p},{s})"),
+        DataType::Utf8 => "string".into(),
+        DataType::LargeUtf8 => "largestring".into(),
+        DataType::Binary => "binary".into(),
+        DataType::LargeBinary => "largebinary".into(),
+        DataType::List(elem) => format!("{}[]", type_name(elem)),
+        DataType::Map(entries, _) => match entries.data_type() {
+            DataType::Struct(kv) if kv.len() == 2 => {
+                format!("map<{},{}>", type_name(&kv[0]), type_name(&kv[1]))
+            }
+            t => t.to_string(),
+        },
+        DataType::Struct(fields) => {
+            let fields = fields
+                .iter()
+                .map(|f| format!("{}:{}", f.name(), type_name(f)))
+                .collect::<Vec<_>>();
+            format!("struct<{}>", fields.join(","))
+        }
+        DataType::Dictionary(_, value) => type_name(&Field::new("", (**value).clone(), true)),
+        t => t.to_string(),
+    }
+}
+
+/// Returns the name of the function kind.
+fn kind_name(kind: &FunctionKind) -> &'static str {
+    match kind {
+        FunctionKind::Scalar(_) | FunctionKind::DynScalar(_) => "scalar",
+        FunctionKind::AsyncScalar(_) => "async_scalar",
+        FunctionKind::Table(_) | FunctionKind::DynTable(_) => "table",
+        FunctionKind::Aggregate(_) => "aggregate",
+    }
+}
+
+/// Returns the name of the volatility.
+fn volatility_name(volatility: Volatility) -> &'static str {
+    match volatility {
+        Volatility::Immutable => "immutable",
+        Volatility::Stable => "stable",
+        Volatility::Volatile => "volatile",
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implicit type coercion of function arguments.
+//!
+//! | From                  | To                                          | Cost |
+//! | --------------------- | ------------------------------------------- | ---- |
+//! | `null`                | any type                                    | 1    |
+//! | `intN` / `uintN`      | wider integers of the same signedness       | 1 per doubling |
+//! | `uintN`               | wider signed integers                       | 1 per doubling |
+//! | `int8` .. `int16`     | `float32`                                   | 4    |
+//! | integers              | `float64`                                   | 5    |
+//! | `float32`             | `float64`                                   | 1    |
+//! | `string` / `binary`   | `largestring` / `largebinary`               | 1    |
+//! | `decimal(p1, s1)`     | `decimal(p2, s2)` with `s2 >= s1` and `p2 - s2 >= p1 - s1` | 1, or 2 to `Decimal256` |
+//! | `T[]`                 | `U[]` if `T` can be cast to `U`             | cost of `T` to `U` |
+//!
+//! Dictionary-encoded types are coerced as their value types.
+
+use arrow_schema::DataType;
+
+/// The cost to cast an integer to `float32`, so that integer widening is preferred.
+const INT_TO_FLOAT32_COST: u32 = 4;
+
+/// The cost to cast an integer to `float64`.
+const INT_TO_FLOAT64_COST: u32 = 5;
+
+/// Returns the cost to implicitly cast `from` to `to`, or `None` if it is not allowed.
+pub fn coercion_cost(from: &DataType, to: &DataType) -> Option<u32> {
+    use DataType::*;
+
+    if from == to {
+        return Some(0);
+    }
+    match (from, to) {
+        (Dictionary(_, value), to) => coercion_cost(value, to),
+        (Null, _) => Some(1),
+        (Float32, Float64) | (Utf8, LargeUtf8) | (Binary, LargeBinary) => Some(1),
+        (Decimal128(p1, s1), Decimal128(p2, s2)) => decimal_cost(*p1, *s1, *p2, *s2),
+        (Decimal128(p1, s1), Decimal256(p2, s2)) => Some(decimal_cost(*p1, *s1, *p2, *s2)? + 1),
+        (Decimal256(p1, s1), Decimal256(p2, s2)) => decimal_cost(*p1, *s1, *p2, *s2),
+        (List(from), List(to)) => coercion_cost(from.data_type(), to.data_type()),
+        (from, Float32) if integer(from)?.1 <= 16 => Some(INT_TO_FLOAT32_COST),
+        (from, Float64) => integer(from).map(|_| INT_TO_FLOAT64_COST),
+        (from, to) => {
+            let (signed1, bits1) = integer(from)?;
+            let (signed2, bits2) = integer(to)?;
+            let allowed = match (signed1, signed2) {
+                (true, true) | (false, false) => bits2 > bits1,
+                (false, true) => bits2 > bits1,
+                (true, false) => false,
+            };
+            allowed.then(|| (bits2 / bits1).trailing_zeros())
+        }
+    }
+}
+
+/// Returns the cost to cast `decimal(p1, s1)` to `decimal(p2, s2)` without losing digits.
+fn decimal_cost(p1: u8, s1: i8, p2: u8, s2: i8) -> Option<u32> {
+    let integer_digits = |p: u8, s: i8| p as i16 - s as i16;
+    (s2 >= s1 && integer_digits(p2, s2) >= integer_digits(p1, s1)).then_some(1)
+}
+
+/// Returns whether the integer type is signed and its bit width.
+fn integer(data_type: &DataType) -> Option<(bool, u32)> {
+    use DataType::*;
+
+    match data_type {
+        Int8 => Some((true, 8)),
+        Int16 => Some((true, 16)),
+        Int32 => Some((true, 32)),
+        Int64 => Some((true, 64)),
+        UInt8 => Some((false, 8)),
+        UInt16 => Some((false, 16)),
+        UInt32 => Some((false, 32)),
+        UInt64 => Some((false, 64)),
+        _ => None,
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Default values of function arguments.
+//!
+//! Default values are SQL literals: `null`, quoted strings like `'abc'` (with `''` escaping a
+//! quote), or unquoted values like `0`, `1.5` or `true`. They are cast from strings to the
+//! argument types with `arrow-cast`.
+
+use crate::{Error, Result};
+use arrow_array::{new_null_array, ArrayRef, RecordBatch, RecordBatchOptions, StringArray};
+use arrow_cast::CastOptions;
+use arrow_schema::{DataType, Field, Schema};
+use std::borrow::{Borrow, Cow};
+use std::sync::Arc;
+
+/// Fill the missing trailing arguments of `input` with their default values.
+///
+/// `arg_types` are the types of all arguments, and `defaults` are the default values of the last
+/// `defaults.len()` arguments. Returns `input` unchanged if no argument is missing.
+pub fn fill_defaults<'a>(
+    input: &'a RecordBatch,
+    arg_types: &[impl Borrow<Field>],
+    defaults: &[impl AsRef<str>],
+) -> Result<Cow<'a, RecordBatch>> {
+    let num_columns = input.num_columns();
+    if num_columns >= arg_types.len() {
+        return Ok(Cow::Borrowed(input));
+    }
+    let min_args = arg_types.len().saturating_sub(defaults.len());
+    if num_columns < min_args {
+        return Err(Error::InvalidArgumentError(format!(
+            "expect at least {min_args} arguments, but got {num_columns}"
+        )));
+    }
+    let mut fields = input.schema().fields().to_vec();
+    let mut columns = input.columns().to_vec();
+    let missing = arg_types[num_columns..]
+        .iter()
+        .zip(&defaults[defaults.len() - (arg_types.len() - num_columns)..]);
+    for (field, default) in missing {
+        let field = field.borrow();
+        columns.push(literal_array(
+            default.as_ref(),
+            field.data_type(),
+            input.num_rows(),
+        )?);
+        fields.push(Arc::new(field.clone()));
+    }
+    let schema = Schema::new_with_metadata(fields, input.schema().metadata().clone());
+    let options = RecordBatchOptions::new().with_row_count(Some(input.num_rows()));
+    let batch = RecordBatch::try_new_with_options(Arc::new(schema), columns, &options)?;
+    Ok(Cow::Owned(batch))
+}
+
+/// Returns an array of `len` copies of the SQL literal.
+pub(super) fn literal_array(literal: &str, data_type: &DataType, len: usize) -> Result<ArrayRef> {
+    if literal.eq_ignore_ascii_case("null") {
+        return Ok(new_null_array(data_type, len));
+    }
+    let value = match literal
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+    {
+        Some(s) => Cow::Owned(s.replace("''", "'")),
+        None => Cow::Borrowed(literal),
+    };
+    let strings = StringArray::from_iter_values(std::iter::repeat_n(value.as_ref(), len));
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    arrow_cast::cast_with_options(&strings, data_type, &options).map_err(|e| {
+        Error::InvalidArgumentError(format!(
+            "invalid default value {literal} for type {data_type}: {e}"
+        ))
+    })
+}
@@ -0,0 +1,130 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Call functions with named arguments, e.g. `f(x => 1, y => 2)`.
+//!
+//! Argument names are the field names of `FunctionSignature::arg_types`.
+
+use super::{defaults, FunctionRegistry, FunctionSignature};
+use crate::{Error, Result};
+use arrow_array::{RecordBatch, RecordBatchOptions};
+use arrow_schema::{Field, Schema};
+use std::sync::Arc;
+
+impl FunctionSignature {
+    /// Returns the position of each named argument in the signature.
+    ///
+    /// Returns an error if a name does not match any argument or is repeated,
+    /// or an argument without default value is not named.
+    fn bind_names<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Result<Vec<usize>> {
+        if self.variadic {
+            return Err(Error::InvalidArgumentError(format!(
+                "function {} is variadic and can not be called with named arguments",
+                self.name
+            )));
+        }
+        let mut bound = vec![false; self.arg_types.len()];
+        let mut positions = vec![];
+        for name in names {
+            let position = self
+                .arg_types
+                .iter()
+                .position(|field| !field.name().is_empty() && field.name() == name)
+                .ok_or_else(|| {
+                    Error::InvalidArgumentError(format!(
+                        "function {} has no argument named {name}",
+                        self.name
+                    ))
+                })?;
+            if std::mem::replace(&mut bound[position], true) {
+                return Err(Error::InvalidArgumentError(format!(
+                    "argument {name} is specified more than once"
+                )));
+            }
+            positions.push(position);
+        }
+        if let Some(i) = bound[..self.min_args()].iter().position(|b| !b) {
+            return Err(Error::InvalidArgumentError(format!(
+                "missing argument {}",
+                self.arg_types[i].name()
+            )));
+        }
+        Ok(positions)
+    }
+
+    /// Reorder the columns of `input` by name to match the arguments of the function.
+    ///
+    /// Each column is bound to the argument of the same name, and omitted arguments are filled
+    /// with their default values. Returns an error if a column does not match any argument,
+    /// or an argument without default value is missing.
+    pub fn bind_named_args(&self, input: &RecordBatch) -> Result<RecordBatch> {
+        let schema = input.schema();
+        let positions = self.bind_names(schema.fields().iter().map(|f| f.name().as_str()))?;
+        let min_args = self.min_args();
+        let mut fields = Vec::with_capacity(self.arg_types.len());
+        let mut columns = Vec::with_capacity(self.arg_types.len());
+        for (i, target) in self.arg_types.iter().enumerate() {
+            match positions.iter().position(|&p| p == i) {
+                Some(j) => {
+                    fields.push(schema.field(j).clone());
+                    columns.push(input.column(j).clone());
+                }
+                None => {
+                    let default = &self.defaults[i - min_args];
+                    let array =
+                        defaults::literal_array(default, target.data_type(), input.num_rows())?;
+                    fields.push(target.as_ref().clone());
+                    columns.push(array);
+                }
+            }
+        }
+        let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+        let options = RecordBatchOptions::new().with_row_count(Some(input.num_rows()));
+        RecordBatch::try_new_with_options(Arc::new(schema), columns, &options)
+    }
+}
+
+impl FunctionRegistry {
+    /// Get the function signature by name and named arguments, along with the return type.
+    ///
+    /// The name can be qualified by a schema, e.g. `schema.name`.
+    /// Arguments are matched by the names of `arg_types` in any order, and arguments with
+    /// default values can be omitted. Use [`FunctionSignature::bind_named_args`] to reorder the
+    /// input before calling the function.
+    pub fn resolve_named(
+        &self,
+        name: &str,
+        arg_types: &[Field],
+    ) -> Option<(&FunctionSignature, Field)> {
+        let sigs = self.signatures.get(name)?;
+        sigs.iter().find_map(|sig| {
+            let positions = sig
+                .bind_names(arg_types.iter().map(|f| f.name().as_str()))
+                .ok()?;
+            // omitted arguments have the declared types
+            let mut types = sig
+                .arg_types
+                .iter()
+                .map(|f| f.as_ref().clone())
+                .collect::<Vec<_>>();
+            for (field, position) in arg_types.iter().zip(positions) {
+                types[position] = field.clone();
+            }
+            if !sig.matches_args(&types) {
+                return None;
+            }
+            Some((sig, sig.infer_return_type(&types).ok()?))
+        })
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2024 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The output of table functions.
+//!
+//! Table functions returning structs emit one column per struct field, like SQL `RETURNS TABLE`
+//! functions, instead of a single struct column.
+
+use arrow_array::cast::AsArray;
+use arrow_array::{make_array, Array, ArrayRef};
+use arrow_buffer::NullBuffer;
+use arrow_schema::{DataType, Field};
+
+use crate::{Error, Result};
+
+/// The names of the index and error columns of table functions.
+const RESERVED_NAMES: [&str; 2] = ["row", "error"];
+
+/// Returns the output fields of a table function returning values of `field`.
+///
+/// Struct fields are flattened into one field per struct field. Other fields are returned as is.
+/// Returns an error if a struct field is named `row` or `error`, which are the names of the
+/// index and error columns.
+pub fn flatten_struct_field(field: Field) -> Result<Vec<Field>> {
+    let DataType::Struct(fields) = field.data_type() else {
+        return Ok(vec![field]);
+    };
+    if let Some(f) = fields
+        .iter()
+        .find(|f| RESERVED_NAMES.contains(&f.name().as_str()))
+    {
+        return Err(Error::InvalidArgumentError(format!(
+            "struct field `{}` of table function `{}` conflicts with the `{}` column",
+            f.name(),
+            field.name(),
+            f.name()
+        )));
+    }
+    // null rows make all columns null
+    Ok(fields
+        .iter()
+        .map(|f| f.as_ref().clone().with_nullable(true))
+        .collect())
+}
+
+/// Returns the output columns of a table function returning `array`.
+///
+/// Struct arrays are flattened into their columns, where null rows of the struct are null.
+/// Other arrays are returned as is.
+pub fn flatten_struct_array(array: ArrayRef) -> Vec<ArrayRef> {
+    let Some(array) = array.as_struct_opt() else {
+        return vec![array];
+    };
+    let Some(nulls) = array.nulls() else {
+        return array.columns().to_vec();
+    };
+    array
+        .columns()
+        .iter()
+        .map(|column| {
+            let nulls = NullBuffer::union(Some(nulls), column.nulls());
+            let data = column.to_data().into_builder().nulls(nulls);
+            make_array(data.build().expect("invalid struct column"))
+        })
+        .collect()
+}
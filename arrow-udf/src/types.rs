@@ -14,19 +14,25 @@
 
 //! Data types for user-defined functions.
 
+use std::sync::Arc;
+
 use arrow_array::builder::StructBuilder;
-use arrow_schema::Fields;
+use arrow_array::{ArrayRef, StructArray};
+use arrow_schema::{DataType, Fields};
 pub use arrow_udf_macros::StructType;
 
 // re-export common types
 pub use chrono;
 #[doc(no_inline)]
-pub use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+pub use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 pub use rust_decimal;
 #[doc(no_inline)]
 pub use rust_decimal::Decimal;
 pub use serde_json;
 
+#[cfg(feature = "serde")]
+pub use crate::serde_type::{Json, Serde};
+
 /// Interval type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Interval {
@@ -37,12 +43,50 @@ pub struct Interval {
 
 /// A trait for user-defined struct types.
 ///
-/// This trait can be automatically derived with [`#[derive(StructType)]`](derive@StructType).
+/// This trait can be automatically derived with [`#[derive(StructType)]`](derive@StructType)
+/// for structs, tuple structs and enums.
+///
+/// Values are always built with a [`StructBuilder`] of [`fields`](StructType::fields). Types
+/// encoded differently in arguments and return values, such as enums, override
+/// [`data_type`](StructType::data_type), [`encode`](StructType::encode) and
+/// [`decode`](StructType::decode).
 pub trait StructType {
+    /// Whether converting values of this type from or to arrays can fail.
+    ///
+    /// Functions taking or returning a fallible type have an error column, where conversion
+    /// errors are reported.
+    const FALLIBLE: bool = false;
     /// Returns the fields of the struct type.
     fn fields() -> Fields;
     /// Appends the struct value to the builder.
-    fn append_to(self, builder: &mut StructBuilder);
+    ///
+    /// If the value can not be converted, a null value is appended and the error is returned.
+    fn append_to(self, builder: &mut StructBuilder) -> crate::Result<()>;
     /// Appends a null value to the builder.
     fn append_null(builder: &mut StructBuilder);
+    /// Returns the data type of arguments and return values of this type.
+    fn data_type() -> DataType {
+        DataType::Struct(Self::fields())
+    }
+    /// Converts the struct array of [`fields`](StructType::fields) to
+    /// [`data_type`](StructType::data_type).
+    fn encode(array: StructArray) -> crate::Result<ArrayRef> {
+        Ok(Arc::new(array))
+    }
+    /// Converts an array of [`data_type`](StructType::data_type) to the struct array of
+    /// [`fields`](StructType::fields).
+    fn decode(array: &ArrayRef) -> crate::Result<ArrayRef> {
+        Ok(array.clone())
+    }
+}
+
+/// A trait for reading user-defined struct types from a [`StructArray`].
+///
+/// This trait is implemented along with [`StructType`] by [`#[derive(StructType)]`](derive@StructType),
+/// so that struct types can be used as function arguments.
+pub trait FromStructArray<'a>: Sized {
+    /// Reads the struct value at `index` from the array.
+    ///
+    /// The value at `index` should not be null. Returns an error if the value can not be converted.
+    fn from_struct_array(array: &'a StructArray, index: usize) -> crate::Result<Self>;
 }
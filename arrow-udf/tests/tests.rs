@@ -12,17 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::iter::Sum;
 use std::ops::{Add, Neg};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use arrow_array::cast::AsArray;
 use arrow_array::temporal_conversions::time_to_time64us;
 use arrow_array::types::{Date32Type, Int32Type};
 use arrow_array::*;
-use arrow_schema::{DataType, Field, Schema, TimeUnit};
-use arrow_udf::function;
+use arrow_schema::{DataType, Field, Fields, Schema, TimeUnit};
 use arrow_udf::types::*;
+use arrow_udf::{aggregate, function, Context, ErrorCode, ErrorPolicy};
 use cases::visibility_tests::{maybe_visible_pub_crate_udf, maybe_visible_pub_udf};
 use common::check;
 use expect_test::expect;
@@ -53,7 +55,14 @@ fn neg<T: Neg<Output = T>>(x: T) -> T {
 }
 
 // test simd with 2 arguments
-#[function("gcd(int, int) -> int")]
+/// Returns the greatest common divisor of two integers.
+///
+/// Returns `a` if `b` is zero.
+#[function(
+    "gcd(int, int) -> int",
+    example = "gcd(15, 25) = 5",
+    example = "gcd(7, 0) = 7"
+)]
 fn gcd(mut a: i32, mut b: i32) -> i32 {
     while b != 0 {
         (a, b) = (b, a % b);
@@ -62,6 +71,7 @@ fn gcd(mut a: i32, mut b: i32) -> i32 {
 }
 
 #[function("add(decimal, decimal) -> decimal")]
+#[function("add(decimal(10,2), decimal(10,2)) -> decimal(11,2)")]
 fn add<T: Add<Output = T>>(x: T, y: T) -> T {
     x + y
 }
@@ -78,10 +88,12 @@ fn add<T: Add<Output = T>>(x: T, y: T) -> T {
 #[function("identity(float32) -> float32")]
 #[function("identity(float64) -> float64")]
 #[function("identity(decimal) -> decimal")]
+#[function("identity(decimal(38, 2)) -> decimal(38, 2)")]
+#[function("identity(decimal(50, 4)) -> decimal(50, 4)")]
 #[function("identity(date) -> date")]
 #[function("identity(time) -> time")]
 #[function("identity(timestamp) -> timestamp")]
-// #[function("identity(timestamptz) -> timestamptz")]
+#[function("identity(timestamptz) -> timestamptz")]
 #[function("identity(interval) -> interval")]
 #[function("identity(json) -> json")]
 #[function("identity(string) -> string")]
@@ -102,6 +114,219 @@ fn div(x: i32, y: i32) -> Result<i32, &'static str> {
     x.checked_div(y).ok_or("division by zero")
 }
 
+#[derive(Debug)]
+enum ParseError {
+    Empty,
+    Invalid(String),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty input"),
+            Self::Invalid(_) => write!(f, "invalid digit"),
+        }
+    }
+}
+
+impl ErrorCode for ParseError {
+    fn code(&self) -> i32 {
+        match self {
+            Self::Empty => 1,
+            Self::Invalid(_) => 2,
+        }
+    }
+
+    fn details(&self) -> Option<String> {
+        match self {
+            Self::Empty => None,
+            Self::Invalid(s) => Some(format!("input: {s:?}")),
+        }
+    }
+}
+
+#[function("parse_int(string) -> int")]
+fn parse_int(s: &str) -> Result<i32, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    s.parse().map_err(|_| ParseError::Invalid(s.to_string()))
+}
+
+// test vectorized evaluation
+#[function("clamp(int, int, int) -> int")]
+fn clamp(x: i32, min: i32, max: i32) -> i32 {
+    // panics if `min > max`
+    x.clamp(min, max)
+}
+
+#[function("checked_add(int, int) -> int")]
+fn checked_add(x: i32, y: i32) -> Option<i32> {
+    x.checked_add(y)
+}
+
+#[function("is_leap_year(date) -> boolean")]
+fn is_leap_year(date: NaiveDate) -> bool {
+    date.leap_year()
+}
+
+#[function("add_days(timestamp, int) -> timestamp")]
+fn add_days(t: NaiveDateTime, days: i32) -> Result<NaiveDateTime, &'static str> {
+    t.checked_add_signed(chrono::Duration::days(days as i64))
+        .ok_or("timestamp out of range")
+}
+
+// test vectorized functions
+#[function("zscore(float64) -> float64", vectorized)]
+fn zscore(a: &Float64Array) -> Float64Array {
+    let n = (a.len() - a.null_count()) as f64;
+    let mean = a.iter().flatten().sum::<f64>() / n;
+    let std = (a.iter().flatten().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+    a.unary(|x| (x - mean) / std)
+}
+
+#[function("prefix_sum(int64) -> int64", vectorized)]
+fn prefix_sum(a: &Int64Array) -> Int64Array {
+    let mut sum = 0;
+    a.iter()
+        .map(|x| {
+            sum += x?;
+            Some(sum)
+        })
+        .collect()
+}
+
+#[function("concat_columns(string, ...) -> string", vectorized)]
+fn concat_columns(first: &StringArray, rest: &[ArrayRef]) -> Result<StringArray, String> {
+    let rest = rest
+        .iter()
+        .map(|c| c.as_any().downcast_ref::<StringArray>())
+        .collect::<Option<Vec<_>>>()
+        .ok_or("expect string columns")?;
+    Ok((0..first.len())
+        .map(|i| {
+            let mut s = first.is_valid(i).then(|| first.value(i).to_string())?;
+            for c in &rest {
+                s += c.is_valid(i).then(|| c.value(i))?;
+            }
+            Some(s)
+        })
+        .collect())
+}
+
+// test typed variadic arguments
+#[function("concat(string, string...) -> string")]
+fn concat(first: &str, rest: &[&str]) -> String {
+    rest.iter().fold(first.to_string(), |s, r| s + r)
+}
+
+#[function("concat_ws(string, string...) -> string")]
+fn concat_ws(sep: &str, values: &[Option<&str>]) -> String {
+    values
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+#[function("latest(timestamp...) -> timestamp")]
+fn latest(values: &[NaiveDateTime]) -> Option<NaiveDateTime> {
+    values.iter().max().copied()
+}
+
+#[function("greatest(int32, int32...) -> int32", vectorized)]
+fn greatest(first: &Int32Array, rest: &[&Int32Array]) -> Int32Array {
+    (0..first.len())
+        .map(|i| {
+            let values = rest.iter().map(|a| a.is_valid(i).then(|| a.value(i)));
+            values.fold(first.is_valid(i).then(|| first.value(i)), |a, b| a.max(b))
+        })
+        .collect()
+}
+
+#[function("round_to(float64, int32 = 0) -> float64")]
+fn round_to(x: f64, digits: i32) -> f64 {
+    let scale = 10f64.powi(digits);
+    (x * scale).round() / scale
+}
+
+#[function("lpad(string, int32, string = ' ') -> string")]
+fn lpad(s: &str, width: i32, fill: &str) -> String {
+    let padding = (width as usize).saturating_sub(s.chars().count());
+    fill.repeat(padding) + s
+}
+
+#[function("repeat_str(string, int32) -> string", arg_names = "str, count")]
+fn repeat_str(s: &str, n: i32) -> String {
+    s.repeat(n.max(0) as usize)
+}
+
+// test polymorphic functions
+#[function("array_first(anyarray) -> any")]
+fn array_first(array: ArrayRef) -> Option<ArrayRef> {
+    (!array.is_empty()).then(|| array.slice(0, 1))
+}
+
+#[function("array_of(any) -> anyarray")]
+fn array_of(value: ArrayRef) -> ArrayRef {
+    value
+}
+
+#[function("array_contains(anyarray, any) -> boolean")]
+fn array_contains(array: ArrayRef, value: ArrayRef) -> bool {
+    (0..array.len()).any(|i| array.slice(i, 1).to_data() == value.to_data())
+}
+
+#[function("to_int64(any) -> any", type_infer = "|_| Ok(DataType::Int64)")]
+fn to_int64(value: ArrayRef) -> Result<ArrayRef, arrow_schema::ArrowError> {
+    arrow_cast::cast(&value, &DataType::Int64)
+}
+
+// test context
+#[function("timezone() -> string")]
+fn timezone(ctx: &Context) -> String {
+    ctx.timezone().unwrap_or("UTC").to_string()
+}
+
+// test volatility
+#[function("next_id() -> int64", volatile)]
+fn next_id() -> i64 {
+    static NEXT_ID: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(0);
+    NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+}
+
+#[function("get_config(string) -> string")]
+fn get_config<'a>(key: &str, ctx: &'a Context) -> Option<&'a str> {
+    ctx.get_config(key)
+}
+
+// test async functions
+#[function("async_lookup(int) -> string", concurrency = "4")]
+async fn async_lookup(key: i32) -> Option<String> {
+    // rows with smaller keys finish later, but the output follows the input order
+    tokio::time::sleep(std::time::Duration::from_millis(10 - key as u64)).await;
+    (key % 2 == 0).then(|| format!("value-{key}"))
+}
+
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static MAX_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+#[function("async_track(int) -> int")]
+async fn async_track(x: i32) -> i32 {
+    let n = IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+    MAX_IN_FLIGHT.fetch_max(n, Ordering::SeqCst);
+    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    x
+}
+
+#[function("async_div(int, int) -> int")]
+async fn async_div(x: i32, y: i32) -> Result<i32, &'static str> {
+    tokio::task::yield_now().await;
+    x.checked_div(y).ok_or("division by zero")
+}
+
 #[function("to_json(boolean) -> json")]
 #[function("to_json(int*) -> json")]
 #[function("to_json(uint*) -> json")]
@@ -119,6 +344,12 @@ fn datetime(date: NaiveDate, time: NaiveTime) -> NaiveDateTime {
     NaiveDateTime::new(date, time)
 }
 
+#[function("add_hour(timestamptz) -> timestamptz")]
+fn add_hour(t: DateTime<Utc>) -> DateTime<FixedOffset> {
+    let offset = FixedOffset::east_opt(3600).unwrap();
+    t.with_timezone(&offset) + chrono::Duration::hours(1)
+}
+
 #[function("length(string) -> int")]
 #[function("length(binary) -> int")]
 #[function("length(largestring) -> int")]
@@ -242,6 +473,67 @@ fn large_binary_array(_: &LargeBinaryArray) -> impl Iterator<Item = Vec<u8>> {
     [].into_iter()
 }
 
+#[function("array_max(date32[]) -> date32")]
+fn array_max(dates: impl Iterator<Item = Option<NaiveDate>>) -> Option<NaiveDate> {
+    dates.flatten().max()
+}
+
+#[function("nested_sum(int32[][]) -> int32[]")]
+fn nested_sum<'a>(lists: impl Iterator<Item = Option<&'a [i32]>>) -> Vec<i32> {
+    lists.map(|l| l.map_or(0, |l| l.iter().sum())).collect()
+}
+
+#[function("range_lists(int32) -> int32[][]")]
+fn range_lists(n: i32) -> impl Iterator<Item = std::ops::Range<i32>> {
+    (0..n).map(|i| 0..i)
+}
+
+#[function("points(int32) -> struct Point[]")]
+fn points(n: i32) -> impl Iterator<Item = Point> {
+    (0..n).map(|i| Point {
+        x: i as f64,
+        y: 1.0,
+    })
+}
+
+#[function("centroid(struct Point[]) -> struct Point")]
+fn centroid(points: impl Iterator<Item = Option<Point>>) -> Option<Point> {
+    let (n, x, y) = points
+        .flatten()
+        .fold((0, 0.0, 0.0), |(n, x, y), p| (n + 1, x + p.x, y + p.y));
+    (n > 0).then(|| Point {
+        x: x / n as f64,
+        y: y / n as f64,
+    })
+}
+
+#[function("tags(string) -> map<string,string>")]
+fn tags(s: &str) -> impl Iterator<Item = (&str, &str)> {
+    s.split(',').filter_map(|kv| kv.split_once('='))
+}
+
+#[function("map_keys(map<string,string>) -> string[]")]
+fn map_keys<'a>(
+    map: impl Iterator<Item = (&'a str, Option<&'a str>)>,
+) -> impl Iterator<Item = &'a str> {
+    map.map(|(k, _)| k)
+}
+
+#[function("map_sum(map<string,int64>) -> int64")]
+fn map_sum<'a>(map: impl Iterator<Item = (&'a str, Option<i64>)>) -> i64 {
+    map.filter_map(|(_, v)| v).sum()
+}
+
+#[function("double(decimal(10,2)) -> decimal(10,2)")]
+fn double(x: Decimal) -> Decimal {
+    x * Decimal::TWO
+}
+
+#[function("to_decimals(int32[]) -> decimal(10,2)[]")]
+fn to_decimals(a: &[i32]) -> impl Iterator<Item = Decimal> + '_ {
+    a.iter().map(|&v| Decimal::new(v as i64, 2))
+}
+
 #[derive(StructType)]
 struct KeyValue<'a> {
     key: &'a str,
@@ -254,6 +546,144 @@ fn key_value(kv: &str) -> Option<KeyValue<'_>> {
     Some(KeyValue { key, value })
 }
 
+#[function("key(struct KeyValue) -> string")]
+fn key(kv: KeyValue<'_>) -> &str {
+    kv.key
+}
+
+#[derive(StructType)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+#[derive(StructType)]
+struct Segment {
+    start: Point,
+    end: Point,
+    label: Option<String>,
+}
+
+#[function("segment_length(struct Segment) -> float64")]
+fn segment_length(s: Segment) -> f64 {
+    (s.end.x - s.start.x).hypot(s.end.y - s.start.y)
+}
+
+#[function("label(struct Segment) -> string")]
+fn label(s: Segment) -> Option<String> {
+    s.label
+}
+
+#[derive(StructType)]
+struct Pair<'a>(i32, Option<&'a str>);
+
+#[function("split_pair(string) -> struct Pair")]
+fn split_pair(s: &str) -> Option<Pair<'_>> {
+    let (n, rest) = s.split_once(':').unwrap_or((s, ""));
+    Some(Pair(n.parse().ok()?, (!rest.is_empty()).then_some(rest)))
+}
+
+#[function("pair_second(struct Pair) -> string")]
+fn pair_second(p: Pair<'_>) -> Option<&str> {
+    p.1
+}
+
+#[derive(StructType)]
+struct Labeled<'a: 'b, 'b> {
+    name: &'a str,
+    label: Option<&'b str>,
+}
+
+type Payload = serde_json::Value;
+type Tag = String;
+
+#[derive(StructType)]
+struct Event {
+    #[struct_type(rename = "type")]
+    kind: String,
+    #[struct_type(ty = "json")]
+    payload: Option<Payload>,
+    #[struct_type(ty = "string[]")]
+    tags: Vec<Tag>,
+    origin: Option<Point>,
+    #[struct_type(skip)]
+    cached: usize,
+}
+
+#[function("parse_event(string) -> struct Event")]
+fn parse_event(s: &str) -> Option<Event> {
+    let (kind, rest) = s.split_once(':')?;
+    let payload = rest.parse().ok();
+    Some(Event {
+        kind: kind.to_string(),
+        payload,
+        tags: kind.split('.').map(String::from).collect(),
+        origin: (kind == "click").then_some(Point { x: 1.0, y: 2.0 }),
+        cached: s.len(),
+    })
+}
+
+#[function("describe_event(struct Event) -> string")]
+fn describe_event(e: Event) -> String {
+    format!(
+        "{} tags={:?} payload={:?} cached={}",
+        e.kind,
+        e.tags,
+        e.payload.map(|p| p.to_string()),
+        e.cached
+    )
+}
+
+#[derive(StructType, Debug, Clone, Copy)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[function("color_of(int) -> struct Color")]
+fn color_of(i: i32) -> Option<Color> {
+    [Color::Red, Color::Green, Color::Blue]
+        .get(i as usize)
+        .copied()
+}
+
+#[function("is_red(struct Color) -> boolean")]
+fn is_red(c: Color) -> bool {
+    matches!(c, Color::Red)
+}
+
+#[derive(StructType, Debug)]
+enum Shape {
+    Circle(f64),
+    Rect { width: f64, height: f64 },
+    Empty,
+}
+
+#[function("parse_shape(string) -> struct Shape")]
+fn parse_shape(s: &str) -> Option<Shape> {
+    let nums = s
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().ok())
+        .collect::<Option<Vec<f64>>>()?;
+    match nums[..] {
+        [] => Some(Shape::Empty),
+        [r] => Some(Shape::Circle(r)),
+        [width, height] => Some(Shape::Rect { width, height }),
+        _ => None,
+    }
+}
+
+#[function("area(struct Shape) -> float64")]
+fn area(s: Shape) -> f64 {
+    match s {
+        Shape::Circle(r) => std::f64::consts::PI * r * r,
+        Shape::Rect { width, height } => width * height,
+        Shape::Empty => 0.0,
+    }
+}
+
 #[function("key_values(string) -> setof struct KeyValue")]
 fn key_values(kv: &str) -> impl Iterator<Item = KeyValue<'_>> {
     kv.split(',').filter_map(|kv| {
@@ -262,6 +692,28 @@ fn key_values(kv: &str) -> impl Iterator<Item = KeyValue<'_>> {
     })
 }
 
+#[function("split_pairs(string) -> table(key string, value int32)")]
+fn split_pairs(s: &str) -> impl Iterator<Item = Option<(&str, Option<i32>)>> {
+    s.split(',').map(|kv| {
+        let (key, value) = kv.split_once('=')?;
+        Some((key, value.parse().ok()))
+    })
+}
+
+#[derive(StructType)]
+struct Cell {
+    row: i32,
+    value: i32,
+}
+
+#[function("cells(string) -> setof struct Cell")]
+fn cells(s: &str) -> impl Iterator<Item = Cell> + '_ {
+    s.split(',').enumerate().map(|(i, v)| Cell {
+        row: i as i32,
+        value: v.len() as i32,
+    })
+}
+
 #[derive(StructType)]
 struct StructOfAll {
     // FIXME: panic on 'StructBuilder and field_builders are of unequal lengths.'
@@ -342,6 +794,96 @@ fn json_array_elements(
     }
 }
 
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Order {
+    id: u64,
+    #[serde(rename = "customer")]
+    customer_name: String,
+    items: Vec<OrderItem>,
+    note: Option<String>,
+    status: OrderStatus,
+    extra: serde_json::Value,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct OrderItem {
+    sku: String,
+    quantity: i32,
+    price: f64,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OrderStatus {
+    Pending,
+    Shipped,
+}
+
+#[cfg(feature = "serde")]
+type OrderStruct = Serde<Order>;
+
+#[cfg(feature = "serde")]
+#[function("parse_order(json) -> struct OrderStruct")]
+fn parse_order(order: Json<Order>) -> OrderStruct {
+    Serde(order.0)
+}
+
+#[cfg(feature = "serde")]
+#[function("order_total(struct OrderStruct) -> float64")]
+fn order_total(order: OrderStruct) -> f64 {
+    let items = order.0.items.iter();
+    items.fold(0.0, |sum, item| sum + item.quantity as f64 * item.price)
+}
+
+#[cfg(feature = "serde")]
+#[function("ship_order(struct OrderStruct) -> json")]
+fn ship_order(order: OrderStruct) -> Json<Order> {
+    Json(Order {
+        status: OrderStatus::Shipped,
+        ..order.0
+    })
+}
+
+#[cfg(feature = "serde")]
+type CountStruct = Serde<u32>;
+
+#[cfg(feature = "serde")]
+#[function("count_value(struct CountStruct) -> int64")]
+fn count_value(count: CountStruct) -> i64 {
+    count.0 as i64
+}
+
+#[aggregate("sum(int32) -> int64", merge = "sum_merge")]
+fn sum(state: i64, value: i32, retract: bool) -> i64 {
+    if retract {
+        state - value as i64
+    } else {
+        state + value as i64
+    }
+}
+
+fn sum_merge(state1: i64, state2: i64) -> i64 {
+    state1 + state2
+}
+
+#[aggregate("count(int32) -> int64", init_state = "0i64", append_only)]
+fn count(state: i64, _: i32) -> i64 {
+    state + 1
+}
+
+#[aggregate("min_len(string) -> int32", state = "int32", finish = "min_len_finish")]
+fn min_len(state: Option<i32>, value: &str) -> i32 {
+    let len = value.len() as i32;
+    state.map_or(len, |s| s.min(len))
+}
+
+fn min_len_finish(state: i32) -> i32 {
+    state
+}
+
 #[function("many_args(int, int, int, int, int, int, int, int, int, int, int, int, int, int, int, int) -> int")]
 #[allow(clippy::too_many_arguments)]
 fn many_args(
@@ -400,13 +942,49 @@ fn test_div() {
     check(
         &[output],
         expect![[r#"
-        +-----+------------------+
-        | div | error            |
-        +-----+------------------+
-        |     | division by zero |
-        | 1   |                  |
-        |     |                  |
-        +-----+------------------+"#]],
+        +-----+---------------------------------------------------------+
+        | div | error                                                   |
+        +-----+---------------------------------------------------------+
+        |     | {row: 0, code: 0, message: division by zero, details: } |
+        | 1   |                                                         |
+        |     |                                                         |
+        +-----+---------------------------------------------------------+"#]],
+    );
+}
+
+#[test]
+fn test_error_code() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec![Some("1"), Some(""), Some("a"), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = parse_int_string_int32_eval(&input).unwrap();
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +-----------+----------------------------------------------------------------+
+        | parse_int | error                                                          |
+        +-----------+----------------------------------------------------------------+
+        | 1         |                                                                |
+        |           | {row: 1, code: 1, message: empty input, details: }             |
+        |           | {row: 2, code: 2, message: invalid digit, details: input: "a"} |
+        |           |                                                                |
+        +-----------+----------------------------------------------------------------+"#]],
+    );
+
+    let error = output.column(1).as_struct();
+    assert_eq!(
+        error
+            .column_by_name("code")
+            .unwrap()
+            .as_primitive::<Int32Type>()
+            .values(),
+        &[0, 1, 2, 0]
+    );
+    assert_eq!(error.null_count(), 2);
+    assert_eq!(
+        output.schema().field(1).metadata()[arrow_udf::error::FUNCTION_METADATA_KEY],
+        "parse_int"
     );
 }
 
@@ -430,296 +1008,2543 @@ fn test_key_value() {
 }
 
 #[test]
-fn test_key_values() {
+fn test_struct_field_attributes() {
     let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
-    let arg0 = StringArray::from(vec!["a=b,c=d"]);
+    let arg0 = StringArray::from(vec![Some(r#"click:{"a":1}"#), Some("key.up:?"), None]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = key_values_string_struct_KeyValue_eval(&input)
-        .unwrap()
-        .next()
-        .unwrap();
+    let output = parse_event_string_struct_Event_eval(&input).unwrap();
+    let DataType::Struct(fields) = output.schema().field(0).data_type().clone() else {
+        panic!("expect struct type");
+    };
+    let names = fields.iter().map(|f| f.name().as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["type", "payload", "tags", "origin"]);
+    assert_eq!(fields[1].data_type(), &DataType::Utf8);
+    assert_eq!(
+        fields[1].metadata().get("ARROW:extension:name").unwrap(),
+        "arrowudf.json"
+    );
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +--------------------------------------------------------------------------+
+        | parse_event                                                              |
+        +--------------------------------------------------------------------------+
+        | {type: click, payload: {"a":1}, tags: [click], origin: {x: 1.0, y: 2.0}} |
+        | {type: key.up, payload: , tags: [key, up], origin: }                     |
+        |                                                                          |
+        +--------------------------------------------------------------------------+"#]],
+    );
+
+    let output = describe_event_struct_Event_string_eval(&output).unwrap();
     check(
         &[output],
         expect![[r#"
-        +-----+--------------------+
-        | row | key_values         |
-        +-----+--------------------+
-        | 0   | {key: a, value: b} |
-        | 0   | {key: c, value: d} |
-        +-----+--------------------+"#]],
+        +---------------------------------------------------------+
+        | describe_event                                          |
+        +---------------------------------------------------------+
+        | click tags=["click"] payload=Some("{\"a\":1}") cached=0 |
+        | key.up tags=["key", "up"] payload=None cached=0         |
+        |                                                         |
+        +---------------------------------------------------------+"#]],
     );
 }
 
 #[test]
-fn test_struct_of_all() {
-    let schema = Schema::new(vec![Field::new("int32", DataType::Int32, true)]);
-    let arg0 = Int32Array::from(vec![1]);
+fn test_tuple_struct() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec![Some("1:a"), Some("2"), Some("?"), None]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = struct_of_all_struct_StructOfAll_eval(&input).unwrap();
+    let output = split_pair_string_struct_Pair_eval(&input).unwrap();
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +----------------+
+        | split_pair     |
+        +----------------+
+        | {f0: 1, f1: a} |
+        | {f0: 2, f1: }  |
+        |                |
+        |                |
+        +----------------+"#]],
+    );
+
+    let output = pair_second_struct_Pair_string_eval(&output).unwrap();
     check(
         &[output],
         expect![[r#"
-        +---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+
-        | struct_of_all                                                                                                                                                                                                                                                                               |
-        +---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+
-        | {b: , a: 0, c: 1, d: 2, e: 3, aa: 4, cc: 5, dd: 6, ee: 7, f: 4.0, g: 5.0, h: 0.006, i: 2022-04-08, j: 12:34:56.789012, k: 2022-04-08T12:34:56.789012, l: 0 years 7 mons 8 days 0 hours 0 mins 0.000000009 secs, m: {"key":"value"}, n: string, o: 0a0b0c, p: [a, b], q: {key: a, value: b}} |
-        +---------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+"#]],
+        +-------------+
+        | pair_second |
+        +-------------+
+        | a           |
+        |             |
+        |             |
+        |             |
+        +-------------+"#]],
     );
 }
 
 #[test]
-fn test_split() {
-    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
-    let arg0 = StringArray::from(vec!["a,b"]);
+fn test_fieldless_enum() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(0), Some(2), Some(5), None]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = split_string_stringarray_eval(&input).unwrap();
+    let output = color_of_int32_struct_Color_eval(&input).unwrap();
+    assert_eq!(
+        output.schema().field(0).data_type(),
+        &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+    );
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +----------+
+        | color_of |
+        +----------+
+        | Red      |
+        | Blue     |
+        |          |
+        |          |
+        +----------+"#]],
+    );
+
+    let output = is_red_struct_Color_boolean_eval(&output).unwrap();
     check(
         &[output],
         expect![[r#"
         +--------+
-        | split  |
+        | is_red |
         +--------+
-        | [a, b] |
+        | true   |
+        | false  |
+        |        |
+        |        |
         +--------+"#]],
     );
+
+    // variant names are also accepted as strings
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec!["Red", "Yellow"]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+    let err = is_red_struct_Color_boolean_eval(&input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"Invalid argument error: invalid variant: "Yellow", expect one of ["Red", "Green", "Blue"]"#
+    );
+
+    // out-of-range variant indices are errors
+    let array = StructArray::new(
+        Color::fields(),
+        vec![Arc::new(Int32Array::from(vec![7]))],
+        None,
+    );
+    let err = Color::from_struct_array(&array, 0).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid argument error: invalid variant index: 7"
+    );
 }
 
 #[test]
-fn test_option_add() {
-    let schema = Schema::new(vec![
-        Field::new("x", DataType::Int32, true),
-        Field::new("y", DataType::Int32, true),
-    ]);
-    let arg0 = Int32Array::from(vec![Some(1), Some(1), None, None]);
-    let arg1 = Int32Array::from(vec![Some(1), None, Some(1), None]);
-    let input =
-        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+fn test_enum_union() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec![Some("1"), Some("2,3"), Some(""), Some("?"), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = option_add_int32_int32_int32_eval(&input).unwrap();
+    let output = parse_shape_string_struct_Shape_eval(&input).unwrap();
+    assert!(matches!(
+        output.schema().field(0).data_type(),
+        DataType::Union(_, arrow_schema::UnionMode::Dense)
+    ));
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +----------------------------------+
+        | parse_shape                      |
+        +----------------------------------+
+        | {Circle={f0: 1.0}}               |
+        | {Rect={width: 2.0, height: 3.0}} |
+        | {Empty=}                         |
+        | {Circle=}                        |
+        | {Circle=}                        |
+        +----------------------------------+"#]],
+    );
+
+    let output = area_struct_Shape_float64_eval(&output).unwrap();
     check(
         &[output],
         expect![[r#"
-        +------------+
-        | option_add |
-        +------------+
-        | 2          |
-        | 1          |
-        |            |
-        |            |
-        +------------+"#]],
+        +-------------------+
+        | area              |
+        +-------------------+
+        | 3.141592653589793 |
+        | 6.0               |
+        | 0.0               |
+        |                   |
+        |                   |
+        +-------------------+"#]],
     );
 }
 
 #[test]
-fn test_array_sum() {
-    let schema = Schema::new(vec![Field::new(
-        "x",
-        DataType::new_list(DataType::Int32, true),
-        true,
-    )]);
-    let arg0 = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
-        Some(vec![Some(0), Some(1), Some(2)]),
-        None,
-        Some(vec![Some(3), None, Some(5)]),
-        Some(vec![Some(6), Some(7)]),
-    ]);
+fn test_struct_arg() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec!["a=b", "??"]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = array_sum_int32array_int32_eval(&input).unwrap();
+    let kv = key_value_string_struct_KeyValue_eval(&input).unwrap();
+    let output = key_struct_KeyValue_string_eval(&kv).unwrap();
     check(
         &[output],
         expect![[r#"
-        +-----------+
-        | array_sum |
-        +-----------+
-        | 3         |
-        |           |
-        | 8         |
-        | 13        |
-        +-----------+"#]],
+        +-----+
+        | key |
+        +-----+
+        | a   |
+        |     |
+        +-----+"#]],
     );
 }
 
 #[test]
-fn test_temporal() {
-    let schema = Schema::new(vec![
-        Field::new("date", DataType::Date32, true),
-        Field::new("time", DataType::Time64(TimeUnit::Microsecond), true),
+fn test_struct_field_nullability() {
+    // only `Option` fields are nullable
+    let fields = Segment::fields();
+    assert!(!fields[0].is_nullable());
+    assert!(!fields[1].is_nullable());
+    assert!(fields[2].is_nullable());
+    let fields = Pair::fields();
+    assert!(!fields[0].is_nullable());
+    assert!(fields[1].is_nullable());
+
+    // inputs with nullable fields are accepted
+    let point = Fields::from(vec![
+        Field::new("x", DataType::Float64, true),
+        Field::new("y", DataType::Float64, true),
     ]);
-    let arg0 = Date32Array::from(vec![Date32Type::from_naive_date(
-        NaiveDate::from_ymd_opt(2022, 4, 8).unwrap(),
-    )]);
-    let arg1 = Time64MicrosecondArray::from(vec![time_to_time64us(
-        NaiveTime::from_hms_micro_opt(12, 34, 56, 789_012).unwrap(),
-    )]);
-    let input =
-        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
-
-    let output = datetime_date32_time64_timestamp_eval(&input).unwrap();
+    let segment = Fields::from(vec![
+        Field::new("start", DataType::Struct(point.clone()), true),
+        Field::new("end", DataType::Struct(point.clone()), true),
+        Field::new("label", DataType::Utf8, true),
+    ]);
+    #[cfg(feature = "global_registry")]
+    {
+        let arg = Field::new("", DataType::Struct(segment.clone()), true);
+        let ret = Field::new("", DataType::Float64, true);
+        let sig = arrow_udf::sig::REGISTRY.get("segment_length", &[arg], &ret);
+        assert!(sig.is_some());
+    }
+    let point_array = |x: f64, y: f64| -> ArrayRef {
+        Arc::new(StructArray::new(
+            point.clone(),
+            vec![
+                Arc::new(Float64Array::from(vec![x])),
+                Arc::new(Float64Array::from(vec![y])),
+            ],
+            None,
+        ))
+    };
+    let array = StructArray::new(
+        segment.clone(),
+        vec![
+            point_array(0.0, 0.0),
+            point_array(3.0, 4.0),
+            Arc::new(StringArray::from(vec![None::<&str>])),
+        ],
+        None,
+    );
+    let schema = Schema::new(vec![Field::new("s", DataType::Struct(segment), true)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array)]).unwrap();
+    let output = segment_length_struct_Segment_float64_eval(&input).unwrap();
     check(
         &[output],
         expect![[r#"
-        +----------------------------+
-        | datetime                   |
-        +----------------------------+
-        | 2022-04-08T12:34:56.789012 |
-        +----------------------------+"#]],
+        +----------------+
+        | segment_length |
+        +----------------+
+        | 5.0            |
+        +----------------+"#]],
     );
 }
 
 #[test]
-fn test_decimal_add() {
-    let schema = Schema::new(vec![decimal_field("a"), decimal_field("b")]);
-    let arg0 = StringArray::from(vec!["0.0001"]);
-    let arg1 = StringArray::from(vec!["0.0002"]);
-    let input =
-        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+fn test_struct_bounded_lifetime() {
+    let mut builder = builder::StructBuilder::from_fields(Labeled::fields(), 1);
+    Labeled {
+        name: "a",
+        label: Some("b"),
+    }
+    .append_to(&mut builder)
+    .unwrap();
+    let array = builder.finish();
+    let labeled = Labeled::from_struct_array(&array, 0).unwrap();
+    assert_eq!((labeled.name, labeled.label), ("a", Some("b")));
+}
 
-    let output = add_decimal_decimal_decimal_eval(&input).unwrap();
-    assert_eq!(output.schema().field(0), &decimal_field("add"));
+#[test]
+fn test_nested_struct_arg() {
+    let mut builder = builder::StructBuilder::from_fields(Segment::fields(), 3);
+    Segment {
+        start: Point { x: 0.0, y: 0.0 },
+        end: Point { x: 3.0, y: 4.0 },
+        label: Some("a".into()),
+    }
+    .append_to(&mut builder)
+    .unwrap();
+    Segment {
+        start: Point { x: 1.0, y: 1.0 },
+        end: Point { x: 2.0, y: 2.0 },
+        label: None,
+    }
+    .append_to(&mut builder)
+    .unwrap();
+    Segment::append_null(&mut builder);
+    let array = builder.finish();
+    let schema = Schema::new(vec![Field::new("s", array.data_type().clone(), true)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array)]).unwrap();
+
+    let output = segment_length_struct_Segment_float64_eval(&input).unwrap();
     check(
         &[output],
         expect![[r#"
-        +--------+
-        | add    |
-        +--------+
-        | 0.0003 |
-        +--------+"#]],
+        +--------------------+
+        | segment_length     |
+        +--------------------+
+        | 5.0                |
+        | 1.4142135623730951 |
+        |                    |
+        +--------------------+"#]],
+    );
+
+    let output = label_struct_Segment_string_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-------+
+        | label |
+        +-------+
+        | a     |
+        |       |
+        |       |
+        +-------+"#]],
     );
 }
 
 #[test]
-fn test_json() {
-    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-    let arg0 = Int32Array::from(vec![Some(1), None]);
+fn test_dictionary_input() {
+    let arg0: DictionaryArray<Int32Type> = vec![Some("a=b"), None, Some("??"), Some("a=b")]
+        .into_iter()
+        .collect();
+    let schema = Schema::new(vec![Field::new("x", arg0.data_type().clone(), true)]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = to_json_int32_json_eval(&input).unwrap();
-    assert_eq!(output.schema().field(0), &json_field("to_json"));
+    // pure functions are evaluated on the dictionary values
+    let output = identity_string_string_eval(&input).unwrap();
+    assert_eq!(
+        output.schema().field(0).data_type(),
+        &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+    );
     check(
         &[output],
         expect![[r#"
-        +---------+
-        | to_json |
-        +---------+
-        | 1       |
-        | null    |
-        +---------+"#]],
+        +----------+
+        | identity |
+        +----------+
+        | a=b      |
+        |          |
+        | ??       |
+        | a=b      |
+        +----------+"#]],
+    );
+
+    let output = key_value_string_struct_KeyValue_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +--------------------+
+        | key_value          |
+        +--------------------+
+        | {key: a, value: b} |
+        |                    |
+        |                    |
+        | {key: a, value: b} |
+        +--------------------+"#]],
     );
+
+    #[cfg(feature = "global_registry")]
+    {
+        let arg = Field::new("", input.schema().field(0).data_type().clone(), true);
+        let ret = Field::new("", DataType::Utf8, true);
+        let sig = arrow_udf::sig::REGISTRY.get("identity", &[arg], &ret);
+        assert!(sig.is_some());
+    }
 }
 
 #[test]
-fn test_range() {
-    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-    let arg0 = Int32Array::from(vec![Some(1), None, Some(3)]);
+fn test_view_input() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8View, true)]);
+    let arg0 = StringViewArray::from(vec![Some("a=b"), None]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = range_int32_int32_eval(&input).unwrap().next().unwrap();
+    let output = key_value_string_struct_KeyValue_eval(&input).unwrap();
     check(
         &[output],
         expect![[r#"
-        +-----+-------+
-        | row | range |
-        +-----+-------+
-        | 0   | 0     |
-        | 2   | 0     |
-        | 2   | 1     |
-        | 2   | 2     |
-        +-----+-------+"#]],
+        +--------------------+
+        | key_value          |
+        +--------------------+
+        | {key: a, value: b} |
+        |                    |
+        +--------------------+"#]],
     );
 
-    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
-    let arg0 = Int32Array::from(vec![1000000]);
+    #[cfg(feature = "global_registry")]
+    {
+        let arg = Field::new("", DataType::Utf8View, true);
+        let ret = Field::new("", DataType::Utf8, true);
+        let sig = arrow_udf::sig::REGISTRY.get("identity", &[arg], &ret);
+        assert!(sig.is_some());
+    }
+}
+
+#[test]
+fn test_key_values() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec!["a=b,c=d"]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    // for large set, the output is split into multiple batches
-    let mut i = 0;
-    for output in range_int32_int32_eval(&input).unwrap() {
-        let array = output
-            .column(1)
-            .as_any()
-            .downcast_ref::<Int32Array>()
-            .unwrap();
-        for x in array {
-            assert_eq!(x, Some(i));
-            i += 1;
-        }
-    }
+    let output = key_values_string_struct_KeyValue_eval(&input)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-----+-----+-------+
+        | row | key | value |
+        +-----+-----+-------+
+        | 0   | a   | b     |
+        | 0   | c   | d     |
+        +-----+-----+-------+"#]],
+    );
 }
 
 #[test]
-fn test_json_array_elements() {
-    let schema = Schema::new(vec![json_field("d")]);
-    let arg0 = StringArray::from(vec![r#"[null,1,""]"#, "1"]);
+fn test_table_columns() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec![Some("a=1,b,c=x"), None, Some("d=4")]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = json_array_elements_json_json_eval(&input)
+    let output = split_pairs_string_table_key_string_value_int32_eval(&input)
         .unwrap()
         .next()
+        .unwrap()
         .unwrap();
     check(
         &[output],
         expect![[r#"
-        +-----+---------------------+--------------+
-        | row | json_array_elements | error        |
-        +-----+---------------------+--------------+
-        | 0   | null                |              |
-        | 0   | 1                   |              |
-        | 0   | ""                  |              |
-        | 1   |                     | not an array |
-        +-----+---------------------+--------------+"#]],
+        +-----+-----+-------+
+        | row | key | value |
+        +-----+-----+-------+
+        | 0   | a   | 1     |
+        | 0   |     |       |
+        | 0   | c   |       |
+        | 2   | d   | 4     |
+        +-----+-----+-------+"#]],
     );
 }
 
 #[test]
-fn test_pub() {
-    let schema = Schema::new(vec![Field::new("uint32", DataType::UInt32, true)]);
-    let arg0 = UInt32Array::from(vec![Some(1), None]);
+fn test_table_column_conflict() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec!["a,bc"]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = maybe_visible_pub_udf(&input).unwrap();
+    let Err(err) = cells_string_struct_Cell_eval(&input) else {
+        panic!("expect an error");
+    };
+    assert_eq!(
+        err.to_string(),
+        "Invalid argument error: struct field `row` of table function `cells` conflicts with the `row` column"
+    );
+}
+
+#[test]
+fn test_struct_of_all() {
+    let schema = Schema::new(vec![Field::new("int32", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![1]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = struct_of_all_struct_StructOfAll_eval(&input).unwrap();
     check(
         &[output],
         expect![[r#"
-    +---------------+
-    | maybe_visible |
-    +---------------+
-    | 1             |
-    |               |
-    +---------------+"#]],
+        +----------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+
+        | struct_of_all                                                                                                                                                                                                                                                        |
+        +----------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+
+        | {b: , a: 0, c: 1, d: 2, e: 3, aa: 4, cc: 5, dd: 6, ee: 7, f: 4.0, g: 5.0, h: 0.006, i: 2022-04-08, j: 12:34:56.789012, k: 2022-04-08T12:34:56.789012, l: 7 mons 8 days 0.000000009 secs, m: {"key":"value"}, n: string, o: 0a0b0c, p: [a, b], q: {key: a, value: b}} |
+        +----------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------+"#]],
     );
 }
 
 #[test]
-fn test_pub_crate() {
-    let schema = Schema::new(vec![Field::new("float32", DataType::Float32, true)]);
-    let arg0 = Float32Array::from(vec![Some(1.0), None]);
+fn test_split() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec!["a,b"]);
     let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
 
-    let output = maybe_visible_pub_crate_udf(&input).unwrap();
+    let output = split_string_stringarray_eval(&input).unwrap();
     check(
         &[output],
         expect![[r#"
-    +---------------+
-    | maybe_visible |
-    +---------------+
-    | 1.0           |
-    |               |
-    +---------------+"#]],
+        +--------+
+        | split  |
+        +--------+
+        | [a, b] |
+        +--------+"#]],
     );
 }
 
-/// Returns a field with JSON type.
-fn json_field(name: &str) -> Field {
-    Field::new(name, DataType::Utf8, true)
-        .with_metadata([("ARROW:extension:name".into(), "arrowudf.json".into())].into())
+#[test]
+fn test_date_array_arg() {
+    let mut builder = builder::ListBuilder::new(builder::Date32Builder::new());
+    builder.append_value([Some(19000), None, Some(19001)]);
+    builder.append_null();
+    builder.append_value([None]);
+    let array = builder.finish();
+    let schema = Schema::new(vec![Field::new("x", array.data_type().clone(), true)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array)]).unwrap();
+
+    let output = array_max_date32array_date32_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +------------+
+        | array_max  |
+        +------------+
+        | 2022-01-09 |
+        |            |
+        |            |
+        +------------+"#]],
+    );
+}
+
+#[test]
+fn test_nested_array() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(3), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = range_lists_int32_int32arrayarray_eval(&input).unwrap();
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +-------------------+
+        | range_lists       |
+        +-------------------+
+        | [[], [0], [0, 1]] |
+        |                   |
+        +-------------------+"#]],
+    );
+
+    let output = nested_sum_int32arrayarray_int32array_eval(&output).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +------------+
+        | nested_sum |
+        +------------+
+        | [0, 0, 1]  |
+        |            |
+        +------------+"#]],
+    );
+}
+
+#[test]
+fn test_struct_array() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(3), Some(0), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = points_int32_struct_Pointarray_eval(&input).unwrap();
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +--------------------------------------------------------+
+        | points                                                 |
+        +--------------------------------------------------------+
+        | [{x: 0.0, y: 1.0}, {x: 1.0, y: 1.0}, {x: 2.0, y: 1.0}] |
+        | []                                                     |
+        |                                                        |
+        +--------------------------------------------------------+"#]],
+    );
+
+    let output = centroid_struct_Pointarray_struct_Point_eval(&output).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +------------------+
+        | centroid         |
+        +------------------+
+        | {x: 1.0, y: 1.0} |
+        |                  |
+        |                  |
+        +------------------+"#]],
+    );
+}
+
+#[test]
+fn test_map() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec![Some("a=1,b=2"), Some(""), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = tags_string_map_string_string_eval(&input).unwrap();
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +--------------+
+        | tags         |
+        +--------------+
+        | {a: 1, b: 2} |
+        | {}           |
+        |              |
+        +--------------+"#]],
+    );
+
+    let output = map_keys_map_string_string_stringarray_eval(&output).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------+
+        | map_keys |
+        +----------+
+        | [a, b]   |
+        | []       |
+        |          |
+        +----------+"#]],
+    );
+}
+
+#[test]
+fn test_map_sum() {
+    // use the names of map entries in Parquet, which differ from the defaults of arrow-rs
+    let field_names = builder::MapFieldNames {
+        entry: "key_value".into(),
+        key: "key".into(),
+        value: "value".into(),
+    };
+    let mut builder = builder::MapBuilder::new(
+        Some(field_names),
+        builder::StringBuilder::new(),
+        builder::Int64Builder::new(),
+    );
+    builder.keys().append_value("a");
+    builder.values().append_value(1);
+    builder.keys().append_value("b");
+    builder.values().append_null();
+    builder.keys().append_value("c");
+    builder.values().append_value(3);
+    builder.append(true).unwrap();
+    builder.append(false).unwrap();
+    let array = builder.finish();
+    let schema = Schema::new(vec![Field::new("x", array.data_type().clone(), true)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array)]).unwrap();
+
+    let output = map_sum_map_string_int64_int64_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +---------+
+        | map_sum |
+        +---------+
+        | 4       |
+        |         |
+        +---------+"#]],
+    );
+
+    #[cfg(feature = "global_registry")]
+    {
+        let arg = input.schema().field(0).clone();
+        let ret = Field::new("", DataType::Int64, true);
+        let sig = arrow_udf::sig::REGISTRY.get("map_sum", &[arg], &ret);
+        assert!(sig.is_some());
+    }
+}
+
+#[test]
+fn test_decimal_array() {
+    let schema = Schema::new(vec![Field::new(
+        "x",
+        DataType::new_list(DataType::Int32, true),
+        true,
+    )]);
+    let arg0 =
+        ListArray::from_iter_primitive::<Int32Type, _, _>(vec![Some(vec![Some(1), Some(-250)])]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = to_decimals_int32array_decimal_10_2array_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +---------------+-------+
+        | to_decimals   | error |
+        +---------------+-------+
+        | [0.01, -2.50] |       |
+        +---------------+-------+"#]],
+    );
+}
+
+#[test]
+fn test_option_add() {
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Int32, true),
+        Field::new("y", DataType::Int32, true),
+    ]);
+    let arg0 = Int32Array::from(vec![Some(1), Some(1), None, None]);
+    let arg1 = Int32Array::from(vec![Some(1), None, Some(1), None]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = option_add_int32_int32_int32_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +------------+
+        | option_add |
+        +------------+
+        | 2          |
+        | 1          |
+        |            |
+        |            |
+        +------------+"#]],
+    );
+}
+
+#[test]
+fn test_array_sum() {
+    let schema = Schema::new(vec![Field::new(
+        "x",
+        DataType::new_list(DataType::Int32, true),
+        true,
+    )]);
+    let arg0 = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(0), Some(1), Some(2)]),
+        None,
+        Some(vec![Some(3), None, Some(5)]),
+        Some(vec![Some(6), Some(7)]),
+    ]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = array_sum_int32array_int32_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-----------+
+        | array_sum |
+        +-----------+
+        | 3         |
+        |           |
+        | 8         |
+        | 13        |
+        +-----------+"#]],
+    );
+}
+
+#[test]
+fn test_temporal() {
+    let schema = Schema::new(vec![
+        Field::new("date", DataType::Date32, true),
+        Field::new("time", DataType::Time64(TimeUnit::Microsecond), true),
+    ]);
+    let arg0 = Date32Array::from(vec![Date32Type::from_naive_date(
+        NaiveDate::from_ymd_opt(2022, 4, 8).unwrap(),
+    )]);
+    let arg1 = Time64MicrosecondArray::from(vec![time_to_time64us(
+        NaiveTime::from_hms_micro_opt(12, 34, 56, 789_012).unwrap(),
+    )]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = datetime_date32_time64_timestamp_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------------------------+
+        | datetime                   |
+        +----------------------------+
+        | 2022-04-08T12:34:56.789012 |
+        +----------------------------+"#]],
+    );
+}
+
+#[test]
+fn test_timestamptz() {
+    let timestamptz = DataType::Timestamp(TimeUnit::Microsecond, Some("+08:00".into()));
+    let schema = Schema::new(vec![Field::new("t", timestamptz.clone(), true)]);
+    let arg0 = TimestampMicrosecondArray::from(vec![Some(1_000_000), None]).with_timezone("+08:00");
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = add_hour_timestamptz_timestamptz_eval(&input).unwrap();
+    assert_eq!(output.schema().field(0).data_type(), &timestamptz);
+    check(
+        &[output],
+        expect![[r#"
+        +---------------------------+
+        | add_hour                  |
+        +---------------------------+
+        | 1970-01-01T09:00:01+08:00 |
+        |                           |
+        +---------------------------+"#]],
+    );
+
+    #[cfg(feature = "global_registry")]
+    {
+        let field = Field::new("", timestamptz, true);
+        let sig = arrow_udf::sig::REGISTRY.get("add_hour", std::slice::from_ref(&field), &field);
+        assert!(sig.is_some());
+    }
+}
+
+#[test]
+fn test_decimal_add() {
+    let schema = Schema::new(vec![decimal_field("a"), decimal_field("b")]);
+    let arg0 = StringArray::from(vec!["0.0001"]);
+    let arg1 = StringArray::from(vec!["0.0002"]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = add_decimal_decimal_decimal_eval(&input).unwrap();
+    assert_eq!(output.schema().field(0), &decimal_field("add"));
+    check(
+        &[output],
+        expect![[r#"
+        +--------+
+        | add    |
+        +--------+
+        | 0.0003 |
+        +--------+"#]],
+    );
+}
+
+#[test]
+fn test_decimal128_add() {
+    let decimal = DataType::Decimal128(10, 2);
+    let schema = Schema::new(vec![
+        Field::new("a", decimal.clone(), true),
+        Field::new("b", decimal.clone(), true),
+    ]);
+    let arg0 = Decimal128Array::from(vec![Some(12345), None])
+        .with_precision_and_scale(10, 2)
+        .unwrap();
+    let arg1 = Decimal128Array::from(vec![Some(-5), Some(1)])
+        .with_precision_and_scale(10, 2)
+        .unwrap();
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = add_decimal_10_2_decimal_10_2_decimal_11_2_eval(&input).unwrap();
+    assert_eq!(
+        output.schema().field(0),
+        &Field::new("add", DataType::Decimal128(11, 2), true)
+    );
+    check(
+        &[output],
+        expect![[r#"
+        +--------+-------+
+        | add    | error |
+        +--------+-------+
+        | 123.40 |       |
+        |        |       |
+        +--------+-------+"#]],
+    );
+}
+
+#[test]
+fn test_decimal_out_of_range() {
+    // 10^34 does not fit in the 96-bit mantissa of `rust_decimal::Decimal`
+    let schema = Schema::new(vec![Field::new("x", DataType::Decimal128(38, 2), true)]);
+    let arg0 = Decimal128Array::from(vec![Some(100), Some(10i128.pow(36)), None])
+        .with_precision_and_scale(38, 2)
+        .unwrap();
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = identity_decimal_38_2_decimal_38_2_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------+-------------------------------------------------------------------------------------------------------------------------------+
+        | identity | error                                                                                                                         |
+        +----------+-------------------------------------------------------------------------------------------------------------------------------+
+        | 1.00     |                                                                                                                               |
+        |          | {row: 1, code: 0, message: Invalid argument error: decimal 10000000000000000000000000000000000.00 is out of range, details: } |
+        |          |                                                                                                                               |
+        +----------+-------------------------------------------------------------------------------------------------------------------------------+"#]],
+    );
+
+    // the result of 2 * 60000000.00 does not fit in the precision
+    let schema = Schema::new(vec![Field::new("x", DataType::Decimal128(10, 2), true)]);
+    let arg0 = Decimal128Array::from(vec![Some(12345), Some(6000000000)])
+        .with_precision_and_scale(10, 2)
+        .unwrap();
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = double_decimal_10_2_decimal_10_2_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +--------+-----------------------------------------------------------------------------------------------------------------------------------------------------+
+        | double | error                                                                                                                                               |
+        +--------+-----------------------------------------------------------------------------------------------------------------------------------------------------+
+        | 246.90 |                                                                                                                                                     |
+        |        | {row: 1, code: 0, message: Invalid argument error: 12000000000 is too large to store in a Decimal128 of precision 10. Max is 9999999999, details: } |
+        +--------+-----------------------------------------------------------------------------------------------------------------------------------------------------+"#]],
+    );
+}
+
+#[test]
+fn test_decimal256() {
+    let decimal = DataType::Decimal256(50, 4);
+    let schema = Schema::new(vec![Field::new("x", decimal.clone(), true)]);
+    let arg0 = Decimal256Array::from(vec![Some(arrow_buffer::i256::from_i128(-1234567)), None])
+        .with_precision_and_scale(50, 4)
+        .unwrap();
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = identity_decimal_50_4_decimal_50_4_eval(&input).unwrap();
+    assert_eq!(output.schema().field(0).data_type(), &decimal);
+    check(
+        &[output],
+        expect![[r#"
+        +-----------+-------+
+        | identity  | error |
+        +-----------+-------+
+        | -123.4567 |       |
+        |           |       |
+        +-----------+-------+"#]],
+    );
+}
+
+#[test]
+fn test_json() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(1), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = to_json_int32_json_eval(&input).unwrap();
+    assert_eq!(output.schema().field(0), &json_field("to_json"));
+    check(
+        &[output],
+        expect![[r#"
+        +---------+
+        | to_json |
+        +---------+
+        | 1       |
+        | null    |
+        +---------+"#]],
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let schema = Schema::new(vec![json_field("x")]);
+    let arg0 = StringArray::from(vec![
+        Some(
+            r#"{"id":1,"customer":"alice","items":[{"sku":"a","quantity":2,"price":1.5}],"note":null,"status":"pending","extra":{"gift":true}}"#,
+        ),
+        Some(
+            r#"{"id":2,"customer":"bob","items":[],"note":"fragile","status":"shipped","extra":null}"#,
+        ),
+        None,
+    ]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = parse_order_json_struct_OrderStruct_eval(&input).unwrap();
+    let DataType::Struct(fields) = output.schema().field(0).data_type().clone() else {
+        panic!("expect struct type");
+    };
+    let names = fields.iter().map(|f| f.name().as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["id", "customer", "items", "note", "status", "extra"]
+    );
+    assert_eq!(fields[0].data_type(), &DataType::UInt64);
+    assert_eq!(fields[4].data_type(), &DataType::Utf8);
+    assert_eq!(
+        fields[5].metadata().get("ARROW:extension:name").unwrap(),
+        "arrowudf.json"
+    );
+    check(
+        std::slice::from_ref(&output),
+        expect![[r#"
+        +---------------------------------------------------------------------------------------------------------------------+-------+
+        | parse_order                                                                                                         | error |
+        +---------------------------------------------------------------------------------------------------------------------+-------+
+        | {id: 1, customer: alice, items: [{sku: a, quantity: 2, price: 1.5}], note: , status: pending, extra: {"gift":true}} |       |
+        | {id: 2, customer: bob, items: [], note: fragile, status: shipped, extra: }                                          |       |
+        |                                                                                                                     |       |
+        +---------------------------------------------------------------------------------------------------------------------+-------+"#]],
+    );
+
+    let total = order_total_struct_OrderStruct_float64_eval(&output).unwrap();
+    check(
+        &[total],
+        expect![[r#"
+        +-------------+-------+
+        | order_total | error |
+        +-------------+-------+
+        | 3.0         |       |
+        | 0.0         |       |
+        |             |       |
+        +-------------+-------+"#]],
+    );
+
+    let shipped = ship_order_struct_OrderStruct_json_eval(&output).unwrap();
+    check(
+        &[shipped],
+        expect![[r#"
+        +---------------------------------------------------------------------------------------------------------------------------------+-------+
+        | ship_order                                                                                                                      | error |
+        +---------------------------------------------------------------------------------------------------------------------------------+-------+
+        | {"id":1,"customer":"alice","items":[{"sku":"a","quantity":2,"price":1.5}],"note":null,"status":"shipped","extra":{"gift":true}} |       |
+        | {"id":2,"customer":"bob","items":[],"note":"fragile","status":"shipped","extra":null}                                           |       |
+        |                                                                                                                                 |       |
+        +---------------------------------------------------------------------------------------------------------------------------------+-------+"#]],
+    );
+
+    // valid json that is not an `Order` is reported in the error column
+    let schema = Schema::new(vec![json_field("x")]);
+    let arg0 = StringArray::from(vec![r#"{"id":-1}"#]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+    let output = parse_order_json_struct_OrderStruct_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-------------+-------------------------------------------------------------------------------------------------------------------------------------------+
+        | parse_order | error                                                                                                                                     |
+        +-------------+-------------------------------------------------------------------------------------------------------------------------------------------+
+        |             | {row: 0, code: 0, message: Invalid argument error: invalid json: invalid value: integer `-1`, expected u64 at line 1 column 8, details: } |
+        +-------------+-------------------------------------------------------------------------------------------------------------------------------------------+"#]],
+    );
+
+    // NaN is serialized as null, which can not be deserialized into `f64`
+    let mut builder = builder::StructBuilder::from_fields(OrderStruct::fields(), 1);
+    Serde(Order {
+        id: 3,
+        customer_name: "carol".into(),
+        items: vec![OrderItem {
+            sku: "c".into(),
+            quantity: 1,
+            price: f64::NAN,
+        }],
+        note: None,
+        status: OrderStatus::Pending,
+        extra: serde_json::Value::Null,
+    })
+    .append_to(&mut builder)
+    .unwrap();
+    let array = builder.finish();
+    let schema = Schema::new(vec![Field::new("x", array.data_type().clone(), true)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array)]).unwrap();
+    let output = order_total_struct_OrderStruct_float64_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-------------+-------------------------------------------------------------------------------------------------------------------------------------+
+        | order_total | error                                                                                                                               |
+        +-------------+-------------------------------------------------------------------------------------------------------------------------------------+
+        |             | {row: 0, code: 0, message: Invalid argument error: failed to deserialize tests::Order: invalid type: null, expected f64, details: } |
+        +-------------+-------------------------------------------------------------------------------------------------------------------------------------+"#]],
+    );
+
+    // types that are not structs have no fields, and functions return an error
+    assert!(CountStruct::fields().is_empty());
+    let array = StructArray::new_empty_fields(1, None);
+    let schema = Schema::new(vec![Field::new("x", array.data_type().clone(), true)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(array)]).unwrap();
+    let err = count_value_struct_CountStruct_int64_eval(&input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid argument error: expect a struct for u32"
+    );
+}
+
+#[test]
+fn test_range() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(1), None, Some(3)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = range_int32_int32_eval(&input)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-----+-------+
+        | row | range |
+        +-----+-------+
+        | 0   | 0     |
+        | 2   | 0     |
+        | 2   | 1     |
+        | 2   | 2     |
+        +-----+-------+"#]],
+    );
+
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![1000000]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    // for large set, the output is split into multiple batches
+    let mut i = 0;
+    for output in range_int32_int32_eval(&input).unwrap() {
+        let output = output.unwrap();
+        let array = output
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        for x in array {
+            assert_eq!(x, Some(i));
+            i += 1;
+        }
+    }
+}
+
+#[test]
+fn test_json_array_elements() {
+    let schema = Schema::new(vec![json_field("d")]);
+    let arg0 = StringArray::from(vec![r#"[null,1,""]"#, "1"]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = json_array_elements_json_json_eval(&input)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-----+---------------------+-----------------------------------------------------+
+        | row | json_array_elements | error                                               |
+        +-----+---------------------+-----------------------------------------------------+
+        | 0   | null                |                                                     |
+        | 0   | 1                   |                                                     |
+        | 0   | ""                  |                                                     |
+        | 1   |                     | {row: 1, code: 0, message: not an array, details: } |
+        +-----+---------------------+-----------------------------------------------------+"#]],
+    );
+}
+
+#[test]
+fn test_aggregate() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(1), None, Some(3), Some(5)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let state = (sum_int32_int64_eval.create_state)().unwrap();
+    assert_eq!(&*state, &Int64Array::from(vec![None]));
+
+    let state = (sum_int32_int64_eval.accumulate)(&state, &input).unwrap();
+    assert_eq!(&*state, &Int64Array::from(vec![9]));
+
+    let ops = BooleanArray::from(vec![false, false, true, false]);
+    let accumulate_or_retract = sum_int32_int64_eval.accumulate_or_retract.unwrap();
+    let state = accumulate_or_retract(&state, &ops, &input).unwrap();
+    assert_eq!(&*state, &Int64Array::from(vec![12]));
+
+    let states = Int64Array::from(vec![Some(1), None, Some(3)]);
+    let state = (sum_int32_int64_eval.merge.unwrap())(&states).unwrap();
+    assert_eq!(&*state, &Int64Array::from(vec![4]));
+
+    let output = (sum_int32_int64_eval.finish)(&states).unwrap();
+    assert_eq!(&*output, &states);
+}
+
+#[test]
+fn test_aggregate_init_state() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(1), None, Some(3)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    assert!(count_int32_int64_eval.accumulate_or_retract.is_none());
+    assert!(count_int32_int64_eval.merge.is_none());
+
+    let state = (count_int32_int64_eval.create_state)().unwrap();
+    assert_eq!(&*state, &Int64Array::from(vec![0]));
+
+    let state = (count_int32_int64_eval.accumulate)(&state, &input).unwrap();
+    assert_eq!(&*state, &Int64Array::from(vec![2]));
+}
+
+#[test]
+fn test_aggregate_finish() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec![Some("hello"), None, Some("hi"), Some("world")]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let state = (min_len_string_int32_eval.create_state)().unwrap();
+    let state = (min_len_string_int32_eval.accumulate)(&state, &input).unwrap();
+    assert_eq!(&*state, &Int32Array::from(vec![2]));
+
+    let output = (min_len_string_int32_eval.finish)(&state).unwrap();
+    assert_eq!(&*output, &Int32Array::from(vec![2]));
+}
+
+#[test]
+fn test_pub() {
+    let schema = Schema::new(vec![Field::new("uint32", DataType::UInt32, true)]);
+    let arg0 = UInt32Array::from(vec![Some(1), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = maybe_visible_pub_udf(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+    +---------------+
+    | maybe_visible |
+    +---------------+
+    | 1             |
+    |               |
+    +---------------+"#]],
+    );
+}
+
+#[test]
+fn test_pub_crate() {
+    let schema = Schema::new(vec![Field::new("float32", DataType::Float32, true)]);
+    let arg0 = Float32Array::from(vec![Some(1.0), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = maybe_visible_pub_crate_udf(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+    +---------------+
+    | maybe_visible |
+    +---------------+
+    | 1.0           |
+    |               |
+    +---------------+"#]],
+    );
+}
+
+/// Returns a field with JSON type.
+fn json_field(name: &str) -> Field {
+    Field::new(name, DataType::Utf8, true).with_metadata(HashMap::from([(
+        "ARROW:extension:name".to_string(),
+        "arrowudf.json".to_string(),
+    )]))
+}
+
+#[tokio::test]
+async fn test_async_lookup() {
+    let schema = Schema::new(vec![Field::new("key", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(0), Some(1), None, Some(2), Some(3), Some(4)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = async_lookup_int32_string_eval(&input).await.unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +--------------+
+        | async_lookup |
+        +--------------+
+        | value-0      |
+        |              |
+        |              |
+        | value-2      |
+        |              |
+        | value-4      |
+        +--------------+"#]],
+    );
+
+    #[cfg(feature = "global_registry")]
+    {
+        let int32 = Field::new("", DataType::Int32, true);
+        let string = Field::new("", DataType::Utf8, true);
+        let sig = arrow_udf::sig::REGISTRY
+            .get("async_lookup", &[int32], &string)
+            .unwrap();
+        let function = sig.function.as_async_scalar().unwrap();
+        let output = function(&input).await.unwrap();
+        assert_eq!(output.num_rows(), 6);
+    }
+}
+
+#[tokio::test]
+async fn test_async_concurrency() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from((0..8).collect::<Vec<_>>());
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    // all rows are evaluated at the same time by default
+    async_track_int32_int32_eval(&input).await.unwrap();
+    assert_eq!(MAX_IN_FLIGHT.swap(0, Ordering::SeqCst), 8);
+
+    // the concurrency of the context overrides the default
+    let ctx = Context::new().with_concurrency(2);
+    ctx.scope(|| async_track_int32_int32_eval(&input))
+        .await
+        .unwrap();
+    assert_eq!(MAX_IN_FLIGHT.swap(0, Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_async_div() {
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Int32, true),
+        Field::new("y", DataType::Int32, true),
+    ]);
+    let arg0 = Int32Array::from(vec![Some(1), Some(-1), None]);
+    let arg1 = Int32Array::from(vec![Some(0), Some(-1), None]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = async_div_int32_int32_int32_eval(&input).await.unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-----------+---------------------------------------------------------+
+        | async_div | error                                                   |
+        +-----------+---------------------------------------------------------+
+        |           | {row: 0, code: 0, message: division by zero, details: } |
+        | 1         |                                                         |
+        |           |                                                         |
+        +-----------+---------------------------------------------------------+"#]],
+    );
+}
+
+#[test]
+fn test_clamp() {
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Int32, true),
+        Field::new("min", DataType::Int32, true),
+        Field::new("max", DataType::Int32, true),
+    ]);
+    // functions with more than 2 arguments are not evaluated on null slots
+    let arg0 = Int32Array::from(vec![Some(-1), Some(5), Some(20), None, None]);
+    let arg1 = Int32Array::from(vec![Some(0), Some(0), None, Some(0), Some(20)]);
+    let arg2 = Int32Array::from(vec![Some(10), Some(10), Some(10), Some(10), Some(10)]);
+    let input = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(arg0), Arc::new(arg1), Arc::new(arg2)],
+    )
+    .unwrap();
+
+    let output = clamp_int32_int32_int32_int32_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-------+
+        | clamp |
+        +-------+
+        | 0     |
+        | 5     |
+        |       |
+        |       |
+        |       |
+        +-------+"#]],
+    );
+}
+
+#[test]
+fn test_checked_add() {
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Int32, true),
+        Field::new("y", DataType::Int32, true),
+    ]);
+    let arg0 = Int32Array::from(vec![Some(1), Some(i32::MAX), None]);
+    let arg1 = Int32Array::from(vec![Some(2), Some(1), Some(1)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = checked_add_int32_int32_int32_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-------------+
+        | checked_add |
+        +-------------+
+        | 3           |
+        |             |
+        |             |
+        +-------------+"#]],
+    );
+}
+
+#[test]
+fn test_is_leap_year() {
+    let schema = Schema::new(vec![Field::new("date", DataType::Date32, true)]);
+    let arg0 = Date32Array::from(vec![
+        Some(Date32Type::from_naive_date(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        )),
+        Some(Date32Type::from_naive_date(
+            NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+        )),
+        None,
+    ]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = is_leap_year_date32_boolean_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +--------------+
+        | is_leap_year |
+        +--------------+
+        | true         |
+        | false        |
+        |              |
+        +--------------+"#]],
+    );
+}
+
+#[test]
+fn test_add_days() {
+    let schema = Schema::new(vec![
+        Field::new("t", DataType::Timestamp(TimeUnit::Microsecond, None), true),
+        Field::new("days", DataType::Int32, true),
+    ]);
+    let max = NaiveDate::MAX.and_hms_opt(0, 0, 0).unwrap();
+    let arg0 = TimestampMicrosecondArray::from(vec![
+        Some(0),
+        Some(max.and_utc().timestamp_micros()),
+        None,
+    ]);
+    let arg1 = Int32Array::from(vec![Some(1), Some(1), Some(1)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = add_days_timestamp_int32_timestamp_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +---------------------+---------------------------------------------------------------+
+        | add_days            | error                                                         |
+        +---------------------+---------------------------------------------------------------+
+        | 1970-01-02T00:00:00 |                                                               |
+        |                     | {row: 1, code: 0, message: timestamp out of range, details: } |
+        |                     |                                                               |
+        +---------------------+---------------------------------------------------------------+"#]],
+    );
+}
+
+#[test]
+fn test_zscore() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Float64, true)]);
+    let arg0 = Float64Array::from(vec![Some(1.0), Some(3.0), None, Some(5.0)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = zscore_float64_float64_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +--------------------+
+        | zscore             |
+        +--------------------+
+        | -1.224744871391589 |
+        | 0.0                |
+        |                    |
+        | 1.224744871391589  |
+        +--------------------+"#]],
+    );
+}
+
+#[test]
+fn test_prefix_sum() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int64, true)]);
+    let arg0 = Int64Array::from(vec![Some(1), Some(2), None, Some(3)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = prefix_sum_int64_int64_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +------------+
+        | prefix_sum |
+        +------------+
+        | 1          |
+        | 3          |
+        |            |
+        | 6          |
+        +------------+"#]],
+    );
+}
+
+#[test]
+fn test_concat_columns() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Utf8, true),
+        Field::new("b", DataType::Utf8, true),
+        Field::new("c", DataType::Utf8, true),
+    ]);
+    let arg0 = StringArray::from(vec![Some("a"), Some("b"), None]);
+    let arg1 = StringArray::from(vec![Some("1"), None, Some("3")]);
+    let arg2 = StringArray::from(vec![Some("x"), Some("y"), Some("z")]);
+    let input = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(arg0), Arc::new(arg1), Arc::new(arg2)],
+    )
+    .unwrap();
+
+    let output = concat_columns_string_variadic_string_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------------+-------+
+        | concat_columns | error |
+        +----------------+-------+
+        | a1x            |       |
+        |                |       |
+        |                |       |
+        +----------------+-------+"#]],
+    );
+
+    // an error fails all rows
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Utf8, true),
+        Field::new("b", DataType::Int32, true),
+    ]);
+    let arg0 = StringArray::from(vec![Some("a"), Some("b")]);
+    let arg1 = Int32Array::from(vec![Some(1), Some(2)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = concat_columns_string_variadic_string_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------------+--------------------------------------------------------------+
+        | concat_columns | error                                                        |
+        +----------------+--------------------------------------------------------------+
+        |                | {row: 0, code: 0, message: expect string columns, details: } |
+        |                | {row: 1, code: 0, message: expect string columns, details: } |
+        +----------------+--------------------------------------------------------------+"#]],
+    );
+
+    #[cfg(feature = "global_registry")]
+    {
+        let string = Field::new("", DataType::Utf8, true);
+        let sig = arrow_udf::sig::REGISTRY.get(
+            "concat_columns",
+            &[string.clone(), string.clone(), string.clone()],
+            &string,
+        );
+        assert!(sig.is_some());
+    }
+}
+
+#[test]
+fn test_array_first() {
+    let schema = Schema::new(vec![Field::new(
+        "x",
+        DataType::new_list(DataType::Int32, true),
+        true,
+    )]);
+    let arg0 = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1), Some(2)]),
+        Some(vec![]),
+        None,
+        Some(vec![None, Some(3)]),
+    ]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = array_first_anyarray_any_eval(&input).unwrap();
+    assert_eq!(output.schema().field(0).data_type(), &DataType::Int32);
+    check(
+        &[output],
+        expect![[r#"
+        +-------------+
+        | array_first |
+        +-------------+
+        | 1           |
+        |             |
+        |             |
+        |             |
+        +-------------+"#]],
+    );
+
+    let schema = Schema::new(vec![Field::new(
+        "x",
+        DataType::new_list(DataType::Utf8, true),
+        true,
+    )]);
+    let mut builder = builder::ListBuilder::new(builder::StringBuilder::new());
+    builder.append_value([Some("a"), Some("b")]);
+    builder.append_null();
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(builder.finish())]).unwrap();
+
+    let output = array_first_anyarray_any_eval(&input).unwrap();
+    assert_eq!(output.schema().field(0).data_type(), &DataType::Utf8);
+    check(
+        &[output],
+        expect![[r#"
+        +-------------+
+        | array_first |
+        +-------------+
+        | a           |
+        |             |
+        +-------------+"#]],
+    );
+}
+
+#[test]
+fn test_array_of() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(1), None, Some(3)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = array_of_any_anyarray_eval(&input).unwrap();
+    assert_eq!(
+        output.schema().field(0).data_type(),
+        &DataType::new_list(DataType::Int32, true)
+    );
+    check(
+        &[output],
+        expect![[r#"
+        +----------+
+        | array_of |
+        +----------+
+        | [1]      |
+        |          |
+        | [3]      |
+        +----------+"#]],
+    );
+}
+
+#[test]
+fn test_array_contains() {
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::new_list(DataType::Int32, true), true),
+        Field::new("y", DataType::Int32, true),
+    ]);
+    let arg0 = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1), Some(2)]),
+        Some(vec![Some(1), Some(2)]),
+        None,
+    ]);
+    let arg1 = Int32Array::from(vec![Some(2), Some(3), Some(1)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let output = array_contains_anyarray_any_boolean_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------------+
+        | array_contains |
+        +----------------+
+        | true           |
+        | false          |
+        |                |
+        +----------------+"#]],
+    );
+}
+
+#[test]
+fn test_type_infer() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(1), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let output = to_int64_any_any_eval(&input).unwrap();
+    assert_eq!(output.schema().field(0).data_type(), &DataType::Int64);
+    check(
+        &[output],
+        expect![[r#"
+        +----------+-------+
+        | to_int64 | error |
+        +----------+-------+
+        | 1        |       |
+        |          |       |
+        +----------+-------+"#]],
+    );
+}
+
+#[test]
+fn test_typed_variadic() {
+    let schema = Schema::new(vec![
+        Field::new("sep", DataType::Utf8, true),
+        Field::new("a", DataType::Utf8, true),
+        Field::new(
+            "b",
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            true,
+        ),
+    ]);
+    let arg0 = StringArray::from(vec![Some("-"), Some("-"), None]);
+    let arg1 = StringArray::from(vec![Some("x"), None, Some("z")]);
+    let arg2: DictionaryArray<Int32Type> =
+        vec![Some("1"), Some("2"), Some("3")].into_iter().collect();
+    let input = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(arg0), Arc::new(arg1), Arc::new(arg2)],
+    )
+    .unwrap();
+
+    // null in variadic arguments makes the row null
+    let output = concat_string_string_variadic_string_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +--------+
+        | concat |
+        +--------+
+        | -x1    |
+        |        |
+        |        |
+        +--------+"#]],
+    );
+
+    // unless they are received as `&[Option<T>]`
+    let output = concat_ws_string_string_variadic_string_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-----------+
+        | concat_ws |
+        +-----------+
+        | x-1       |
+        | 2         |
+        |           |
+        +-----------+"#]],
+    );
+
+    // variadic arguments must have the declared type
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Utf8, true),
+        Field::new("b", DataType::Int32, true),
+    ]);
+    let arg0 = StringArray::from(vec![Some("a")]);
+    let arg1 = Int32Array::from(vec![Some(1)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+    let err = concat_string_string_variadic_string_eval(&input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Cast error: expect StringArray for the 1-th argument"
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Timestamp(TimeUnit::Microsecond, None), true),
+        Field::new("b", DataType::Timestamp(TimeUnit::Microsecond, None), true),
+    ]);
+    let arg0 = TimestampMicrosecondArray::from(vec![Some(1_000_000), Some(0), None]);
+    let arg1 = TimestampMicrosecondArray::from(vec![Some(0), Some(2_000_000), Some(0)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+    let output = latest_timestamp_variadic_timestamp_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +---------------------+
+        | latest              |
+        +---------------------+
+        | 1970-01-01T00:00:01 |
+        | 1970-01-01T00:00:02 |
+        |                     |
+        +---------------------+"#]],
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int32, true),
+        Field::new("b", DataType::Int32, true),
+        Field::new("c", DataType::Int32, true),
+    ]);
+    let arg0 = Int32Array::from(vec![Some(1), None, Some(5)]);
+    let arg1 = Int32Array::from(vec![Some(3), Some(2), None]);
+    let arg2 = Int32Array::from(vec![Some(2), None, Some(4)]);
+    let input = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(arg0), Arc::new(arg1), Arc::new(arg2)],
+    )
+    .unwrap();
+    let output = greatest_int32_int32_variadic_int32_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------+
+        | greatest |
+        +----------+
+        | 3        |
+        | 2        |
+        | 5        |
+        +----------+"#]],
+    );
+}
+
+#[test]
+fn test_default_arguments() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Float64, true)]);
+    let arg0 = Float64Array::from(vec![Some(1.25), Some(-2.5), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    // the missing argument is filled with its default value
+    let output = round_to_float64_int32_float64_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------+
+        | round_to |
+        +----------+
+        | 1.0      |
+        | -3.0     |
+        |          |
+        +----------+"#]],
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Float64, true),
+        Field::new("digits", DataType::Int32, true),
+    ]);
+    let arg0 = Float64Array::from(vec![Some(1.25), Some(-2.5), Some(3.0)]);
+    let arg1 = Int32Array::from(vec![Some(1), Some(0), None]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+    let output = round_to_float64_int32_float64_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------+
+        | round_to |
+        +----------+
+        | 1.3      |
+        | -3.0     |
+        |          |
+        +----------+"#]],
+    );
+
+    let schema = Schema::new(vec![
+        Field::new("s", DataType::Utf8, true),
+        Field::new("width", DataType::Int32, true),
+    ]);
+    let arg0 = StringArray::from(vec!["abc", "abcdef"]);
+    let arg1 = Int32Array::from(vec![5, 5]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+    let output = lpad_string_int32_string_string_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +--------+
+        | lpad   |
+        +--------+
+        |   abc  |
+        | abcdef |
+        +--------+"#]],
+    );
+
+    // arguments without defaults are required
+    let input = input.project(&[0]).unwrap();
+    let err = lpad_string_int32_string_string_eval(&input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid argument error: expect at least 2 arguments, but got 1"
+    );
+}
+
+#[test]
+#[cfg(feature = "global_registry")]
+fn test_resolve_defaults() {
+    use arrow_udf::sig::REGISTRY;
+
+    let int32 = Field::new("", DataType::Int32, true);
+    let float64 = Field::new("", DataType::Float64, true);
+    let string = Field::new("", DataType::Utf8, true);
+
+    let (sig, _) = REGISTRY
+        .resolve("round_to", std::slice::from_ref(&float64))
+        .unwrap();
+    assert_eq!(sig.defaults, vec!["0"]);
+    assert_eq!(sig.min_args(), 1);
+    assert!(REGISTRY
+        .resolve("round_to", &[float64.clone(), int32.clone()])
+        .is_some());
+    assert!(REGISTRY.resolve("round_to", &[]).is_none());
+    assert!(REGISTRY
+        .resolve("lpad", std::slice::from_ref(&string))
+        .is_none());
+
+    // fill the default values before calling the function
+    let (sig, _) = REGISTRY
+        .resolve("lpad", &[string.clone(), int32.clone()])
+        .unwrap();
+    assert_eq!(sig.defaults, vec!["' '"]);
+    let schema = Schema::new(vec![
+        Field::new("s", DataType::Utf8, true),
+        Field::new("width", DataType::Int32, true),
+    ]);
+    let arg0 = StringArray::from(vec!["abc"]);
+    let arg1 = Int32Array::from(vec![4]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+    let input = sig.fill_defaults(&input).unwrap();
+    assert_eq!(input.num_columns(), 3);
+    assert_eq!(input.column(2).as_string::<i32>().value(0), " ");
+}
+
+#[test]
+#[cfg(feature = "global_registry")]
+fn test_named_arguments() {
+    use arrow_udf::sig::REGISTRY;
+
+    let int32 = Field::new("", DataType::Int32, true);
+    let float64 = Field::new("", DataType::Float64, true);
+    let string = Field::new("", DataType::Utf8, true);
+
+    // argument names are taken from the Rust function or `arg_names`
+    let (sig, _) = REGISTRY
+        .resolve("round_to", &[float64.clone(), int32.clone()])
+        .unwrap();
+    let names = sig.arg_types.iter().map(|f| f.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["x", "digits"]);
+    let (sig, _) = REGISTRY
+        .resolve("repeat_str", &[string.clone(), int32.clone()])
+        .unwrap();
+    let names = sig.arg_types.iter().map(|f| f.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["str", "count"]);
+
+    // arguments can be named in any order, and defaults can be omitted
+    let (sig, return_type) = REGISTRY
+        .resolve_named(
+            "round_to",
+            &[
+                int32.clone().with_name("digits"),
+                float64.clone().with_name("x"),
+            ],
+        )
+        .unwrap();
+    assert_eq!(return_type.data_type(), &DataType::Float64);
+    assert!(REGISTRY
+        .resolve_named("round_to", &[float64.clone().with_name("x")])
+        .is_some());
+    assert!(REGISTRY
+        .resolve_named("round_to", &[int32.clone().with_name("digits")])
+        .is_none());
+    assert!(REGISTRY
+        .resolve_named("round_to", &[float64.clone().with_name("y")])
+        .is_none());
+
+    let schema = Schema::new(vec![
+        Field::new("digits", DataType::Int32, true),
+        Field::new("x", DataType::Float64, true),
+    ]);
+    let arg0 = Int32Array::from(vec![Some(1), Some(2)]);
+    let arg1 = Float64Array::from(vec![Some(1.25), Some(5.4321)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+    let input = sig.bind_named_args(&input).unwrap();
+    let output = sig.function.as_scalar().unwrap()(&input).unwrap();
+    assert_eq!(input.schema().field(0).name(), "x");
+    check(
+        &[output],
+        expect![[r#"
+        +----------+
+        | round_to |
+        +----------+
+        | 1.3      |
+        | 5.43     |
+        +----------+"#]],
+    );
+
+    // omitted arguments are filled with their default values
+    let (sig, _) = REGISTRY
+        .resolve_named(
+            "lpad",
+            &[
+                int32.clone().with_name("width"),
+                string.clone().with_name("s"),
+            ],
+        )
+        .unwrap();
+    let schema = Schema::new(vec![
+        Field::new("width", DataType::Int32, true),
+        Field::new("s", DataType::Utf8, true),
+    ]);
+    let arg0 = Int32Array::from(vec![4]);
+    let arg1 = StringArray::from(vec!["ab"]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+    let input = sig.bind_named_args(&input).unwrap();
+    let output = sig.function.as_scalar().unwrap()(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +------+
+        | lpad |
+        +------+
+        |   ab |
+        +------+"#]],
+    );
+
+    let input = input.project(&[0]).unwrap();
+    let err = sig.bind_named_args(&input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid argument error: missing argument width"
+    );
+    let schema = Schema::new(vec![Field::new("t", DataType::Utf8, true)]);
+    let input = RecordBatch::new_empty(Arc::new(schema));
+    let err = sig.bind_named_args(&input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid argument error: function lpad has no argument named t"
+    );
+}
+
+#[test]
+#[cfg(feature = "global_registry")]
+fn test_resolve_variadic() {
+    use arrow_udf::sig::REGISTRY;
+
+    let int16 = Field::new("", DataType::Int16, true);
+    let int32 = Field::new("", DataType::Int32, true);
+    let string = Field::new("", DataType::Utf8, true);
+
+    let (sig, _) = REGISTRY
+        .resolve("concat", &[string.clone(), string.clone(), string.clone()])
+        .unwrap();
+    assert!(sig.variadic);
+    assert_eq!(
+        sig.variadic_type.as_ref().unwrap().data_type(),
+        &DataType::Utf8
+    );
+    assert!(sig.strict);
+    assert!(REGISTRY
+        .resolve("concat", std::slice::from_ref(&string))
+        .is_some());
+    assert!(REGISTRY
+        .resolve("concat", &[string.clone(), int32.clone()])
+        .is_none());
+
+    let (sig, _) = REGISTRY
+        .resolve("concat_ws", &[string.clone(), string.clone()])
+        .unwrap();
+    assert!(!sig.strict);
+
+    // untyped variadic arguments can be of any type
+    assert!(REGISTRY
+        .resolve("concat_columns", &[string.clone(), int32.clone()])
+        .is_some());
+
+    // typed variadic arguments can be coerced
+    let sig = REGISTRY
+        .resolve_with_coercion("greatest", &[int32.clone(), int16.clone(), int32.clone()])
+        .unwrap();
+    assert_eq!(sig.casts, vec![None, Some(DataType::Int32), None]);
+}
+
+#[test]
+#[cfg(feature = "global_registry")]
+fn test_resolve_polymorphic() {
+    use arrow_udf::sig::REGISTRY;
+
+    let int32 = Field::new("", DataType::Int32, true);
+    let string = Field::new("", DataType::Utf8, true);
+    let int32_list = Field::new("", DataType::new_list(DataType::Int32, true), true);
+
+    let (_, ret) = REGISTRY
+        .resolve("array_first", std::slice::from_ref(&int32_list))
+        .unwrap();
+    assert_eq!(ret.data_type(), &DataType::Int32);
+    assert!(REGISTRY
+        .get("array_first", std::slice::from_ref(&int32_list), &int32)
+        .is_some());
+    assert!(REGISTRY
+        .get("array_first", std::slice::from_ref(&int32_list), &string)
+        .is_none());
+    assert!(REGISTRY
+        .resolve("array_first", std::slice::from_ref(&int32))
+        .is_none());
+
+    let (_, ret) = REGISTRY
+        .resolve("array_of", std::slice::from_ref(&string))
+        .unwrap();
+    assert_eq!(ret.data_type(), &DataType::new_list(DataType::Utf8, true));
+
+    // `any` and the elements of `anyarray` must have the same type
+    assert!(REGISTRY
+        .resolve("array_contains", &[int32_list.clone(), int32.clone()])
+        .is_some());
+    assert!(REGISTRY
+        .resolve("array_contains", &[int32_list, string])
+        .is_none());
+
+    // non-polymorphic functions can also be resolved by argument types
+    let (_, ret) = REGISTRY
+        .resolve("gcd", &[int32.clone(), int32.clone()])
+        .unwrap();
+    assert_eq!(ret.data_type(), &DataType::Int32);
+}
+
+#[test]
+#[cfg(feature = "global_registry")]
+fn test_function_metadata() {
+    use arrow_udf::sig::{Volatility, REGISTRY};
+
+    let int32 = Field::new("", DataType::Int32, true);
+    let int64 = Field::new("", DataType::Int64, true);
+    let string = Field::new("", DataType::Utf8, true);
+
+    let gcd = REGISTRY
+        .get("gcd", &[int32.clone(), int32.clone()], &int32)
+        .unwrap();
+    assert_eq!(gcd.volatility, Volatility::Immutable);
+    assert!(gcd.is_deterministic());
+    assert!(gcd.strict);
+    assert_eq!(
+        gcd.description.as_deref(),
+        Some("Returns the greatest common divisor of two integers.\n\nReturns `a` if `b` is zero.")
+    );
+    assert_eq!(gcd.examples, ["gcd(15, 25) = 5", "gcd(7, 0) = 7"]);
+
+    // functions with `Option` arguments handle nulls themselves
+    let option_add = REGISTRY
+        .get("option_add", &[int32.clone(), int32.clone()], &int32)
+        .unwrap();
+    assert!(!option_add.strict);
+    assert_eq!(option_add.description, None);
+    assert!(option_add.examples.is_empty());
+
+    // functions reading the context are stable
+    let timezone = REGISTRY.get("timezone", &[], &string).unwrap();
+    assert_eq!(timezone.volatility, Volatility::Stable);
+    assert!(!timezone.is_deterministic());
+
+    let next_id = REGISTRY.get("next_id", &[], &int64).unwrap();
+    assert_eq!(next_id.volatility, Volatility::Volatile);
+
+    // vectorized functions receive null values
+    let float64 = Field::new("", DataType::Float64, true);
+    let zscore = REGISTRY
+        .get("zscore", std::slice::from_ref(&float64), &float64)
+        .unwrap();
+    assert!(!zscore.strict);
+}
+
+#[test]
+#[cfg(feature = "global_registry")]
+fn test_catalog() {
+    use arrow_udf::sig::{FunctionRegistry, REGISTRY};
+
+    let int32 = Field::new("", DataType::Int32, true);
+    let json = Field::new("", DataType::Utf8, true).with_metadata(HashMap::from([(
+        "ARROW:extension:name".to_string(),
+        "arrowudf.json".to_string(),
+    )]));
+    let mut registry = FunctionRegistry::new();
+    let to_json = REGISTRY
+        .get("to_json", std::slice::from_ref(&int32), &json)
+        .unwrap();
+    registry.register_in("ext", to_json.clone());
+    let array_first = REGISTRY
+        .iter()
+        .find(|sig| sig.name == "array_first")
+        .unwrap();
+    registry.register_in("ext", array_first.clone());
+
+    expect![[r#"
+        [
+          {
+            "arg_types": [
+              {
+                "metadata": {
+                  "ARROW:extension:name": "arrowudf.anyarray"
+                },
+                "name": "array",
+                "nullable": true,
+                "type": "anyarray"
+              }
+            ],
+            "defaults": [],
+            "description": null,
+            "examples": [],
+            "kind": "scalar",
+            "name": "array_first",
+            "return_type": {
+              "metadata": {
+                "ARROW:extension:name": "arrowudf.any"
+              },
+              "name": "array_first",
+              "nullable": true,
+              "type": "any"
+            },
+            "schema": "ext",
+            "strict": true,
+            "variadic": false,
+            "variadic_type": null,
+            "volatility": "immutable"
+          },
+          {
+            "arg_types": [
+              {
+                "metadata": {},
+                "name": "x",
+                "nullable": true,
+                "type": "int32"
+              }
+            ],
+            "defaults": [],
+            "description": null,
+            "examples": [],
+            "kind": "scalar",
+            "name": "to_json",
+            "return_type": {
+              "metadata": {
+                "ARROW:extension:name": "arrowudf.json"
+              },
+              "name": "to_json",
+              "nullable": true,
+              "type": "json"
+            },
+            "schema": "ext",
+            "strict": false,
+            "variadic": false,
+            "variadic_type": null,
+            "volatility": "immutable"
+          }
+        ]"#]]
+    .assert_eq(&serde_json::to_string_pretty(&registry.to_json()).unwrap());
+
+    let output = registry.to_record_batch().unwrap();
+    assert_eq!(output.num_rows(), 2);
+    check(
+        &[output],
+        expect![[r#"
+        +--------+-------------+--------+------------------------------------------------------------------------------------------------------+----------+---------------+----------+------------------------------------------------------------------------------------------------+------------+--------+-------------+----------+
+        | schema | name        | kind   | arg_types                                                                                            | variadic | variadic_type | defaults | return_type                                                                                    | volatility | strict | description | examples |
+        +--------+-------------+--------+------------------------------------------------------------------------------------------------------+----------+---------------+----------+------------------------------------------------------------------------------------------------+------------+--------+-------------+----------+
+        | ext    | array_first | scalar | [{name: array, type: anyarray, nullable: true, metadata: {ARROW:extension:name: arrowudf.anyarray}}] | false    |               | []       | {name: array_first, type: any, nullable: true, metadata: {ARROW:extension:name: arrowudf.any}} | immutable  | true   |             | []       |
+        | ext    | to_json     | scalar | [{name: x, type: int32, nullable: true, metadata: {}}]                                               | false    |               | []       | {name: to_json, type: json, nullable: true, metadata: {ARROW:extension:name: arrowudf.json}}   | immutable  | false  |             | []       |
+        +--------+-------------+--------+------------------------------------------------------------------------------------------------------+----------+---------------+----------+------------------------------------------------------------------------------------------------+------------+--------+-------------+----------+"#]],
+    );
+}
+
+#[test]
+#[cfg(feature = "global_registry")]
+fn test_resolve_with_coercion() {
+    use arrow_udf::sig::REGISTRY;
+
+    let field = |ty: DataType| Field::new("", ty, true);
+
+    // exact matches need no casts
+    let sig = REGISTRY
+        .resolve_with_coercion("neg", &[field(DataType::Int16)])
+        .unwrap();
+    assert!(!sig.needs_cast());
+    assert_eq!(sig.return_type.data_type(), &DataType::Int16);
+
+    // the narrowest wider integer is preferred
+    let sig = REGISTRY
+        .resolve_with_coercion("neg", &[field(DataType::UInt8)])
+        .unwrap();
+    assert_eq!(sig.casts, vec![Some(DataType::Int16)]);
+
+    // integers are cast to floats
+    let sig = REGISTRY
+        .resolve_with_coercion("zscore", &[field(DataType::Int32)])
+        .unwrap();
+    assert_eq!(sig.casts, vec![Some(DataType::Float64)]);
+
+    // decimal precision is widened, but scale is never reduced
+    let sig = REGISTRY
+        .resolve_with_coercion(
+            "add",
+            &[
+                field(DataType::Decimal128(8, 2)),
+                field(DataType::Decimal128(10, 2)),
+            ],
+        )
+        .unwrap();
+    assert_eq!(sig.casts, vec![Some(DataType::Decimal128(10, 2)), None]);
+    assert!(REGISTRY
+        .resolve_with_coercion(
+            "add",
+            &[
+                field(DataType::Decimal128(10, 3)),
+                field(DataType::Decimal128(10, 2)),
+            ],
+        )
+        .is_none());
+
+    // narrowing casts are not allowed
+    assert!(REGISTRY
+        .resolve_with_coercion("gcd", &[field(DataType::Int64), field(DataType::Int32)])
+        .is_none());
+
+    let sig = REGISTRY
+        .resolve_with_coercion("gcd", &[field(DataType::Int16), field(DataType::Int32)])
+        .unwrap();
+    assert_eq!(sig.casts, vec![Some(DataType::Int32), None]);
+    assert_eq!(sig.return_type.data_type(), &DataType::Int32);
+
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Int16, true),
+        Field::new("y", DataType::Int32, true),
+    ]);
+    let arg0 = Int16Array::from(vec![Some(15), None]);
+    let arg1 = Int32Array::from(vec![Some(25), Some(1)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+    let input = sig.cast_input(&input).unwrap();
+    assert_eq!(input.schema().field(0).name(), "x");
+    assert_eq!(input.schema().field(0).data_type(), &DataType::Int32);
+    let output = sig.signature.function.as_scalar().unwrap()(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +-----+
+        | gcd |
+        +-----+
+        | 5   |
+        |     |
+        +-----+"#]],
+    );
+}
+
+#[test]
+fn test_runtime_registry() {
+    use arrow_udf::sig::{FunctionKind, FunctionRegistry, FunctionSignature, Volatility};
+
+    let int32 = Field::new("", DataType::Int32, true);
+    let int64 = Field::new("", DataType::Int64, true);
+    let offset = 10;
+    let add_offset = move |input: &RecordBatch| {
+        let array = input.column(0).as_primitive::<Int32Type>();
+        let output = arrow_arith::numeric::add(array, &Int32Array::new_scalar(offset))?;
+        let schema = Schema::new(vec![Field::new("add_offset", DataType::Int32, true)]);
+        RecordBatch::try_new(Arc::new(schema), vec![output])
+    };
+    let sig = |name: &str| FunctionSignature {
+        name: name.into(),
+        arg_types: vec![int32.clone()].into(),
+        variadic: false,
+        variadic_type: None,
+        defaults: vec![],
+        return_type: int32.clone(),
+        type_infer: None,
+        function: FunctionKind::DynScalar(Arc::new(add_offset)),
+        volatility: Volatility::Immutable,
+        strict: true,
+        description: None,
+        examples: vec![],
+    };
+
+    let mut registry = FunctionRegistry::new();
+    assert!(registry.register(sig("add_offset")).is_none());
+    assert!(registry.register_in("ext", sig("add_offset")).is_none());
+    // a function with the same name and types is replaced
+    assert!(registry.register_in("ext", sig("add_offset")).is_some());
+    assert_eq!(registry.iter().count(), 2);
+    assert_eq!(registry.iter_schema("ext").count(), 1);
+    assert_eq!(registry.iter_schema("ex").count(), 0);
+
+    let found = registry
+        .get("ext.add_offset", std::slice::from_ref(&int32), &int32)
+        .unwrap();
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(1), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+    let output = found.function.as_dyn_scalar().unwrap()(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +------------+
+        | add_offset |
+        +------------+
+        | 11         |
+        |            |
+        +------------+"#]],
+    );
+
+    assert!(registry
+        .get("add_offset", std::slice::from_ref(&int32), &int64)
+        .is_none());
+    let other_return_type = FunctionSignature {
+        return_type: int64.clone(),
+        ..sig("add_offset")
+    };
+    assert!(registry
+        .unregister("ext.add_offset", &other_return_type)
+        .is_none());
+    assert!(registry
+        .unregister("ext.add_offset", &sig("add_offset"))
+        .is_some());
+    assert!(registry
+        .get("ext.add_offset", std::slice::from_ref(&int32), &int32)
+        .is_none());
+    assert!(registry
+        .get("add_offset", std::slice::from_ref(&int32), &int32)
+        .is_some());
+
+    // merge with the functions defined by `#[function]`
+    #[cfg(feature = "global_registry")]
+    {
+        let mut merged = arrow_udf::sig::REGISTRY.clone();
+        merged.merge(&registry);
+        assert!(merged
+            .get("add_offset", std::slice::from_ref(&int32), &int32)
+            .is_some());
+        let sig = merged
+            .get("gcd", &[int32.clone(), int32.clone()], &int32)
+            .unwrap();
+        assert!(sig.function.as_dyn_scalar().is_some());
+    }
+
+    // signatures differing only in variadic arguments are distinct
+    let variadic = FunctionSignature {
+        variadic: true,
+        variadic_type: Some(int32.clone()),
+        ..sig("add_offset")
+    };
+    assert!(registry.register(variadic.clone()).is_none());
+    assert_eq!(registry.iter().count(), 2);
+    let removed = registry
+        .unregister("add_offset", &sig("add_offset"))
+        .unwrap();
+    assert!(!removed.variadic);
+    let removed = registry.unregister("add_offset", &sig("add_offset"));
+    assert!(removed.is_none());
+    let removed = registry.unregister("add_offset", &variadic).unwrap();
+    assert!(removed.variadic);
+    assert_eq!(registry.iter().count(), 0);
+}
+
+#[test]
+fn test_context() {
+    let input = RecordBatch::try_new_with_options(
+        Arc::new(Schema::empty()),
+        vec![],
+        &RecordBatchOptions::default().with_row_count(Some(1)),
+    )
+    .unwrap();
+
+    let output = timezone_string_eval(&input).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +----------+
+        | timezone |
+        +----------+
+        | UTC      |
+        +----------+"#]],
+    );
+    // the empty context outside a scope is shared
+    assert!(Arc::ptr_eq(&Context::current(), &Context::current()));
+
+    let ctx = Context::new().with_timezone("Asia/Shanghai");
+    let output = ctx.scope(|| timezone_string_eval(&input)).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +---------------+
+        | timezone      |
+        +---------------+
+        | Asia/Shanghai |
+        +---------------+"#]],
+    );
+}
+
+#[test]
+fn test_context_config() {
+    let schema = Schema::new(vec![Field::new("key", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec![Some("locale"), Some("unknown"), None]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let ctx = Context::new().with_config("locale", "zh_CN");
+    let output = ctx.scope(|| get_config_string_string_eval(&input)).unwrap();
+    check(
+        &[output],
+        expect![[r#"
+        +------------+
+        | get_config |
+        +------------+
+        | zh_CN      |
+        |            |
+        |            |
+        +------------+"#]],
+    );
+}
+
+#[test]
+fn test_context_deadline() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, true)]);
+    let arg0 = Int32Array::from(vec![Some(1)]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+
+    let ctx = Context::new().with_deadline(std::time::Instant::now());
+    let err = ctx.scope(|| neg_int32_int32_eval(&input)).unwrap_err();
+    assert_eq!(err.to_string(), "Compute error: deadline exceeded");
+
+    let ctx = Context::new().with_timeout(std::time::Duration::from_secs(60));
+    ctx.scope(|| neg_int32_int32_eval(&input)).unwrap();
+}
+
+#[test]
+fn test_error_policy() {
+    let schema = Schema::new(vec![
+        Field::new("x", DataType::Int32, true),
+        Field::new("y", DataType::Int32, true),
+    ]);
+    let arg0 = Int32Array::from(vec![Some(1), Some(-1)]);
+    let arg1 = Int32Array::from(vec![Some(1), Some(0)]);
+    let input =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0), Arc::new(arg1)]).unwrap();
+
+    let ctx = Context::new().with_error_policy(ErrorPolicy::FailFast);
+    let err = ctx
+        .scope(|| div_int32_int32_int32_eval(&input))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Compute error: division by zero (code 0, row 1)"
+    );
+
+    let schema = Schema::new(vec![Field::new("x", DataType::Utf8, true)]);
+    let arg0 = StringArray::from(vec![Some("1"), Some("a")]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+    let err = ctx
+        .scope(|| parse_int_string_int32_eval(&input))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"Compute error: invalid digit (code 2, row 1): input: "a""#
+    );
+
+    // the error column is still present if no error occurs
+    let arg0 = StringArray::from(vec![Some("1"), None]);
+    let input = RecordBatch::try_new(input.schema(), vec![Arc::new(arg0)]).unwrap();
+    let output = ctx.scope(|| parse_int_string_int32_eval(&input)).unwrap();
+    assert_eq!(output.num_columns(), 2);
+    assert_eq!(output.column(1).null_count(), 2);
+
+    // table functions yield the error and end the iteration
+    let schema = Schema::new(vec![json_field("d")]);
+    let arg0 = StringArray::from(vec![Some("1"), Some("[2]")]);
+    let input = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(arg0)]).unwrap();
+    let outputs = ctx.scope(|| {
+        json_array_elements_json_json_eval(&input)
+            .unwrap()
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(
+        outputs[0].as_ref().unwrap_err().to_string(),
+        "Compute error: not an array (code 0, row 0)"
+    );
 }
 
 /// Returns a field with decimal type.
 fn decimal_field(name: &str) -> Field {
-    Field::new(name, DataType::Utf8, true)
-        .with_metadata([("ARROW:extension:name".into(), "arrowudf.decimal".into())].into())
+    Field::new(name, DataType::Utf8, true).with_metadata(HashMap::from([(
+        "ARROW:extension:name".to_string(),
+        "arrowudf.decimal".to_string(),
+    )]))
 }